@@ -7,7 +7,7 @@
 
 use std::sync::Arc;
 
-use humidefi_runtime::{opaque::Block, AccountId, Balance, Nonce};
+use humidefi_runtime::{opaque::Block, AccountId, Balance, HumidefiAssetId, Nonce};
 
 use sc_client_api::AuxStore;
 // pub use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
@@ -44,9 +44,11 @@ where
 		+ 'static,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
+	C::Api: pallet_humidefi_rpc::HumidefiRuntimeApi<Block, AccountId, HumidefiAssetId, u32, Balance>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + Sync + Send + 'static,
 {
+	use pallet_humidefi_rpc::{Humidefi, HumidefiApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
@@ -54,6 +56,7 @@ where
 	let FullDeps { client, pool, deny_unsafe } = deps;
 
 	module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
-	module.merge(TransactionPayment::new(client).into_rpc())?;
+	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	module.merge(Humidefi::new(client).into_rpc())?;
 	Ok(module)
 }
@@ -0,0 +1,72 @@
+//! Runtime API definition for the humidefi pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{FixedU128, RuntimeDebug};
+use sp_std::vec::Vec;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// Reserves and LP token supply of a liquidity pool.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PoolInfo<AssetId, LpToken, Balance> {
+	pub asset_x: AssetId,
+	pub asset_y: AssetId,
+	pub asset_x_balance: Balance,
+	pub asset_y_balance: Balance,
+	pub price: FixedU128,
+	pub lp_token: LpToken,
+	pub lp_token_supply: Balance,
+}
+
+/// A liquidity position recorded for an account.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PositionInfo<AssetId, LpToken, Balance> {
+	pub id: u64,
+	pub asset_x: AssetId,
+	pub asset_y: AssetId,
+	pub asset_x_balance: Balance,
+	pub asset_y_balance: Balance,
+	pub lp_token: LpToken,
+	pub lp_token_balance: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	pub trait HumidefiApi<AccountId, AssetId, LpToken, Balance>
+	where
+		AccountId: Codec,
+		AssetId: Codec,
+		LpToken: Codec,
+		Balance: Codec,
+	{
+		/// Amount received at the end of `path` when selling exactly `amount_in`.
+		fn quote_exact_in(path: Vec<AssetId>, amount_in: Balance) -> Option<Balance>;
+
+		/// Amount to pay at the start of `path` in order to buy exactly `amount_out`.
+		fn quote_exact_out(path: Vec<AssetId>, amount_out: Balance) -> Option<Balance>;
+
+		/// LP tokens minted when depositing `amount_x` and `amount_y` into the pool of the pair.
+		fn quote_add_liquidity(
+			asset_x: AssetId,
+			asset_y: AssetId,
+			amount_x: Balance,
+			amount_y: Balance,
+		) -> Option<Balance>;
+
+		/// At most `limit` pools, skipping the first `offset`.
+		fn list_pools(offset: u32, limit: u32) -> Vec<PoolInfo<AssetId, LpToken, Balance>>;
+
+		/// Every liquidity position held by `account`, across all pairs.
+		fn account_positions(account: AccountId) -> Vec<PositionInfo<AssetId, LpToken, Balance>>;
+
+		/// Time-weighted average price of `asset_a` in `asset_b` over the last `window` blocks.
+		fn twap(asset_a: AssetId, asset_b: AssetId, window: u32) -> Option<FixedU128>;
+	}
+}
@@ -0,0 +1,197 @@
+//! RPC interface for the humidefi pallet.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{traits::Block as BlockT, FixedU128};
+
+pub use pallet_humidefi_rpc_runtime_api::{HumidefiApi as HumidefiRuntimeApi, PoolInfo, PositionInfo};
+
+#[rpc(client, server)]
+pub trait HumidefiApi<BlockHash, AccountId, AssetId, LpToken, Balance> {
+	#[method(name = "humidefi_quoteExactIn")]
+	fn quote_exact_in(
+		&self,
+		path: Vec<AssetId>,
+		amount_in: Balance,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<Balance>>;
+
+	#[method(name = "humidefi_quoteExactOut")]
+	fn quote_exact_out(
+		&self,
+		path: Vec<AssetId>,
+		amount_out: Balance,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<Balance>>;
+
+	#[method(name = "humidefi_quoteAddLiquidity")]
+	fn quote_add_liquidity(
+		&self,
+		asset_x: AssetId,
+		asset_y: AssetId,
+		amount_x: Balance,
+		amount_y: Balance,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<Balance>>;
+
+	#[method(name = "humidefi_listPools")]
+	fn list_pools(
+		&self,
+		offset: u32,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<PoolInfo<AssetId, LpToken, Balance>>>;
+
+	#[method(name = "humidefi_accountPositions")]
+	fn account_positions(
+		&self,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<PositionInfo<AssetId, LpToken, Balance>>>;
+
+	#[method(name = "humidefi_twap")]
+	fn twap(
+		&self,
+		asset_a: AssetId,
+		asset_b: AssetId,
+		window: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<FixedU128>>;
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+/// Provides RPC methods to query humidefi quotes.
+pub struct Humidefi<C, Block> {
+	client: Arc<C>,
+	_marker: PhantomData<Block>,
+}
+
+impl<C, Block> Humidefi<C, Block> {
+	/// Creates a new instance of the Humidefi RPC helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+fn runtime_error(message: &'static str, e: impl std::fmt::Debug) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(
+		Error::RuntimeError.into(),
+		message,
+		Some(format!("{:?}", e)),
+	))
+	.into()
+}
+
+impl<C, Block, AccountId, AssetId, LpToken, Balance>
+	HumidefiApiServer<<Block as BlockT>::Hash, AccountId, AssetId, LpToken, Balance> for Humidefi<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: HumidefiRuntimeApi<Block, AccountId, AssetId, LpToken, Balance>,
+	AccountId: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	AssetId: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	LpToken: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	Balance: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+	fn quote_exact_in(
+		&self,
+		path: Vec<AssetId>,
+		amount_in: Balance,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<Balance>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.quote_exact_in(at_hash, path, amount_in)
+			.map_err(|e| runtime_error("Unable to quote exact in swap.", e))
+	}
+
+	fn quote_exact_out(
+		&self,
+		path: Vec<AssetId>,
+		amount_out: Balance,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<Balance>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.quote_exact_out(at_hash, path, amount_out)
+			.map_err(|e| runtime_error("Unable to quote exact out swap.", e))
+	}
+
+	fn quote_add_liquidity(
+		&self,
+		asset_x: AssetId,
+		asset_y: AssetId,
+		amount_x: Balance,
+		amount_y: Balance,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<Balance>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.quote_add_liquidity(at_hash, asset_x, asset_y, amount_x, amount_y)
+			.map_err(|e| runtime_error("Unable to quote add liquidity.", e))
+	}
+
+	fn list_pools(
+		&self,
+		offset: u32,
+		limit: u32,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<PoolInfo<AssetId, LpToken, Balance>>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.list_pools(at_hash, offset, limit)
+			.map_err(|e| runtime_error("Unable to list pools.", e))
+	}
+
+	fn account_positions(
+		&self,
+		account: AccountId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<PositionInfo<AssetId, LpToken, Balance>>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.account_positions(at_hash, account)
+			.map_err(|e| runtime_error("Unable to query account positions.", e))
+	}
+
+	fn twap(
+		&self,
+		asset_a: AssetId,
+		asset_b: AssetId,
+		window: u32,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<FixedU128>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.twap(at_hash, asset_a, asset_b, window)
+			.map_err(|e| runtime_error("Unable to query time-weighted average price.", e))
+	}
+}
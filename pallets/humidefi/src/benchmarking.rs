@@ -0,0 +1,567 @@
+//! Benchmarking setup for pallet-humidefi
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Humidefi;
+use frame_benchmarking::v2::*;
+use frame_support::{
+	assert_ok,
+	sp_runtime::{FixedPointNumber, FixedU128, Perbill},
+	sp_std::vec::Vec,
+	traits::{fungibles, EnsureOrigin, Get},
+	weights::Weight,
+	BoundedVec,
+};
+use frame_system::RawOrigin;
+use interfaces::humidefi::HumidefiCaller;
+
+const LIQUIDITY: u128 = 1_000_000_000_000_000_000_000;
+const SWAP_AMOUNT: u128 = 1_000_000_000_000_000_000;
+const FIRST_ASSET: u32 = 1_000;
+
+fn create_asset<T: Config>(asset: AssetIdOf<T>, who: &T::AccountId) {
+	let admin: T::AccountId = account("admin", 0, 0);
+
+	assert_ok!(<T::Fungibles as fungibles::Create<_>>::create(asset, admin, true, 1u128));
+	assert_ok!(<T::Fungibles as fungibles::Mutate<_>>::mint_into(asset, who, LIQUIDITY * 1_000));
+}
+
+fn create_liquidity_pool<T: Config>(
+	who: &T::AccountId,
+	asset_x: AssetIdOf<T>,
+	asset_y: AssetIdOf<T>,
+) -> AssetPairs<T> {
+	let asset_pair = AssetPairs::<T>::new(NativeOrAssetId::Asset(asset_x), NativeOrAssetId::Asset(asset_y));
+
+	assert_ok!(<Humidefi<T> as HumidefiCaller>::new_liquidity(
+		who.clone(),
+		asset_pair.clone(),
+		LIQUIDITY,
+		LIQUIDITY,
+		0,
+	));
+
+	asset_pair
+}
+
+fn create_concentrated_pool<T: Config>(who: &T::AccountId) -> AssetPairs<T> {
+	create_asset::<T>(FIRST_ASSET, who);
+	create_asset::<T>(FIRST_ASSET + 1, who);
+
+	let asset_pair = AssetPairs::<T>::new(
+		NativeOrAssetId::Asset(FIRST_ASSET),
+		NativeOrAssetId::Asset(FIRST_ASSET + 1),
+	);
+
+	assert_ok!(<Humidefi<T> as HumidefiCaller>::create_pool(
+		who.clone(),
+		asset_pair.clone(),
+		PoolKind::Concentrated { tick_spacing: 10 },
+		LIQUIDITY,
+		LIQUIDITY,
+	));
+
+	asset_pair
+}
+
+/// Places a limit order selling `SWAP_AMOUNT` of the first asset that the pool can fill right away.
+fn create_limit_order<T: Config>(who: &T::AccountId) -> LimitOrderId {
+	create_asset::<T>(FIRST_ASSET, who);
+	create_asset::<T>(FIRST_ASSET + 1, who);
+	create_liquidity_pool::<T>(who, FIRST_ASSET, FIRST_ASSET + 1);
+
+	let expiry = frame_system::Pallet::<T>::block_number() + 10u32.into();
+	let placed = <Humidefi<T> as HumidefiCaller>::place_limit_order(
+		who.clone(),
+		NativeOrAssetId::Asset(FIRST_ASSET),
+		SWAP_AMOUNT,
+		NativeOrAssetId::Asset(FIRST_ASSET + 1),
+		FixedU128::from_rational(1, 2),
+		expiry,
+	);
+	assert_ok!(placed);
+
+	placed.unwrap_or_default()
+}
+
+/// Schedules ten runs swapping `SWAP_AMOUNT` of the first asset, the first of them due in the next block.
+fn create_dca_schedule<T: Config>(who: &T::AccountId) -> DcaScheduleId {
+	create_asset::<T>(FIRST_ASSET, who);
+	create_asset::<T>(FIRST_ASSET + 1, who);
+	create_liquidity_pool::<T>(who, FIRST_ASSET, FIRST_ASSET + 1);
+
+	let scheduled = <Humidefi<T> as HumidefiCaller>::schedule_dca(
+		who.clone(),
+		NativeOrAssetId::Asset(FIRST_ASSET),
+		NativeOrAssetId::Asset(FIRST_ASSET + 1),
+		SWAP_AMOUNT,
+		Perbill::from_percent(5),
+		10u32.into(),
+		10,
+	);
+	assert_ok!(scheduled);
+
+	scheduled.unwrap_or_default()
+}
+
+/// Creates `n` assets chained by `n - 1` pools and returns the path through them.
+fn create_path<T: Config>(who: &T::AccountId, n: u32) -> Vec<NativeOrAssetIdOf<T>> {
+	for index in 0..n {
+		create_asset::<T>(FIRST_ASSET + index, who);
+	}
+
+	for index in 1..n {
+		create_liquidity_pool::<T>(who, FIRST_ASSET + index - 1, FIRST_ASSET + index);
+	}
+
+	(0..n).map(|index| NativeOrAssetId::Asset(FIRST_ASSET + index)).collect()
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn new_liquidity() -> Result<(), BenchmarkError> {
+		let origin = T::PoolCreateOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let caller = T::PoolCreateOrigin::ensure_origin(origin.clone()).map_err(|_| BenchmarkError::Weightless)?;
+		create_asset::<T>(FIRST_ASSET, &caller);
+		create_asset::<T>(FIRST_ASSET + 1, &caller);
+
+		let asset_pair = AssetPairs::<T>::new(
+			NativeOrAssetId::Asset(FIRST_ASSET),
+			NativeOrAssetId::Asset(FIRST_ASSET + 1),
+		);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, asset_pair.clone(), LIQUIDITY, LIQUIDITY, 0);
+
+		assert!(LiquidityPoolStorage::<T>::contains_key(asset_pair));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn redeem_liquidity() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_asset::<T>(FIRST_ASSET, &caller);
+		create_asset::<T>(FIRST_ASSET + 1, &caller);
+
+		let asset_pair = create_liquidity_pool::<T>(&caller, FIRST_ASSET, FIRST_ASSET + 1);
+		let lp_token = LiquidityPoolStorage::<T>::get(asset_pair.clone()).unwrap().lp_token;
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), asset_pair.clone(), lp_token, 1);
+
+		assert!(!AccountLiquidityPoolStorage::<T>::contains_key((caller, asset_pair)));
+	}
+
+	#[benchmark]
+	fn redeem_liquidity_partial() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_asset::<T>(FIRST_ASSET, &caller);
+		create_asset::<T>(FIRST_ASSET + 1, &caller);
+
+		let asset_pair = create_liquidity_pool::<T>(&caller, FIRST_ASSET, FIRST_ASSET + 1);
+		let lp_token = LiquidityPoolStorage::<T>::get(asset_pair.clone()).unwrap().lp_token;
+
+		#[extrinsic_call]
+		_(
+			RawOrigin::Signed(caller.clone()),
+			asset_pair.clone(),
+			lp_token,
+			1,
+			RedeemLiquidityAmount::Portion(Perbill::from_percent(50)),
+		);
+
+		assert!(AccountLiquidityPoolStorage::<T>::contains_key((caller, asset_pair)));
+	}
+
+	#[benchmark]
+	fn redeem_lp_token() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_asset::<T>(FIRST_ASSET, &caller);
+		create_asset::<T>(FIRST_ASSET + 1, &caller);
+
+		let asset_pair = create_liquidity_pool::<T>(&caller, FIRST_ASSET, FIRST_ASSET + 1);
+		let lp_token_balance = LiquidityPoolStorage::<T>::get(asset_pair.clone()).unwrap().lp_token_balance;
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), asset_pair.clone(), lp_token_balance.into_inner() / 2);
+
+		assert!(AccountLiquidityPoolStorage::<T>::contains_key((caller, asset_pair)));
+	}
+
+	#[benchmark]
+	fn convert_merged_lp_token() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_asset::<T>(FIRST_ASSET, &caller);
+		create_asset::<T>(FIRST_ASSET + 1, &caller);
+
+		let asset_pair = create_liquidity_pool::<T>(&caller, FIRST_ASSET, FIRST_ASSET + 1);
+		let lp_token = LiquidityPoolStorage::<T>::get(asset_pair.clone()).unwrap().lp_token;
+
+		// A legacy LP token held by the caller, converting one to one.
+		let legacy_lp_token = FIRST_ASSET + 2;
+		create_asset::<T>(legacy_lp_token, &caller);
+		assert_ok!(<T::Fungibles as fungibles::Mutate<_>>::mint_into(
+			lp_token,
+			&Humidefi::<T>::get_dex_account(),
+			LIQUIDITY,
+		));
+		MergedLpTokens::<T>::insert(legacy_lp_token, (asset_pair, FixedU128::from_u32(1)));
+
+		let lp_token_balance = <T::Fungibles as fungibles::Inspect<_>>::balance(lp_token, &caller);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), legacy_lp_token, LIQUIDITY);
+
+		assert_eq!(
+			<T::Fungibles as fungibles::Inspect<_>>::balance(lp_token, &caller),
+			lp_token_balance + LIQUIDITY
+		);
+	}
+
+	#[benchmark]
+	fn swap_exact_in_for_out() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_asset::<T>(FIRST_ASSET, &caller);
+		create_asset::<T>(FIRST_ASSET + 1, &caller);
+		create_liquidity_pool::<T>(&caller, FIRST_ASSET, FIRST_ASSET + 1);
+
+		#[extrinsic_call]
+		_(
+			RawOrigin::Signed(caller),
+			NativeOrAssetId::Asset(FIRST_ASSET),
+			SWAP_AMOUNT,
+			NativeOrAssetId::Asset(FIRST_ASSET + 1),
+			0,
+			None,
+		);
+	}
+
+	#[benchmark]
+	fn swap_in_for_exact_out() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_asset::<T>(FIRST_ASSET, &caller);
+		create_asset::<T>(FIRST_ASSET + 1, &caller);
+		create_liquidity_pool::<T>(&caller, FIRST_ASSET, FIRST_ASSET + 1);
+
+		#[extrinsic_call]
+		_(
+			RawOrigin::Signed(caller),
+			NativeOrAssetId::Asset(FIRST_ASSET + 1),
+			SWAP_AMOUNT,
+			NativeOrAssetId::Asset(FIRST_ASSET),
+			u128::MAX,
+			None,
+		);
+	}
+
+	#[benchmark]
+	fn swap_exact_in_for_out_path(n: Linear<2, { T::MaxSwapPathLength::get() }>) {
+		let caller: T::AccountId = whitelisted_caller();
+		let path: BoundedVec<_, T::MaxSwapPathLength> = create_path::<T>(&caller, n).try_into().unwrap();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), path, SWAP_AMOUNT, 0, None);
+	}
+
+	#[benchmark]
+	fn swap_in_for_exact_out_path(n: Linear<2, { T::MaxSwapPathLength::get() }>) {
+		let caller: T::AccountId = whitelisted_caller();
+		let path: BoundedVec<_, T::MaxSwapPathLength> = create_path::<T>(&caller, n).try_into().unwrap();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), path, SWAP_AMOUNT, u128::MAX, None);
+	}
+
+	#[benchmark]
+	fn transfer_asset() {
+		let caller: T::AccountId = whitelisted_caller();
+		let recipient: T::AccountId = account("recipient", 0, 0);
+		create_asset::<T>(FIRST_ASSET, &caller);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), FIRST_ASSET, SWAP_AMOUNT, recipient.clone());
+
+		assert_eq!(
+			<T::Fungibles as fungibles::Inspect<_>>::balance(FIRST_ASSET, &recipient),
+			SWAP_AMOUNT
+		);
+	}
+
+	#[benchmark]
+	fn set_swap_fee() -> Result<(), BenchmarkError> {
+		let caller: T::AccountId = whitelisted_caller();
+		create_asset::<T>(FIRST_ASSET, &caller);
+		create_asset::<T>(FIRST_ASSET + 1, &caller);
+
+		let asset_pair = create_liquidity_pool::<T>(&caller, FIRST_ASSET, FIRST_ASSET + 1);
+		let swap_fee = Perbill::from_percent(1);
+
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, asset_pair.clone(), swap_fee);
+
+		assert_eq!(LiquidityPoolStorage::<T>::get(asset_pair).unwrap().swap_fee, swap_fee);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_listing_mode() -> Result<(), BenchmarkError> {
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, ListingMode::AllowList);
+
+		assert_eq!(AssetListingMode::<T>::get(), ListingMode::AllowList);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_asset_listing() -> Result<(), BenchmarkError> {
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let asset = NativeOrAssetId::Asset(FIRST_ASSET);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, asset, Some(AssetListingStatus::Allowed));
+
+		assert_eq!(AssetListing::<T>::get(asset), Some(AssetListingStatus::Allowed));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn pause_pool() -> Result<(), BenchmarkError> {
+		let caller: T::AccountId = whitelisted_caller();
+		create_asset::<T>(FIRST_ASSET, &caller);
+		create_asset::<T>(FIRST_ASSET + 1, &caller);
+
+		let asset_pair = create_liquidity_pool::<T>(&caller, FIRST_ASSET, FIRST_ASSET + 1);
+		let origin = T::PauseOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, asset_pair.clone(), true);
+
+		assert_eq!(LiquidityPoolStorage::<T>::get(asset_pair).unwrap().status, PoolStatus::SwapsPaused);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn pause_all() -> Result<(), BenchmarkError> {
+		let origin = T::PauseOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, false);
+
+		assert_eq!(GlobalPoolStatus::<T>::get(), PoolStatus::Paused);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn unpause() -> Result<(), BenchmarkError> {
+		let caller: T::AccountId = whitelisted_caller();
+		create_asset::<T>(FIRST_ASSET, &caller);
+		create_asset::<T>(FIRST_ASSET + 1, &caller);
+
+		let asset_pair = create_liquidity_pool::<T>(&caller, FIRST_ASSET, FIRST_ASSET + 1);
+		LiquidityPoolStorage::<T>::mutate(asset_pair.clone(), |query| {
+			if let Some(liquidity_pool) = query {
+				liquidity_pool.status = PoolStatus::Paused;
+			}
+		});
+
+		let origin = T::PauseOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, Some(asset_pair.clone()));
+
+		assert_eq!(LiquidityPoolStorage::<T>::get(asset_pair).unwrap().status, PoolStatus::Active);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn create_pool() -> Result<(), BenchmarkError> {
+		let origin = T::PoolCreateOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let caller = T::PoolCreateOrigin::ensure_origin(origin.clone()).map_err(|_| BenchmarkError::Weightless)?;
+		create_asset::<T>(FIRST_ASSET, &caller);
+		create_asset::<T>(FIRST_ASSET + 1, &caller);
+
+		let asset_pair = AssetPairs::<T>::new(
+			NativeOrAssetId::Asset(FIRST_ASSET),
+			NativeOrAssetId::Asset(FIRST_ASSET + 1),
+		);
+		// Concentrated pools also search for the tick of their starting price.
+		let pool_kind = PoolKind::Concentrated { tick_spacing: 10 };
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, asset_pair.clone(), pool_kind, LIQUIDITY, LIQUIDITY);
+
+		assert!(LiquidityPoolStorage::<T>::contains_key(asset_pair.clone()));
+		assert!(ConcentratedPools::<T>::contains_key(asset_pair.clone()));
+		assert_eq!(PoolKinds::<T>::get(asset_pair), pool_kind);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_amplification() -> Result<(), BenchmarkError> {
+		let caller: T::AccountId = whitelisted_caller();
+		create_asset::<T>(FIRST_ASSET, &caller);
+		create_asset::<T>(FIRST_ASSET + 1, &caller);
+
+		let asset_pair = AssetPairs::<T>::new(
+			NativeOrAssetId::Asset(FIRST_ASSET),
+			NativeOrAssetId::Asset(FIRST_ASSET + 1),
+		);
+		assert_ok!(<Humidefi<T> as HumidefiCaller>::create_pool(
+			caller,
+			asset_pair.clone(),
+			PoolKind::StableSwap { amplification: 100 },
+			LIQUIDITY,
+			LIQUIDITY * 2,
+		));
+
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, asset_pair.clone(), 1_000);
+
+		assert_eq!(PoolKinds::<T>::get(asset_pair), PoolKind::StableSwap { amplification: 1_000 });
+		Ok(())
+	}
+
+	#[benchmark]
+	fn add_range_liquidity() -> Result<(), BenchmarkError> {
+		let caller: T::AccountId = whitelisted_caller();
+		let asset_pair = create_concentrated_pool::<T>(&caller);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), asset_pair.clone(), -100, 100, SWAP_AMOUNT, SWAP_AMOUNT);
+
+		assert_eq!(InitializedTicks::<T>::get(asset_pair.clone()).into_inner(), Vec::from([-100, 100]));
+		assert!(AccountLiquidityPoolStorage::<T>::get((caller, asset_pair))
+			.map_or(false, |account_liquidity_pools| account_liquidity_pools.len() == 2));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn remove_range_liquidity() -> Result<(), BenchmarkError> {
+		let caller: T::AccountId = whitelisted_caller();
+		let asset_pair = create_concentrated_pool::<T>(&caller);
+
+		assert_ok!(<Humidefi<T> as HumidefiCaller>::add_range_liquidity(
+			caller.clone(),
+			asset_pair.clone(),
+			-100,
+			100,
+			SWAP_AMOUNT,
+			SWAP_AMOUNT,
+		));
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), asset_pair.clone(), 2);
+
+		assert!(InitializedTicks::<T>::get(asset_pair.clone()).is_empty());
+		assert!(AccountLiquidityPoolStorage::<T>::get((caller, asset_pair))
+			.map_or(false, |account_liquidity_pools| account_liquidity_pools.len() == 1));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn place_limit_order() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_asset::<T>(FIRST_ASSET, &caller);
+		create_asset::<T>(FIRST_ASSET + 1, &caller);
+		create_liquidity_pool::<T>(&caller, FIRST_ASSET, FIRST_ASSET + 1);
+		let expiry = frame_system::Pallet::<T>::block_number() + 10u32.into();
+
+		#[extrinsic_call]
+		_(
+			RawOrigin::Signed(caller.clone()),
+			NativeOrAssetId::Asset(FIRST_ASSET),
+			SWAP_AMOUNT,
+			NativeOrAssetId::Asset(FIRST_ASSET + 1),
+			FixedU128::from_rational(1, 2),
+			expiry,
+		);
+
+		assert_eq!(AccountLimitOrders::<T>::get(caller).into_inner(), Vec::from([0]));
+	}
+
+	#[benchmark]
+	fn cancel_limit_order() {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = create_limit_order::<T>(&caller);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), id);
+
+		assert!(!LimitOrders::<T>::contains_key(id));
+		assert!(AccountLimitOrders::<T>::get(caller).is_empty());
+	}
+
+	#[benchmark]
+	fn process_limit_order() {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = create_limit_order::<T>(&caller);
+
+		#[block]
+		{
+			Humidefi::<T>::process_limit_orders(Weight::MAX);
+		}
+
+		assert!(!LimitOrders::<T>::contains_key(id));
+		assert!(AccountLimitOrders::<T>::get(caller).is_empty());
+	}
+
+	#[benchmark]
+	fn schedule_dca() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_asset::<T>(FIRST_ASSET, &caller);
+		create_asset::<T>(FIRST_ASSET + 1, &caller);
+		create_liquidity_pool::<T>(&caller, FIRST_ASSET, FIRST_ASSET + 1);
+
+		#[extrinsic_call]
+		_(
+			RawOrigin::Signed(caller.clone()),
+			NativeOrAssetId::Asset(FIRST_ASSET),
+			NativeOrAssetId::Asset(FIRST_ASSET + 1),
+			SWAP_AMOUNT,
+			Perbill::from_percent(5),
+			10u32.into(),
+			10,
+		);
+
+		assert_eq!(AccountDcaSchedules::<T>::get(caller).into_inner(), Vec::from([0]));
+	}
+
+	#[benchmark]
+	fn cancel_dca() {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = create_dca_schedule::<T>(&caller);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), id);
+
+		assert!(!DcaSchedules::<T>::contains_key(id));
+		assert!(AccountDcaSchedules::<T>::get(caller).is_empty());
+	}
+
+	#[benchmark]
+	fn execute_dca_run() {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = create_dca_schedule::<T>(&caller);
+		let block_number = frame_system::Pallet::<T>::block_number() + 1u32.into();
+
+		#[block]
+		{
+			Humidefi::<T>::execute_dca_runs(block_number);
+		}
+
+		assert_eq!(DcaSchedules::<T>::get(id).map(|dca_schedule| dca_schedule.remaining_runs), Some(9));
+	}
+
+	impl_benchmark_test_suite!(Humidefi, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,268 @@
+//! Tick and range math for concentrated liquidity pools, after Uniswap v3.
+//!
+//! Prices are quoted as `asset_y` per `asset_x` and kept as their square roots. Tick `i` is the
+//! price `1.0001^i`, so neighbouring ticks are one basis point apart. Within a tick range the pool
+//! behaves like a constant product pool with virtual reserves `x = L / √P` and `y = L · √P`.
+
+use frame_support::sp_runtime::{traits::CheckedMul, FixedPointNumber, FixedU128};
+use sp_core::U256;
+
+/// Lowest usable tick, a price of about `4.2e-18`.
+pub const MIN_TICK: i32 = -400_000;
+/// Highest usable tick, a price of about `2.4e17`.
+pub const MAX_TICK: i32 = 400_000;
+/// Upper bound on the tick spacing of a pool.
+pub const MAX_TICK_SPACING: u32 = 10_000;
+
+fn div(numerator: U256, denominator: U256, round_up: bool) -> Option<U256> {
+	if denominator.is_zero() {
+		return None;
+	}
+
+	let (quotient, remainder) = numerator.div_mod(denominator);
+	if round_up && !remainder.is_zero() {
+		quotient.checked_add(U256::one())
+	} else {
+		Some(quotient)
+	}
+}
+
+fn to_u128(value: U256) -> Option<u128> {
+	value.try_into().ok()
+}
+
+/// Liquidity that a full range position holding both reserves backs at `sqrt_price`:
+/// `min(x · √P, y / √P)`, rounded down, so that its virtual reserves never exceed the real ones.
+pub fn full_range_liquidity(sqrt_price: FixedU128, reserve_x: u128, reserve_y: u128) -> Option<u128> {
+	let unit = U256::from(FixedU128::DIV);
+	let sqrt_price = U256::from(sqrt_price.into_inner());
+
+	let liquidity_for_x = div(U256::from(reserve_x).checked_mul(sqrt_price)?, unit, false)?;
+	let liquidity_for_y = div(U256::from(reserve_y).checked_mul(unit)?, sqrt_price, false)?;
+
+	to_u128(liquidity_for_x.min(liquidity_for_y))
+}
+
+/// `liquidity + liquidity_delta`, or `None` if it would leave the range of `u128`.
+pub fn add_liquidity_delta(liquidity: u128, liquidity_delta: i128) -> Option<u128> {
+	if liquidity_delta >= 0 {
+		liquidity.checked_add(liquidity_delta.unsigned_abs())
+	} else {
+		liquidity.checked_sub(liquidity_delta.unsigned_abs())
+	}
+}
+
+/// `√1.0001^tick`.
+pub fn sqrt_price_at_tick(tick: i32) -> Option<FixedU128> {
+	if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+		return None;
+	}
+
+	let mut base = FixedU128::from_rational(10_001, 10_000).sqrt();
+	let mut exponent = tick.unsigned_abs();
+	let mut result = FixedU128::one();
+	while exponent > 0 {
+		if exponent & 1 == 1 {
+			result = result.checked_mul(&base)?;
+		}
+
+		exponent >>= 1;
+		if exponent > 0 {
+			base = base.checked_mul(&base)?;
+		}
+	}
+
+	if tick < 0 {
+		result.reciprocal()
+	} else {
+		Some(result)
+	}
+}
+
+/// The greatest tick whose square root price is at most `sqrt_price`.
+pub fn tick_at_sqrt_price(sqrt_price: FixedU128) -> Option<i32> {
+	if sqrt_price < sqrt_price_at_tick(MIN_TICK)? {
+		return None;
+	}
+
+	let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+	while low < high {
+		let middle = low + (high - low + 1) / 2;
+		if sqrt_price_at_tick(middle)? <= sqrt_price {
+			low = middle;
+		} else {
+			high = middle - 1;
+		}
+	}
+
+	Some(low)
+}
+
+/// `asset_x` backing `liquidity` between two square root prices: `L · (√P_b - √P_a) / (√P_a · √P_b)`.
+pub fn amount_x_delta(liquidity: u128, sqrt_price_a: FixedU128, sqrt_price_b: FixedU128, round_up: bool) -> Option<u128> {
+	let (lower, upper) = if sqrt_price_a <= sqrt_price_b { (sqrt_price_a, sqrt_price_b) } else { (sqrt_price_b, sqrt_price_a) };
+	let (lower, upper) = (U256::from(lower.into_inner()), U256::from(upper.into_inner()));
+
+	let numerator = U256::from(liquidity)
+		.checked_mul(upper - lower)?
+		.checked_mul(U256::from(FixedU128::DIV))?;
+
+	to_u128(div(div(numerator, lower, round_up)?, upper, round_up)?)
+}
+
+/// `asset_y` backing `liquidity` between two square root prices: `L · (√P_b - √P_a)`.
+pub fn amount_y_delta(liquidity: u128, sqrt_price_a: FixedU128, sqrt_price_b: FixedU128, round_up: bool) -> Option<u128> {
+	let (lower, upper) = if sqrt_price_a <= sqrt_price_b { (sqrt_price_a, sqrt_price_b) } else { (sqrt_price_b, sqrt_price_a) };
+	let numerator = U256::from(liquidity).checked_mul(U256::from(upper.into_inner() - lower.into_inner()))?;
+
+	to_u128(div(numerator, U256::from(FixedU128::DIV), round_up)?)
+}
+
+/// Amounts of both assets held by `liquidity` over `[sqrt_price_lower, sqrt_price_upper]` at `sqrt_price`.
+pub fn amounts_for_liquidity(
+	sqrt_price: FixedU128,
+	sqrt_price_lower: FixedU128,
+	sqrt_price_upper: FixedU128,
+	liquidity: u128,
+	round_up: bool,
+) -> Option<(u128, u128)> {
+	if sqrt_price <= sqrt_price_lower {
+		Some((amount_x_delta(liquidity, sqrt_price_lower, sqrt_price_upper, round_up)?, 0))
+	} else if sqrt_price < sqrt_price_upper {
+		Some((
+			amount_x_delta(liquidity, sqrt_price, sqrt_price_upper, round_up)?,
+			amount_y_delta(liquidity, sqrt_price_lower, sqrt_price, round_up)?,
+		))
+	} else {
+		Some((0, amount_y_delta(liquidity, sqrt_price_lower, sqrt_price_upper, round_up)?))
+	}
+}
+
+/// The most liquidity that `asset_x_balance` and `asset_y_balance` can back over the range.
+pub fn liquidity_for_amounts(
+	sqrt_price: FixedU128,
+	sqrt_price_lower: FixedU128,
+	sqrt_price_upper: FixedU128,
+	asset_x_balance: u128,
+	asset_y_balance: u128,
+) -> Option<u128> {
+	let liquidity_for_x = |lower: FixedU128, upper: FixedU128| -> Option<u128> {
+		let (lower, upper) = (U256::from(lower.into_inner()), U256::from(upper.into_inner()));
+		let numerator = U256::from(asset_x_balance).checked_mul(lower)?;
+		let liquidity = match numerator.checked_mul(upper) {
+			Some(numerator) => div(numerator, (upper - lower).checked_mul(U256::from(FixedU128::DIV))?, false)?,
+			// Large balances at high prices only lose a little liquidity to the earlier rounding.
+			None => div(div(numerator, upper - lower, false)?.checked_mul(upper)?, U256::from(FixedU128::DIV), false)?,
+		};
+
+		to_u128(liquidity)
+	};
+	let liquidity_for_y = |lower: FixedU128, upper: FixedU128| -> Option<u128> {
+		let numerator = U256::from(asset_y_balance).checked_mul(U256::from(FixedU128::DIV))?;
+		to_u128(div(numerator, U256::from(upper.into_inner() - lower.into_inner()), false)?)
+	};
+
+	if sqrt_price_lower >= sqrt_price_upper {
+		return None;
+	}
+
+	if sqrt_price <= sqrt_price_lower {
+		liquidity_for_x(sqrt_price_lower, sqrt_price_upper)
+	} else if sqrt_price < sqrt_price_upper {
+		Some(liquidity_for_x(sqrt_price, sqrt_price_upper)?.min(liquidity_for_y(sqrt_price_lower, sqrt_price)?))
+	} else {
+		liquidity_for_y(sqrt_price_lower, sqrt_price_upper)
+	}
+}
+
+/// Largest part of `asset_x_balance` and `asset_y_balance` in the ratio of the price `sqrt_price²`.
+pub fn amounts_at_price(sqrt_price: FixedU128, asset_x_balance: u128, asset_y_balance: u128) -> Option<(u128, u128)> {
+	let sqrt_price = U256::from(sqrt_price.into_inner());
+	let div_squared = U256::from(FixedU128::DIV).checked_mul(U256::from(FixedU128::DIV))?;
+	let price_squared = sqrt_price.checked_mul(sqrt_price)?;
+
+	let asset_y_needed = to_u128(div(U256::from(asset_x_balance).checked_mul(price_squared)?, div_squared, true)?)?;
+	if asset_y_needed <= asset_y_balance {
+		return Some((asset_x_balance, asset_y_needed));
+	}
+
+	let asset_x_needed = to_u128(div(U256::from(asset_y_balance).checked_mul(div_squared)?, price_squared, false)?)?;
+	Some((asset_x_needed, asset_y_balance))
+}
+
+/// One step of a swap towards `sqrt_price_target` with `liquidity` in range.
+///
+/// `amount_remaining` is the input left to sell when `exact_in`, otherwise the output left to buy.
+/// Returns the square root price reached and the step's input and output; inputs round up and
+/// outputs round down.
+pub fn swap_step(
+	sqrt_price: FixedU128,
+	sqrt_price_target: FixedU128,
+	liquidity: u128,
+	amount_remaining: u128,
+	exact_in: bool,
+) -> Option<(FixedU128, u128, u128)> {
+	// Selling `asset_x` pushes the price down, selling `asset_y` pushes it up.
+	let x_in = sqrt_price_target < sqrt_price;
+	let (price, liquidity_u256) = (U256::from(sqrt_price.into_inner()), U256::from(liquidity));
+	let unit = U256::from(FixedU128::DIV);
+
+	let (max_in, max_out) = if x_in {
+		(
+			amount_x_delta(liquidity, sqrt_price_target, sqrt_price, true)?,
+			amount_y_delta(liquidity, sqrt_price_target, sqrt_price, false)?,
+		)
+	} else {
+		(
+			amount_y_delta(liquidity, sqrt_price, sqrt_price_target, true)?,
+			amount_x_delta(liquidity, sqrt_price, sqrt_price_target, false)?,
+		)
+	};
+
+	let reaches_target = if exact_in { amount_remaining >= max_in } else { amount_remaining >= max_out };
+	let next_sqrt_price = if reaches_target {
+		sqrt_price_target
+	} else {
+		let next = match (x_in, exact_in) {
+			// 1/√P' = 1/√P + x/L, rounded up so the price falls no further than paid for.
+			(true, true) => {
+				let denominator = liquidity_u256.checked_mul(unit)?.checked_add(U256::from(amount_remaining).checked_mul(price)?)?;
+				div(liquidity_u256.checked_mul(unit)?.checked_mul(price)?, denominator, true)?
+			},
+			// √P' = √P + y/L, rounded down.
+			(false, true) => price.checked_add(div(U256::from(amount_remaining).checked_mul(unit)?, liquidity_u256, false)?)?,
+			// √P' = √P - y/L, rounded down so enough `asset_x` is charged.
+			(true, false) => price.checked_sub(div(U256::from(amount_remaining).checked_mul(unit)?, liquidity_u256, true)?)?,
+			// 1/√P' = 1/√P - x/L, rounded up.
+			(false, false) => {
+				let denominator = liquidity_u256.checked_mul(unit)?.checked_sub(U256::from(amount_remaining).checked_mul(price)?)?;
+				div(liquidity_u256.checked_mul(unit)?.checked_mul(price)?, denominator, true)?
+			},
+		};
+
+		// Rounding must not carry the price past the target without crossing it.
+		let next = FixedU128::from_inner(to_u128(next)?);
+		if x_in { next.max(sqrt_price_target) } else { next.min(sqrt_price_target) }
+	};
+
+	let (amount_in, amount_out) = if x_in {
+		(
+			amount_x_delta(liquidity, next_sqrt_price, sqrt_price, true)?,
+			amount_y_delta(liquidity, next_sqrt_price, sqrt_price, false)?,
+		)
+	} else {
+		(
+			amount_y_delta(liquidity, sqrt_price, next_sqrt_price, true)?,
+			amount_x_delta(liquidity, sqrt_price, next_sqrt_price, false)?,
+		)
+	};
+
+	// A step that stops short of the target takes the whole remainder, even if rounding left some over.
+	let (amount_in, amount_out) = match (reaches_target, exact_in) {
+		(false, true) => (amount_remaining, amount_out),
+		(false, false) => (amount_in, amount_remaining),
+		_ => (amount_in, amount_out),
+	};
+
+	Some((next_sqrt_price, amount_in, amount_out))
+}
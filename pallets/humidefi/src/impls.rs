@@ -4,43 +4,107 @@ use frame_support::{
         BoundedVec,
 		traits::{
 			AccountIdConversion,
-			EnsureAdd, EnsureSub,
+			SaturatedConversion,
+			EnsureAdd, EnsureSub, EnsureMul, EnsureDiv,
+			Saturating,
 			Zero,
 			ConstU32
 		},
+		helpers_128bit::multiply_by_rational_with_rounding,
+		FixedPointNumber,
 		FixedU128,
+		Perbill,
+		PerThing,
+		Rounding,
 	},
-	traits::{fungibles},
+	traits::{fungible, fungibles},
+	sp_std::vec::Vec,
 	PalletId,
 };
+use frame_system::pallet_prelude::BlockNumberFor;
 use super::*;
 
-use interfaces::humidefi::{ AssetPairsTrait, LiquidityPoolTrait, AccountLiquidityPoolTrait };
-use interfaces::humidefi::{ HumidefiCaller, HumidefiHelpers };
+use interfaces::humidefi::{ AssetPairsTrait, RedeemLiquidityAmountTrait };
+use interfaces::humidefi::{ HumidefiCaller, HumidefiInspect, PriceOracle };
+use pallet_humidefi_rpc_runtime_api::{PoolInfo, PositionInfo};
+#[cfg(any(feature = "try-runtime", test))]
+use frame_support::sp_std::collections::btree_map::BTreeMap;
 
 const HUMIDEFI: PalletId = PalletId(*b"HUMIDEFI");
 
 impl<T: Config> AssetPairsTrait for AssetPairs<T> { type AssetPairs = Self; }
-impl<T: Config> LiquidityPoolTrait for LiquidityPool<T> { type LiquidityPool = Self; }
-impl<T: Config> AccountLiquidityPoolTrait for AccountLiquidityPool<T> { type AccountLiquidityPool = Self; }
+impl<T: Config> RedeemLiquidityAmountTrait for RedeemLiquidityAmount<T> { type RedeemLiquidityAmount = Self; }
 
 impl<T: Config> HumidefiCaller for Pallet<T> {
 	type AccountId = T::AccountId;
 	type AssetId = <T::Fungibles as fungibles::Inspect<Self::AccountId>>::AssetId;
 	type AssetBalance = <T::Fungibles as fungibles::Inspect<Self::AccountId>>::Balance;
+	type AssetKind = NativeOrAssetIdOf<T>;
 	type AccountLiquidityPoolId = u64;
 	type AssetPairs = <AssetPairs<T> as AssetPairsTrait>::AssetPairs;
+	type RedeemLiquidityAmount = <RedeemLiquidityAmount<T> as RedeemLiquidityAmountTrait>::RedeemLiquidityAmount;
+	type BlockNumber = BlockNumberFor<T>;
+	type PoolKind = PoolKind;
+	type LimitOrderId = LimitOrderId;
+	type DcaScheduleId = DcaScheduleId;
 
 	fn new_liquidity(
 		who:  Self::AccountId,
 		asset_pair: Self::AssetPairs,
 		asset_x_balance:  Self::AssetBalance,
 		asset_y_balance:  Self::AssetBalance,
+		min_lp_token: Self::AssetBalance,
 	) -> Result<(), DispatchError> {
-		let humidefi_account_id = <Pallet<T> as HumidefiHelpers>::get_dex_account();
+		ensure!(asset_pair.asset_x != asset_pair.asset_y, Error::<T>::IdenticalAssets);
+
+		// Pools are always stored under the canonical ordering of their pair.
+		let (asset_pair, asset_x_balance, asset_y_balance) = if asset_pair.is_canonical() {
+			(asset_pair, asset_x_balance, asset_y_balance)
+		} else {
+			(asset_pair.normalize(), asset_y_balance, asset_x_balance)
+		};
+
+		// Only what matches the pool's current price is deposited, the rest stays with `who`.
+		// Concentrated pools are priced by their range state rather than their reserves' ratio.
+		let (asset_x_balance, asset_y_balance) = match ConcentratedPools::<T>::get(asset_pair.clone()) {
+			Some(concentrated_pool) => {
+				concentrated_math::amounts_at_price(concentrated_pool.sqrt_price, asset_x_balance, asset_y_balance)
+					.ok_or(Error::<T>::ComputeRangeLiquidityError)?
+			},
+			None => Self::compute_deposit_amounts(
+				asset_pair.clone(),
+				asset_x_balance,
+				asset_y_balance,
+			)?,
+		};
+
+		let humidefi_account_id = Self::get_dex_account();
+		let pool_account_id = Self::get_pool_account(asset_pair.clone());
+		let existing_liquidity_pool = Self::get_liquidity_pool(asset_pair.clone());
+		let is_first_deposit = existing_liquidity_pool
+			.as_ref()
+			.map_or(true, |liquidity_pool| liquidity_pool.lp_token_balance.is_zero());
+
+		// A fresh or drained pool account needs a provider before it can hold non-sufficient assets.
+		if let Some(liquidity_pool) = existing_liquidity_pool {
+			Pallet::<T>::ensure_pool_status(&liquidity_pool, PoolStatus::Active)?;
+
+			if is_first_deposit {
+				frame_system::Pallet::<T>::inc_providers(&pool_account_id);
+			}
+		} else {
+			ensure!(GlobalPoolStatus::<T>::get() == PoolStatus::Active, Error::<T>::PoolPaused);
+
+			ensure!(
+				Pallet::<T>::is_asset_listed(asset_pair.asset_x) && Pallet::<T>::is_asset_listed(asset_pair.asset_y),
+				Error::<T>::AssetNotListed
+			);
+
+			frame_system::Pallet::<T>::inc_providers(&pool_account_id);
+		}
 
 		ensure!(
-			<Pallet<T> as HumidefiHelpers>::check_asset_balance(
+			Self::check_asset_balance(
 				who.clone(),
 				asset_pair.clone().asset_x,
 				asset_x_balance,
@@ -49,50 +113,70 @@ impl<T: Config> HumidefiCaller for Pallet<T> {
 		);
 
 		ensure!(
-			<Pallet<T> as HumidefiHelpers>::check_asset_balance(
+			Self::check_asset_balance(
 				who.clone(),
 				asset_pair.clone().asset_y,
-				asset_x_balance,
+				asset_y_balance,
 			).is_ok(),
 			Error::<T>::CheckAssetYBalanceError
 		);
 
-		<T::Fungibles as fungibles::Mutate<_>>::transfer(
+		Self::transfer_pool_asset(
 			asset_pair.clone().asset_x,
-			&who.clone(),
-			&humidefi_account_id.clone(),
+			who.clone(),
+			pool_account_id.clone(),
 			asset_x_balance,
-			frame_support::traits::tokens::Preservation::Expendable,
 		)?;
 
-		<T::Fungibles as fungibles::Mutate<_>>::transfer(
+		Self::transfer_pool_asset(
 			asset_pair.clone().asset_y,
-			&who.clone(),
-			&humidefi_account_id.clone(),
+			who.clone(),
+			pool_account_id.clone(),
 			asset_y_balance,
-			frame_support::traits::tokens::Preservation::Expendable,
 		)?;
 
-		let mint_liquidity_result = <Pallet<T> as HumidefiHelpers>::compute_and_mint_lp_token(
+		let mint_liquidity_result = Self::compute_and_mint_lp_token(
 			asset_pair.clone(),
 			asset_x_balance,
 			asset_y_balance,
 		);
 
-		let (lp_token, lp_token_balance) = match mint_liquidity_result {
+		let (lp_token, lp_token_minted) = match mint_liquidity_result {
 			Ok(mint_liquidity) => mint_liquidity,
 			Err(_) => return Err(Error::<T>::ComputeAndMintLiquidityPoolTokenError.into()),
 		};
 
 		ensure!(
-			<Pallet<T> as HumidefiHelpers>::check_asset_balance(
+			Self::check_asset_balance(
 				humidefi_account_id.clone(),
-				lp_token,
-				lp_token_balance,
+				NativeOrAssetId::Asset(lp_token),
+				lp_token_minted,
 			).is_ok(),
 			Error::<T>::CheckAssetYBalanceError
 		);
 
+		// The first deposit locks `MINIMUM_LIQUIDITY` of its LP tokens in the pool account.
+		let lp_token_balance = if is_first_deposit {
+			let lp_token_balance = lp_token_minted
+				.checked_sub(MINIMUM_LIQUIDITY)
+				.filter(|lp_token_balance| !lp_token_balance.is_zero())
+				.ok_or(Error::<T>::InsufficientLiquidity)?;
+
+			<T::Fungibles as fungibles::Mutate<_>>::transfer(
+				lp_token,
+				&humidefi_account_id.clone(),
+				&pool_account_id.clone(),
+				MINIMUM_LIQUIDITY,
+				frame_support::traits::tokens::Preservation::Expendable,
+			)?;
+
+			lp_token_balance
+		} else {
+			lp_token_minted
+		};
+
+		ensure!(lp_token_balance >= min_lp_token, Error::<T>::SlippageExceeded);
+
 		<T::Fungibles as fungibles::Mutate<_>>::transfer(
 			lp_token,
 			&humidefi_account_id.clone(),
@@ -101,9 +185,11 @@ impl<T: Config> HumidefiCaller for Pallet<T> {
 			frame_support::traits::tokens::Preservation::Expendable,
 		)?;
 
-		let get_liquidity_pool = <Pallet<T> as HumidefiHelpers>::get_liquidity_pool(asset_pair.clone());
+		let get_liquidity_pool = Self::get_liquidity_pool(asset_pair.clone());
 		match get_liquidity_pool {
 			Some(liquidity_pool) => {
+				Pallet::<T>::update_price_accumulator(&liquidity_pool);
+
 				let update_asset_x_balance = liquidity_pool
 					.asset_x_balance
 					.add(FixedU128::from_inner(asset_x_balance));
@@ -112,7 +198,8 @@ impl<T: Config> HumidefiCaller for Pallet<T> {
 					.asset_y_balance
 					.add(FixedU128::from_inner(asset_y_balance));
 
-				let update_price_result = <Pallet<T> as HumidefiHelpers>::compute_price(
+				let update_price_result = Self::compute_pool_price(
+					asset_pair.clone(),
 					update_asset_x_balance.into_inner(),
 					update_asset_y_balance.into_inner()
 				);
@@ -124,7 +211,7 @@ impl<T: Config> HumidefiCaller for Pallet<T> {
 
 				let update_lp_token_balance = liquidity_pool
 					.lp_token_balance
-					.add(FixedU128::from_inner(lp_token_balance));
+					.add(FixedU128::from_inner(lp_token_minted));
 
 				LiquidityPoolStorage::<T>::mutate(asset_pair.clone(), |query| {
 					let liquidity_pool_payload = LiquidityPool::<T> {
@@ -134,15 +221,18 @@ impl<T: Config> HumidefiCaller for Pallet<T> {
 						price: update_price,
 						asset_x_fee: liquidity_pool.asset_x_fee,
 						asset_y_fee: liquidity_pool.asset_y_fee,
+						swap_fee: liquidity_pool.swap_fee,
 						lp_token: liquidity_pool.lp_token,
 						lp_token_balance: update_lp_token_balance,
+						status: liquidity_pool.status,
 					};
 
 					*query = Some(liquidity_pool_payload);
 				});
 			},
 			None => {
-				let new_price_result = <Pallet<T> as HumidefiHelpers>::compute_price(
+				let new_price_result = Self::compute_pool_price(
+					asset_pair.clone(),
 					asset_x_balance,
 					asset_y_balance
 				);
@@ -159,15 +249,23 @@ impl<T: Config> HumidefiCaller for Pallet<T> {
 					price: new_price,
 					asset_x_fee: FixedU128::from_inner(0),
 					asset_y_fee: FixedU128::from_inner(0),
+					swap_fee: T::DefaultSwapFee::get(),
 					lp_token,
-					lp_token_balance: FixedU128::from_inner(lp_token_balance),
+					lp_token_balance: FixedU128::from_inner(lp_token_minted),
+					status: PoolStatus::Active,
 				};
 
+				Pallet::<T>::update_price_accumulator(&liquidity_pool_payload);
 				LiquidityPoolStorage::<T>::insert(asset_pair.clone(), liquidity_pool_payload);
+
+				Pallet::<T>::deposit_event(Event::PoolCreated {
+					pair: asset_pair.clone(),
+					lp_token,
+				});
 			}
 		}
 
-		let mut account_liquidity_pool_payload = AccountLiquidityPool::<T> {
+		let account_liquidity_pool_payload = AccountLiquidityPool::<T> {
 			id: 1u64,
 			account_id: who.clone(),
 			asset_pair: asset_pair.clone(),
@@ -175,633 +273,3000 @@ impl<T: Config> HumidefiCaller for Pallet<T> {
 			asset_y_balance: FixedU128::from_inner(asset_y_balance),
 			lp_token,
 			lp_token_balance: FixedU128::from_inner(lp_token_balance),
+			range: None,
 		};
 
-		let get_account_liquidity_pools = <Pallet<T> as HumidefiHelpers>::get_account_liquidity_pools(who.clone(), asset_pair.clone());
-		match get_account_liquidity_pools {
-			Some(account_liquidity_pools) => {
-				let mut last_id = 0u64;
-				if let Some(account_liquidity_pool) = account_liquidity_pools.last() {
-					last_id = account_liquidity_pool.id;
-				}
-
-				let new_id_result = last_id.ensure_add(1);
-				let new_id = match new_id_result {
-					Ok(id) => id,
-					Err(_) => return Err(Error::<T>::AccountLiquidityPoolIdError.into()),
-				};
-
-				account_liquidity_pool_payload.id = new_id;
-
-				let mut mutate_account_liquidity_pools = account_liquidity_pools.clone();
-				mutate_account_liquidity_pools
-					.try_push(account_liquidity_pool_payload.clone())
-					.map_err(|_| Error::<T>::AccountLiquidityPoolBoundedVecError)?;
+		let position_id = Pallet::<T>::push_account_liquidity_pool(
+			who.clone(),
+			asset_pair.clone(),
+			account_liquidity_pool_payload
+		)?;
 
-				let storage_key = (who.clone(), asset_pair.clone());
-				AccountLiquidityPoolStorage::<T>::mutate(storage_key, |mut query| {
-					let update_account_liquidity_pools = mutate_account_liquidity_pools.clone();
-					*query = Some(update_account_liquidity_pools)
-				});
-			},
-			None => {
-				let mut new_account_liquidity_pools: BoundedVec<
-					AccountLiquidityPool<T>,
-					ConstU32<100>,
-				> = Default::default();
-
-				new_account_liquidity_pools
-					.try_push(account_liquidity_pool_payload.clone())
-					.map_err(|_| Error::<T>::AccountLiquidityPoolBoundedVecError)?;
-
-				AccountLiquidityPoolStorage::<T>::insert(
-					(who, asset_pair),
-					new_account_liquidity_pools,
-				);
-			}
-		}
+		Pallet::<T>::deposit_event(Event::LiquidityAdded {
+			who,
+			pair: asset_pair,
+			amount_x: asset_x_balance,
+			amount_y: asset_y_balance,
+			lp_minted: lp_token_balance,
+			position_id,
+		});
 
 		Ok(())
 	}
 
-	fn redeem_liquidity(
+	fn create_pool(
 		who: Self::AccountId,
 		asset_pair: Self::AssetPairs,
-		lp_token: Self::AssetId,
-		id: Self::AccountLiquidityPoolId,
+		pool_kind: Self::PoolKind,
+		asset_x_balance: Self::AssetBalance,
+		asset_y_balance: Self::AssetBalance,
 	) -> Result<(), DispatchError> {
-		let get_liquidity_pool = <Pallet<T> as HumidefiHelpers>::get_liquidity_pool(asset_pair.clone());
-		if get_liquidity_pool.is_none() {
-			return Err(Error::<T>::LiquidityPoolDoesNotExists.into())
-		}
-
-		let asset_xy_balances_result = <Pallet<T> as HumidefiHelpers>::compute_xy_assets(
-			who.clone(),
-			asset_pair.clone(),
-			lp_token,
-			id
-		);
-
-		let (asset_x_balance, asset_y_balance, lp_token_balance) = match asset_xy_balances_result {
-			Ok(asset_xy_balances) => asset_xy_balances,
-			Err(_) => return Err(Error::<T>::ComputeXYBalancesError.into()),
-		};
-
-		let humidefi_account_id = <Pallet<T> as HumidefiHelpers>::get_dex_account();
-
-		ensure!(
-			<Pallet<T> as HumidefiHelpers>::check_asset_balance(
-				humidefi_account_id.clone(),
-				asset_pair.clone().asset_x,
-				asset_x_balance,
-			).is_ok(),
-			Error::<T>::CheckAssetXBalanceError
-		);
-
 		ensure!(
-			<Pallet<T> as HumidefiHelpers>::check_asset_balance(
-				humidefi_account_id.clone(),
-				asset_pair.clone().asset_x,
-				asset_y_balance,
-			).is_ok(),
-			Error::<T>::CheckAssetYBalanceError
+			Self::get_liquidity_pool(asset_pair.clone()).is_none(),
+			Error::<T>::LiquidityPoolAlreadyExists
 		);
 
-		<T::Fungibles as fungibles::Mutate<_>>::transfer(
-			asset_pair.clone().asset_x,
-			&humidefi_account_id.clone(),
-			&who.clone(),
-			asset_x_balance,
-			frame_support::traits::tokens::Preservation::Expendable,
-		)?;
+		Pallet::<T>::ensure_valid_pool_kind(pool_kind)?;
 
-		<T::Fungibles as fungibles::Mutate<_>>::transfer(
-			asset_pair.clone().asset_y,
-			&humidefi_account_id.clone(),
-			&who.clone(),
-			asset_y_balance,
-			frame_support::traits::tokens::Preservation::Expendable,
-		)?;
+		// Weights belong to the assets, so they follow them into the canonical ordering.
+		let pool_kind = match pool_kind {
+			PoolKind::Weighted { weight_x } if !asset_pair.is_canonical() => {
+				PoolKind::Weighted { weight_x: weight_x.left_from_one() }
+			},
+			_ => pool_kind,
+		};
 
-		LiquidityPoolStorage::<T>::mutate(asset_pair.clone(), |query| {
-			if let Some(mutate_liquidity_pool) = query {
-				let update_asset_x_balance = mutate_liquidity_pool
-					.asset_x_balance
-					.sub(FixedU128::from_inner(asset_x_balance));
+		// Set before the first deposit so LP tokens and the price follow the chosen curve.
+		PoolKinds::<T>::insert(asset_pair.normalize(), pool_kind);
 
-				let update_asset_y_balance = mutate_liquidity_pool
-					.asset_y_balance
-					.sub(FixedU128::from_inner(asset_y_balance));
+		<Pallet<T> as HumidefiCaller>::new_liquidity(who, asset_pair.clone(), asset_x_balance, asset_y_balance, Zero::zero())?;
 
-				let update_price_result = <Pallet<T> as HumidefiHelpers>::compute_price(
-					update_asset_x_balance.into_inner(),
-					update_asset_y_balance.into_inner()
-				);
+		// Range positions start out empty at the price of the first deposit.
+		if let PoolKind::Concentrated { .. } = pool_kind {
+			let liquidity_pool = Self::get_liquidity_pool(asset_pair.clone())
+				.ok_or(Error::<T>::LiquidityPoolDoesNotExists)?;
 
-				let update_price = match update_price_result {
-					Ok(price) => price,
-					Err(_) => {
-						return;
-					}
-				};
+			let sqrt_price = FixedU128::checked_from_rational(
+				liquidity_pool.asset_y_balance.into_inner(),
+				liquidity_pool.asset_x_balance.into_inner()
+			).ok_or(Error::<T>::ComputePriceError)?.sqrt();
 
-				let update_lp_token_balance = mutate_liquidity_pool
-					.lp_token_balance
-					.sub(FixedU128::from_inner(lp_token_balance));
+			ensure!(
+				sqrt_price <= concentrated_math::sqrt_price_at_tick(concentrated_math::MAX_TICK)
+					.ok_or(Error::<T>::InvalidTickRange)?,
+				Error::<T>::InvalidTickRange
+			);
 
-				let liquidity_pool_payload = LiquidityPool::<T> {
-					asset_pair: asset_pair.clone(),
-					asset_x_balance: update_asset_x_balance,
-					asset_y_balance: update_asset_y_balance,
-					price: update_price,
-					asset_x_fee: mutate_liquidity_pool.asset_x_fee,
-					asset_y_fee: mutate_liquidity_pool.asset_y_fee,
-					lp_token: mutate_liquidity_pool.lp_token,
-					lp_token_balance: update_lp_token_balance,
-				};
+			let tick = concentrated_math::tick_at_sqrt_price(sqrt_price)
+				.ok_or(Error::<T>::InvalidTickRange)?;
 
-				*query = Some(liquidity_pool_payload);
-			}
-		});
+			ConcentratedPools::<T>::insert(asset_pair.normalize(), ConcentratedPool {
+				sqrt_price,
+				tick,
+				..Default::default()
+			});
+		}
 
-		AccountLiquidityPoolStorage::<T>::remove((who.clone(), asset_pair.clone()));
+		if pool_kind != PoolKind::ConstantProduct {
+			Pallet::<T>::deposit_event(Event::PoolKindUpdated {
+				pair: asset_pair.normalize(),
+				kind: pool_kind,
+			});
+		}
 
 		Ok(())
 	}
 
-	fn swap_exact_in_for_out(
+	fn add_range_liquidity(
 		who: Self::AccountId,
-		asset_exact_in: Self::AssetId,
-		asset_exact_in_balance: Self::AssetBalance,
-		asset_max_out: Self::AssetId,
+		asset_pair: Self::AssetPairs,
+		lower_tick: i32,
+		upper_tick: i32,
+		asset_x_balance: Self::AssetBalance,
+		asset_y_balance: Self::AssetBalance,
 	) -> Result<(), DispatchError> {
-		let humidefi_account_id = <Pallet<T> as HumidefiHelpers>::get_dex_account();
-
-		ensure!(
-			<Pallet<T> as HumidefiHelpers>::check_asset_balance(
-				who.clone(),
-				asset_exact_in,
-				asset_exact_in_balance,
-			).is_ok(),
-			Error::<T>::CheckAssetSwapInBalanceError
-		);
-
-		<T::Fungibles as fungibles::Mutate<_>>::transfer(
-			asset_exact_in,
-			&who.clone(),
-			&humidefi_account_id.clone(),
-			asset_exact_in_balance,
-			frame_support::traits::tokens::Preservation::Expendable,
-		)?;
+		// Ticks always price the canonical pair, only the amounts follow the assets.
+		let (asset_pair, asset_x_balance, asset_y_balance) = if asset_pair.is_canonical() {
+			(asset_pair, asset_x_balance, asset_y_balance)
+		} else {
+			(asset_pair.normalize(), asset_y_balance, asset_x_balance)
+		};
 
-		let asset_pair = AssetPairs::<T> { asset_x: asset_exact_in, asset_y: asset_max_out };
-		let get_liquidity_pool = <Pallet<T> as HumidefiHelpers>::get_liquidity_pool(asset_pair.clone());
-		match get_liquidity_pool {
-			Some(liquidity_pool) => {
-				let mut price = FixedU128::from_inner(0);
+		let liquidity_pool = Self::get_liquidity_pool(asset_pair.clone())
+			.ok_or(Error::<T>::LiquidityPoolDoesNotExists)?;
+		Pallet::<T>::ensure_pool_status(&liquidity_pool, PoolStatus::Active)?;
 
-				if asset_exact_in == liquidity_pool.asset_pair.asset_x {
-					let price_result = <Pallet<T> as HumidefiHelpers>::compute_price(
-						liquidity_pool.asset_x_balance.into_inner(),
-						liquidity_pool.asset_y_balance.into_inner()
-					);
+		let tick_spacing = match PoolKinds::<T>::get(asset_pair.clone()) {
+			PoolKind::Concentrated { tick_spacing } => tick_spacing as i32,
+			_ => return Err(Error::<T>::NotConcentratedPool.into()),
+		};
 
-					price = match price_result {
-						Ok(data) => data,
-						Err(_) => return Err(Error::<T>::ComputePriceError.into()),
-					};
-				}
+		let mut concentrated_pool = ConcentratedPools::<T>::get(asset_pair.clone())
+			.ok_or(Error::<T>::NotConcentratedPool)?;
 
-				if asset_exact_in == liquidity_pool.asset_pair.asset_y {
-					let price_result = <Pallet<T> as HumidefiHelpers>::compute_price(
-						liquidity_pool.asset_y_balance.into_inner(),
-						liquidity_pool.asset_x_balance.into_inner()
-					);
+		ensure!(
+			lower_tick < upper_tick
+				&& concentrated_math::MIN_TICK <= lower_tick
+				&& upper_tick <= concentrated_math::MAX_TICK
+				&& lower_tick.rem_euclid(tick_spacing) == 0
+				&& upper_tick.rem_euclid(tick_spacing) == 0,
+			Error::<T>::InvalidTickRange
+		);
 
-					price = match price_result {
-						Ok(data) => data,
-						Err(_) => return Err(Error::<T>::ComputePriceError.into()),
-					};
-				}
+		let sqrt_price_lower = concentrated_math::sqrt_price_at_tick(lower_tick)
+			.ok_or(Error::<T>::InvalidTickRange)?;
+		let sqrt_price_upper = concentrated_math::sqrt_price_at_tick(upper_tick)
+			.ok_or(Error::<T>::InvalidTickRange)?;
 
-				let asset_max_out_balance = FixedU128::from_inner(price.into_inner()).mul(FixedU128::from_inner(asset_exact_in_balance));
+		let liquidity = concentrated_math::liquidity_for_amounts(
+			concentrated_pool.sqrt_price,
+			sqrt_price_lower,
+			sqrt_price_upper,
+			asset_x_balance,
+			asset_y_balance
+		).ok_or(Error::<T>::ComputeRangeLiquidityError)?;
 
-				ensure!(
-					<Pallet<T> as HumidefiHelpers>::check_asset_balance(
-						humidefi_account_id.clone(),
-						asset_max_out,
-						asset_max_out_balance.into_inner(),
-					).is_ok(),
-					Error::<T>::CheckAssetSwapOutBalanceError
-				);
+		if liquidity.is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
 
-				<T::Fungibles as fungibles::Mutate<_>>::transfer(
-					asset_max_out,
-					&humidefi_account_id.clone(),
-					&who.clone(),
-					asset_max_out_balance.into_inner(),
-					frame_support::traits::tokens::Preservation::Expendable,
-				)?;
+		let liquidity_delta = i128::try_from(liquidity).map_err(|_| Error::<T>::ComputeRangeLiquidityError)?;
+
+		// Rounded up, so the position never backs more than it paid for.
+		let (amount_x, amount_y) = concentrated_math::amounts_for_liquidity(
+			concentrated_pool.sqrt_price,
+			sqrt_price_lower,
+			sqrt_price_upper,
+			liquidity,
+			true
+		).ok_or(Error::<T>::ComputeRangeLiquidityError)?;
+
+		let pool_account_id = Self::get_pool_account(asset_pair.clone());
+		for (asset, asset_balance, error) in [
+			(asset_pair.asset_x, amount_x, Error::<T>::CheckAssetXBalanceError),
+			(asset_pair.asset_y, amount_y, Error::<T>::CheckAssetYBalanceError),
+		] {
+			if asset_balance.is_zero() {
+				continue
+			}
 
-				let mut update_asset_x_balance = FixedU128::from_inner(0);
-				let mut update_asset_y_balance = FixedU128::from_inner(0);
+			ensure!(
+				Self::check_asset_balance(who.clone(), asset, asset_balance).is_ok(),
+				error
+			);
 
-				if asset_exact_in == liquidity_pool.asset_pair.asset_x {
-					update_asset_x_balance = liquidity_pool
-						.asset_x_balance
-						.add(FixedU128::from_inner(asset_exact_in_balance));
+			Self::transfer_pool_asset(
+				asset,
+				who.clone(),
+				pool_account_id.clone(),
+				asset_balance,
+			)?;
+		}
 
-					update_asset_y_balance = liquidity_pool
-						.asset_y_balance
-						.sub(asset_max_out_balance);
-				}
+		Pallet::<T>::update_tick(asset_pair.clone(), &concentrated_pool, lower_tick, liquidity_delta, false)?;
+		Pallet::<T>::update_tick(asset_pair.clone(), &concentrated_pool, upper_tick, liquidity_delta, true)?;
 
-				if asset_exact_in == liquidity_pool.asset_pair.asset_y {
-					update_asset_x_balance = liquidity_pool
-						.asset_x_balance
-						.sub(asset_max_out_balance);
+		if lower_tick <= concentrated_pool.tick && concentrated_pool.tick < upper_tick {
+			concentrated_pool.liquidity = concentrated_pool.liquidity
+				.checked_add(liquidity)
+				.ok_or(Error::<T>::ComputeRangeLiquidityError)?;
 
-					update_asset_y_balance = liquidity_pool
-						.asset_y_balance
-						.add(FixedU128::from_inner(asset_exact_in_balance));
-				}
+			ConcentratedPools::<T>::insert(asset_pair.clone(), concentrated_pool);
+		}
 
-				let update_price_result = <Pallet<T> as HumidefiHelpers>::compute_price(
-					update_asset_x_balance.into_inner(),
-					update_asset_y_balance.into_inner()
-				);
+		let (fee_growth_inside_x, fee_growth_inside_y) = Pallet::<T>::fee_growth_inside(
+			asset_pair.clone(),
+			&concentrated_pool,
+			lower_tick,
+			upper_tick
+		);
 
-				let update_price = match update_price_result {
-					Ok(price) => price,
-					Err(_) => return Err(Error::<T>::ComputePriceError.into()),
-				};
+		let account_liquidity_pool_payload = AccountLiquidityPool::<T> {
+			id: 1u64,
+			account_id: who.clone(),
+			asset_pair: asset_pair.clone(),
+			asset_x_balance: FixedU128::from_inner(amount_x),
+			asset_y_balance: FixedU128::from_inner(amount_y),
+			lp_token: liquidity_pool.lp_token,
+			lp_token_balance: FixedU128::from_inner(0),
+			range: Some(LiquidityRange {
+				lower_tick,
+				upper_tick,
+				liquidity,
+				fee_growth_inside_x,
+				fee_growth_inside_y,
+			}),
+		};
 
-				LiquidityPoolStorage::<T>::mutate(asset_pair.clone(), |query| {
-					let liquidity_pool_payload = LiquidityPool::<T> {
-						asset_pair: asset_pair.clone(),
-						asset_x_balance: update_asset_x_balance,
-						asset_y_balance: update_asset_y_balance,
-						price: update_price,
-						asset_x_fee: liquidity_pool.asset_x_fee,
-						asset_y_fee: liquidity_pool.asset_y_fee,
-						lp_token: liquidity_pool.lp_token,
-						lp_token_balance: liquidity_pool.lp_token_balance,
-					};
+		let position_id = Pallet::<T>::push_account_liquidity_pool(
+			who.clone(),
+			asset_pair.clone(),
+			account_liquidity_pool_payload
+		)?;
 
-					*query = Some(liquidity_pool_payload);
-				});
-			},
-			None => {
-				return Err(Error::<T>::LiquidityPoolDoesNotExists.into())
-			}
-		}
+		Pallet::<T>::deposit_event(Event::RangeLiquidityAdded {
+			who,
+			pair: asset_pair,
+			position_id,
+			lower_tick,
+			upper_tick,
+			liquidity,
+			amount_x,
+			amount_y,
+		});
 
 		Ok(())
 	}
 
-	fn swap_in_for_exact_out(
+	fn remove_range_liquidity(
 		who: Self::AccountId,
-		asset_exact_out: Self::AssetId,
-		asset_exact_out_balance: Self::AssetBalance,
-		asset_min_in: Self::AssetId,
+		asset_pair: Self::AssetPairs,
+		id: Self::AccountLiquidityPoolId,
 	) -> Result<(), DispatchError> {
-		let humidefi_account_id = <Pallet<T> as HumidefiHelpers>::get_dex_account();
-
-		ensure!(
-			<Pallet<T> as HumidefiHelpers>::check_asset_balance(
-				humidefi_account_id.clone(),
-				asset_exact_out,
-				asset_exact_out_balance,
-			).is_ok(),
-			Error::<T>::CheckAssetSwapOutBalanceError
-		);
+		let asset_pair = asset_pair.normalize();
+		let liquidity_pool = Self::get_liquidity_pool(asset_pair.clone())
+			.ok_or(Error::<T>::LiquidityPoolDoesNotExists)?;
+		Pallet::<T>::ensure_pool_status(&liquidity_pool, PoolStatus::SwapsPaused)?;
 
-		<T::Fungibles as fungibles::Mutate<_>>::transfer(
-			asset_exact_out,
-			&humidefi_account_id.clone(),
-			&who.clone(),
-			asset_exact_out_balance,
-			frame_support::traits::tokens::Preservation::Expendable,
+		let account_liquidity_pool = Self::get_account_liquidity_pool(
+			who.clone(),
+			asset_pair.clone(),
+			liquidity_pool.lp_token,
+			id
 		)?;
 
-		let asset_pair = AssetPairs::<T> { asset_x: asset_min_in, asset_y: asset_exact_out };
-		let get_liquidity_pool = <Pallet<T> as HumidefiHelpers>::get_liquidity_pool(asset_pair.clone());
-		match get_liquidity_pool {
-			Some(liquidity_pool) => {
-				let mut price = FixedU128::from_inner(0);
+		let range = account_liquidity_pool.range.ok_or(Error::<T>::NotRangePosition)?;
+		let mut concentrated_pool = ConcentratedPools::<T>::get(asset_pair.clone())
+			.ok_or(Error::<T>::NotConcentratedPool)?;
 
-				if asset_min_in == liquidity_pool.asset_pair.asset_x {
-					let price_result = <Pallet<T> as HumidefiHelpers>::compute_price(
-						liquidity_pool.asset_x_balance.into_inner(),
-						liquidity_pool.asset_y_balance.into_inner()
-					);
+		// Fees are whatever the range earned per unit of liquidity since the position was opened.
+		let (fee_growth_inside_x, fee_growth_inside_y) = Pallet::<T>::fee_growth_inside(
+			asset_pair.clone(),
+			&concentrated_pool,
+			range.lower_tick,
+			range.upper_tick
+		);
 
-					price = match price_result {
-						Ok(data) => data,
-						Err(_) => return Err(Error::<T>::ComputePriceError.into()),
-					};
-				}
+		let fee_x = multiply_by_rational_with_rounding(
+			fee_growth_inside_x.wrapping_sub(range.fee_growth_inside_x),
+			range.liquidity,
+			FixedU128::DIV,
+			Rounding::Down,
+		).ok_or(Error::<T>::ComputeFeeShareError)?;
+
+		let fee_y = multiply_by_rational_with_rounding(
+			fee_growth_inside_y.wrapping_sub(range.fee_growth_inside_y),
+			range.liquidity,
+			FixedU128::DIV,
+			Rounding::Down,
+		).ok_or(Error::<T>::ComputeFeeShareError)?;
+
+		let (amount_x, amount_y) = concentrated_math::amounts_for_liquidity(
+			concentrated_pool.sqrt_price,
+			concentrated_math::sqrt_price_at_tick(range.lower_tick).ok_or(Error::<T>::InvalidTickRange)?,
+			concentrated_math::sqrt_price_at_tick(range.upper_tick).ok_or(Error::<T>::InvalidTickRange)?,
+			range.liquidity,
+			false
+		).ok_or(Error::<T>::ComputeRangeLiquidityError)?;
+
+		let liquidity_delta = i128::try_from(range.liquidity)
+			.ok()
+			.and_then(|liquidity| liquidity.checked_neg())
+			.ok_or(Error::<T>::ComputeRangeLiquidityError)?;
+
+		Pallet::<T>::update_tick(asset_pair.clone(), &concentrated_pool, range.lower_tick, liquidity_delta, false)?;
+		Pallet::<T>::update_tick(asset_pair.clone(), &concentrated_pool, range.upper_tick, liquidity_delta, true)?;
+
+		if range.lower_tick <= concentrated_pool.tick && concentrated_pool.tick < range.upper_tick {
+			concentrated_pool.liquidity = concentrated_pool.liquidity
+				.checked_sub(range.liquidity)
+				.ok_or(Error::<T>::ComputeRangeLiquidityError)?;
+
+			ConcentratedPools::<T>::insert(asset_pair.clone(), concentrated_pool);
+		}
 
-				if asset_min_in == liquidity_pool.asset_pair.asset_y {
-					let price_result = <Pallet<T> as HumidefiHelpers>::compute_price(
-						liquidity_pool.asset_y_balance.into_inner(),
-						liquidity_pool.asset_x_balance.into_inner()
-					);
+		// Range positions hold no LP tokens, so settling zero of them closes the position.
+		Self::reduce_account_liquidity_pool(who.clone(), asset_pair.clone(), id, 0)?;
 
-					price = match price_result {
-						Ok(data) => data,
-						Err(_) => return Err(Error::<T>::ComputePriceError.into()),
-					};
-				}
+		let pool_account_id = Self::get_pool_account(asset_pair.clone());
+		for (asset, asset_balance) in [
+			(asset_pair.asset_x, amount_x.ensure_add(fee_x)?),
+			(asset_pair.asset_y, amount_y.ensure_add(fee_y)?),
+		] {
+			if asset_balance.is_zero() {
+				continue
+			}
 
-				let asset_min_in_balance = FixedU128::from_inner(price.into_inner()).mul(FixedU128::from_inner(asset_exact_out_balance));
+			Self::transfer_pool_asset(
+				asset,
+				pool_account_id.clone(),
+				who.clone(),
+				asset_balance,
+			)?;
+		}
 
-				ensure!(
-					<Pallet<T> as HumidefiHelpers>::check_asset_balance(
-						who.clone(),
-						asset_min_in,
-						asset_min_in_balance.into_inner(),
-					).is_ok(),
-					Error::<T>::CheckAssetSwapInBalanceError
-				);
+		Pallet::<T>::deposit_event(Event::RangeLiquidityRemoved {
+			who,
+			pair: asset_pair,
+			position_id: id,
+			liquidity: range.liquidity,
+			amount_x,
+			amount_y,
+			fee_x,
+			fee_y,
+		});
 
-				<T::Fungibles as fungibles::Mutate<_>>::transfer(
-					asset_min_in,
-					&who.clone(),
-					&humidefi_account_id.clone(),
-					asset_min_in_balance.into_inner(),
-					frame_support::traits::tokens::Preservation::Expendable,
+		Ok(())
+	}
+
+	fn redeem_liquidity(
+		who: Self::AccountId,
+		asset_pair: Self::AssetPairs,
+		lp_token: Self::AssetId,
+		id: Self::AccountLiquidityPoolId,
+	) -> Result<(), DispatchError> {
+		let account_liquidity_pool = Self::get_account_liquidity_pool(
+			who.clone(),
+			asset_pair.clone(),
+			lp_token,
+			id
+		)?;
+
+		<Pallet<T> as HumidefiCaller>::redeem_liquidity_partial(
+			who,
+			asset_pair,
+			lp_token,
+			id,
+			RedeemLiquidityAmount::<T>::LpToken(account_liquidity_pool.lp_token_balance.into_inner())
+		)
+	}
+
+	fn redeem_liquidity_partial(
+		who: Self::AccountId,
+		asset_pair: Self::AssetPairs,
+		lp_token: Self::AssetId,
+		id: Self::AccountLiquidityPoolId,
+		redeem_amount: Self::RedeemLiquidityAmount,
+	) -> Result<(), DispatchError> {
+		let account_liquidity_pool = Self::get_account_liquidity_pool(
+			who.clone(),
+			asset_pair.clone(),
+			lp_token,
+			id
+		)?;
+
+		ensure!(account_liquidity_pool.range.is_none(), Error::<T>::RangePosition);
+
+		let account_lp_token_balance = account_liquidity_pool.lp_token_balance.into_inner();
+		let redeem_lp_token_balance = match redeem_amount {
+			RedeemLiquidityAmount::LpToken(lp_token_balance) => lp_token_balance,
+			RedeemLiquidityAmount::Portion(portion) => portion.mul_floor(account_lp_token_balance),
+		};
+
+		if redeem_lp_token_balance.is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
+
+		ensure!(
+			redeem_lp_token_balance <= account_lp_token_balance,
+			Error::<T>::CheckAssetLiquidityPoolTokenBalanceError
+		);
+
+		let (asset_x_balance, asset_y_balance) = Self::withdraw_liquidity(
+			who.clone(),
+			asset_pair.clone(),
+			redeem_lp_token_balance
+		)?;
+
+		Self::reduce_account_liquidity_pool(
+			who.clone(),
+			asset_pair.clone(),
+			id,
+			redeem_lp_token_balance
+		)?;
+
+		Pallet::<T>::deposit_event(Event::LiquidityRedeemed {
+			who,
+			pair: asset_pair.normalize(),
+			amount_x: asset_x_balance,
+			amount_y: asset_y_balance,
+			lp_burned: redeem_lp_token_balance,
+			position_id: Some(id),
+		});
+
+		Ok(())
+	}
+
+	fn redeem_lp_token(
+		who: Self::AccountId,
+		asset_pair: Self::AssetPairs,
+		lp_token_balance: Self::AssetBalance,
+	) -> Result<(), DispatchError> {
+		if lp_token_balance.is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
+
+		let (asset_x_balance, asset_y_balance) = Self::withdraw_liquidity(
+			who.clone(),
+			asset_pair.clone(),
+			lp_token_balance
+		)?;
+
+		// Positions are only bookkeeping, the caller's oldest ones are settled first.
+		let account_liquidity_pools = Self::get_account_liquidity_pools(
+			who.clone(),
+			asset_pair.clone()
+		).unwrap_or_default();
+
+		let mut remaining_lp_token_balance = lp_token_balance;
+		for account_liquidity_pool in account_liquidity_pools {
+			if remaining_lp_token_balance.is_zero() {
+				break
+			}
+
+			if account_liquidity_pool.range.is_some() {
+				continue
+			}
+
+			let settled_lp_token_balance = remaining_lp_token_balance
+				.min(account_liquidity_pool.lp_token_balance.into_inner());
+
+			Self::reduce_account_liquidity_pool(
+				who.clone(),
+				asset_pair.clone(),
+				account_liquidity_pool.id,
+				settled_lp_token_balance
+			)?;
+
+			remaining_lp_token_balance = remaining_lp_token_balance.saturating_sub(settled_lp_token_balance);
+		}
+
+		Pallet::<T>::deposit_event(Event::LiquidityRedeemed {
+			who,
+			pair: asset_pair.normalize(),
+			amount_x: asset_x_balance,
+			amount_y: asset_y_balance,
+			lp_burned: lp_token_balance,
+			position_id: None,
+		});
+
+		Ok(())
+	}
+
+	fn convert_merged_lp_token(
+		who: Self::AccountId,
+		legacy_lp_token: Self::AssetId,
+		lp_token_balance: Self::AssetBalance,
+	) -> Result<(), DispatchError> {
+		if lp_token_balance.is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
+
+		let (asset_pair, lp_token_rate) = MergedLpTokens::<T>::get(legacy_lp_token)
+			.ok_or(Error::<T>::LpTokenNotMerged)?;
+
+		let liquidity_pool = Self::get_liquidity_pool(asset_pair)
+			.ok_or(Error::<T>::LiquidityPoolDoesNotExists)?;
+
+		// The LP tokens for every legacy holder were minted to the DEX account when the pools merged.
+		let converted_lp_token_balance = lp_token_rate.saturating_mul_int(lp_token_balance);
+		if converted_lp_token_balance.is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
+
+		<T::Fungibles as fungibles::Mutate<_>>::burn_from(
+			legacy_lp_token,
+			&who.clone(),
+			lp_token_balance,
+			frame_support::traits::tokens::Precision::Exact,
+			frame_support::traits::tokens::Fortitude::Polite,
+		)?;
+
+		<T::Fungibles as fungibles::Mutate<_>>::transfer(
+			liquidity_pool.lp_token,
+			&Self::get_dex_account(),
+			&who.clone(),
+			converted_lp_token_balance,
+			frame_support::traits::tokens::Preservation::Expendable,
+		)?;
+
+		Pallet::<T>::deposit_event(Event::MergedLpTokenConverted {
+			who,
+			legacy_lp_token,
+			lp_token: liquidity_pool.lp_token,
+			legacy_lp_burned: lp_token_balance,
+			lp_received: converted_lp_token_balance,
+		});
+
+		Ok(())
+	}
+
+	fn swap_exact_in_for_out(
+		who: Self::AccountId,
+		asset_exact_in: Self::AssetKind,
+		asset_exact_in_balance: Self::AssetBalance,
+		asset_max_out: Self::AssetKind,
+		min_amount_out: Self::AssetBalance,
+		deadline: Option<Self::BlockNumber>,
+	) -> Result<(), DispatchError> {
+		if let Some(deadline) = deadline {
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= deadline,
+				Error::<T>::DeadlineExpired
+			);
+		}
+
+		ensure!(asset_exact_in != asset_max_out, Error::<T>::IdenticalAssets);
+
+		let asset_pair = AssetPairs::<T>::new(asset_exact_in, asset_max_out);
+		let pool_account_id = Self::get_pool_account(asset_pair.clone());
+		let get_liquidity_pool = Self::get_liquidity_pool(asset_pair);
+		match get_liquidity_pool {
+			Some(liquidity_pool) => {
+				Pallet::<T>::ensure_pool_status(&liquidity_pool, PoolStatus::Active)?;
+
+				let is_asset_x_in = asset_exact_in == liquidity_pool.asset_pair.asset_x;
+
+				let (reserve_in, reserve_out) = if is_asset_x_in {
+					(liquidity_pool.asset_x_balance.into_inner(), liquidity_pool.asset_y_balance.into_inner())
+				} else {
+					(liquidity_pool.asset_y_balance.into_inner(), liquidity_pool.asset_x_balance.into_inner())
+				};
+
+				let swap_fee_result = Self::compute_swap_fee_exact_in(
+					liquidity_pool.asset_pair.clone(),
+					asset_exact_in_balance
+				);
+
+				let (swap_fee, asset_exact_in_balance_after_fee) = match swap_fee_result {
+					Ok(data) => data,
+					Err(_) => return Err(Error::<T>::ComputeSwapFeeError.into()),
+				};
+
+				let asset_max_out_balance = Self::compute_pool_amount_out(
+					asset_exact_in,
+					asset_max_out,
+					reserve_in,
+					reserve_out,
+					asset_exact_in_balance_after_fee
+				)?;
+
+				ensure!(
+					asset_max_out_balance >= min_amount_out,
+					Error::<T>::SlippageExceeded
+				);
+
+				let (reserve_amount_in, reserve_amount_out, reserve_swap_fee) = Pallet::<T>::apply_swap_to_pool(
+					&liquidity_pool,
+					asset_exact_in,
+					asset_exact_in_balance_after_fee,
+					asset_max_out_balance,
+					true,
+					swap_fee
+				)?;
+
+				let (
+					update_asset_x_balance,
+					update_asset_y_balance,
+					update_asset_x_fee,
+					update_asset_y_fee,
+				) = if is_asset_x_in {
+					(
+						liquidity_pool.asset_x_balance.add(FixedU128::from_inner(reserve_amount_in.saturating_add(reserve_swap_fee))),
+						liquidity_pool.asset_y_balance.sub(FixedU128::from_inner(reserve_amount_out)),
+						liquidity_pool.asset_x_fee.add(FixedU128::from_inner(reserve_swap_fee)),
+						liquidity_pool.asset_y_fee,
+					)
+				} else {
+					(
+						liquidity_pool.asset_x_balance.sub(FixedU128::from_inner(reserve_amount_out)),
+						liquidity_pool.asset_y_balance.add(FixedU128::from_inner(reserve_amount_in.saturating_add(reserve_swap_fee))),
+						liquidity_pool.asset_x_fee,
+						liquidity_pool.asset_y_fee.add(FixedU128::from_inner(reserve_swap_fee)),
+					)
+				};
+
+				Self::check_pool_invariant(
+					liquidity_pool.asset_pair.clone(),
+					liquidity_pool.asset_x_balance.into_inner(),
+					liquidity_pool.asset_y_balance.into_inner(),
+					update_asset_x_balance.into_inner(),
+					update_asset_y_balance.into_inner()
+				)?;
+
+				let update_price_result = Self::compute_pool_price(
+					liquidity_pool.asset_pair.clone(),
+					update_asset_x_balance.into_inner(),
+					update_asset_y_balance.into_inner()
+				);
+
+				let update_price = match update_price_result {
+					Ok(price) => price,
+					Err(_) => return Err(Error::<T>::ComputePriceError.into()),
+				};
+
+				Pallet::<T>::check_price_move(&liquidity_pool, update_price)?;
+				Pallet::<T>::update_price_accumulator(&liquidity_pool);
+
+				ensure!(
+					Self::check_asset_balance(
+						who.clone(),
+						asset_exact_in,
+						asset_exact_in_balance,
+					).is_ok(),
+					Error::<T>::CheckAssetSwapInBalanceError
+				);
+
+				Self::transfer_pool_asset(
+					asset_exact_in,
+					who.clone(),
+					pool_account_id.clone(),
+					asset_exact_in_balance,
+				)?;
+
+				ensure!(
+					Self::check_asset_balance(
+						pool_account_id.clone(),
+						asset_max_out,
+						asset_max_out_balance,
+					).is_ok(),
+					Error::<T>::CheckAssetSwapOutBalanceError
+				);
+
+				Self::transfer_pool_asset(
+					asset_max_out,
+					pool_account_id.clone(),
+					who.clone(),
+					asset_max_out_balance,
+				)?;
+
+				LiquidityPoolStorage::<T>::mutate(liquidity_pool.asset_pair.clone(), |query| {
+					let liquidity_pool_payload = LiquidityPool::<T> {
+						asset_pair: liquidity_pool.asset_pair.clone(),
+						asset_x_balance: update_asset_x_balance,
+						asset_y_balance: update_asset_y_balance,
+						price: update_price,
+						asset_x_fee: update_asset_x_fee,
+						asset_y_fee: update_asset_y_fee,
+						swap_fee: liquidity_pool.swap_fee,
+						lp_token: liquidity_pool.lp_token,
+						lp_token_balance: liquidity_pool.lp_token_balance,
+						status: liquidity_pool.status,
+					};
+
+					*query = Some(liquidity_pool_payload);
+				});
+
+				Pallet::<T>::deposit_event(Event::Swapped {
+					who,
+					asset_in: asset_exact_in,
+					amount_in: asset_exact_in_balance,
+					asset_out: asset_max_out,
+					amount_out: asset_max_out_balance,
+					fee: swap_fee,
+				});
+			},
+			None => {
+				return Err(Error::<T>::LiquidityPoolDoesNotExists.into())
+			}
+		}
+
+		Ok(())
+	}
+
+	fn swap_in_for_exact_out(
+		who: Self::AccountId,
+		asset_exact_out: Self::AssetKind,
+		asset_exact_out_balance: Self::AssetBalance,
+		asset_min_in: Self::AssetKind,
+		max_amount_in: Self::AssetBalance,
+		deadline: Option<Self::BlockNumber>,
+	) -> Result<(), DispatchError> {
+		if let Some(deadline) = deadline {
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= deadline,
+				Error::<T>::DeadlineExpired
+			);
+		}
+
+		ensure!(asset_min_in != asset_exact_out, Error::<T>::IdenticalAssets);
+
+		let asset_pair = AssetPairs::<T>::new(asset_min_in, asset_exact_out);
+		let pool_account_id = Self::get_pool_account(asset_pair.clone());
+		let get_liquidity_pool = Self::get_liquidity_pool(asset_pair);
+		match get_liquidity_pool {
+			Some(liquidity_pool) => {
+				Pallet::<T>::ensure_pool_status(&liquidity_pool, PoolStatus::Active)?;
+
+				let is_asset_x_in = asset_min_in == liquidity_pool.asset_pair.asset_x;
+
+				let (reserve_in, reserve_out) = if is_asset_x_in {
+					(liquidity_pool.asset_x_balance.into_inner(), liquidity_pool.asset_y_balance.into_inner())
+				} else {
+					(liquidity_pool.asset_y_balance.into_inner(), liquidity_pool.asset_x_balance.into_inner())
+				};
+
+				let asset_min_in_balance = Self::compute_pool_amount_in(
+					asset_min_in,
+					asset_exact_out,
+					reserve_in,
+					reserve_out,
+					asset_exact_out_balance
+				)?;
+
+				let swap_fee_result = Self::compute_swap_fee_exact_out(
+					liquidity_pool.asset_pair.clone(),
+					asset_min_in_balance
+				);
+
+				let (swap_fee, asset_min_in_balance_with_fee) = match swap_fee_result {
+					Ok(data) => data,
+					Err(_) => return Err(Error::<T>::ComputeSwapFeeError.into()),
+				};
+
+				ensure!(
+					asset_min_in_balance_with_fee <= max_amount_in,
+					Error::<T>::SlippageExceeded
+				);
+
+				let (reserve_amount_in, reserve_amount_out, reserve_swap_fee) = Pallet::<T>::apply_swap_to_pool(
+					&liquidity_pool,
+					asset_min_in,
+					asset_min_in_balance,
+					asset_exact_out_balance,
+					false,
+					swap_fee
+				)?;
+
+				let (
+					update_asset_x_balance,
+					update_asset_y_balance,
+					update_asset_x_fee,
+					update_asset_y_fee,
+				) = if is_asset_x_in {
+					(
+						liquidity_pool.asset_x_balance.add(FixedU128::from_inner(reserve_amount_in.saturating_add(reserve_swap_fee))),
+						liquidity_pool.asset_y_balance.sub(FixedU128::from_inner(reserve_amount_out)),
+						liquidity_pool.asset_x_fee.add(FixedU128::from_inner(reserve_swap_fee)),
+						liquidity_pool.asset_y_fee,
+					)
+				} else {
+					(
+						liquidity_pool.asset_x_balance.sub(FixedU128::from_inner(reserve_amount_out)),
+						liquidity_pool.asset_y_balance.add(FixedU128::from_inner(reserve_amount_in.saturating_add(reserve_swap_fee))),
+						liquidity_pool.asset_x_fee,
+						liquidity_pool.asset_y_fee.add(FixedU128::from_inner(reserve_swap_fee)),
+					)
+				};
+
+				Self::check_pool_invariant(
+					liquidity_pool.asset_pair.clone(),
+					liquidity_pool.asset_x_balance.into_inner(),
+					liquidity_pool.asset_y_balance.into_inner(),
+					update_asset_x_balance.into_inner(),
+					update_asset_y_balance.into_inner()
+				)?;
+
+				let update_price_result = Self::compute_pool_price(
+					liquidity_pool.asset_pair.clone(),
+					update_asset_x_balance.into_inner(),
+					update_asset_y_balance.into_inner()
+				);
+
+				let update_price = match update_price_result {
+					Ok(price) => price,
+					Err(_) => return Err(Error::<T>::ComputePriceError.into()),
+				};
+
+				Pallet::<T>::check_price_move(&liquidity_pool, update_price)?;
+				Pallet::<T>::update_price_accumulator(&liquidity_pool);
+
+				ensure!(
+					Self::check_asset_balance(
+						who.clone(),
+						asset_min_in,
+						asset_min_in_balance_with_fee,
+					).is_ok(),
+					Error::<T>::CheckAssetSwapInBalanceError
+				);
+
+				Self::transfer_pool_asset(
+					asset_min_in,
+					who.clone(),
+					pool_account_id.clone(),
+					asset_min_in_balance_with_fee,
+				)?;
+
+				ensure!(
+					Self::check_asset_balance(
+						pool_account_id.clone(),
+						asset_exact_out,
+						asset_exact_out_balance,
+					).is_ok(),
+					Error::<T>::CheckAssetSwapOutBalanceError
+				);
+
+				Self::transfer_pool_asset(
+					asset_exact_out,
+					pool_account_id.clone(),
+					who.clone(),
+					asset_exact_out_balance,
 				)?;
 
-				let mut update_asset_x_balance = FixedU128::from_inner(0);
-				let mut update_asset_y_balance = FixedU128::from_inner(0);
+				LiquidityPoolStorage::<T>::mutate(liquidity_pool.asset_pair.clone(), |query| {
+					let liquidity_pool_payload = LiquidityPool::<T> {
+						asset_pair: liquidity_pool.asset_pair.clone(),
+						asset_x_balance: update_asset_x_balance,
+						asset_y_balance: update_asset_y_balance,
+						price: update_price,
+						asset_x_fee: update_asset_x_fee,
+						asset_y_fee: update_asset_y_fee,
+						swap_fee: liquidity_pool.swap_fee,
+						lp_token: liquidity_pool.lp_token,
+						lp_token_balance: liquidity_pool.lp_token_balance,
+						status: liquidity_pool.status,
+					};
+
+					*query = Some(liquidity_pool_payload);
+				});
+
+				Pallet::<T>::deposit_event(Event::Swapped {
+					who,
+					asset_in: asset_min_in,
+					amount_in: asset_min_in_balance_with_fee,
+					asset_out: asset_exact_out,
+					amount_out: asset_exact_out_balance,
+					fee: swap_fee,
+				});
+			},
+			None => {
+				return Err(Error::<T>::LiquidityPoolDoesNotExists.into())
+			}
+		}
+
+		Ok(())
+	}
+
+	fn swap_exact_in_for_out_path(
+		who: Self::AccountId,
+		path: Vec<Self::AssetKind>,
+		asset_exact_in_balance: Self::AssetBalance,
+		min_amount_out: Self::AssetBalance,
+		deadline: Option<Self::BlockNumber>,
+	) -> Result<(), DispatchError> {
+		if let Some(deadline) = deadline {
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= deadline,
+				Error::<T>::DeadlineExpired
+			);
+		}
+
+		let amounts = Self::compute_swap_exact_in_path(
+			path.clone(),
+			asset_exact_in_balance
+		)?;
+
+		let amount_out = amounts.last().copied().ok_or(Error::<T>::InvalidSwapPath)?;
+		ensure!(amount_out >= min_amount_out, Error::<T>::SlippageExceeded);
+
+		for (index, assets) in path.windows(2).enumerate() {
+			<Pallet<T> as HumidefiCaller>::swap_exact_in_for_out(
+				who.clone(),
+				assets[0],
+				amounts[index],
+				assets[1],
+				amounts[index + 1],
+				None
+			)?;
+		}
+
+		Ok(())
+	}
+
+	fn swap_in_for_exact_out_path(
+		who: Self::AccountId,
+		path: Vec<Self::AssetKind>,
+		asset_exact_out_balance: Self::AssetBalance,
+		max_amount_in: Self::AssetBalance,
+		deadline: Option<Self::BlockNumber>,
+	) -> Result<(), DispatchError> {
+		if let Some(deadline) = deadline {
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <= deadline,
+				Error::<T>::DeadlineExpired
+			);
+		}
+
+		let amounts = Self::compute_swap_exact_out_path(
+			path.clone(),
+			asset_exact_out_balance
+		)?;
+
+		let amount_in = amounts.first().copied().ok_or(Error::<T>::InvalidSwapPath)?;
+		ensure!(amount_in <= max_amount_in, Error::<T>::SlippageExceeded);
+
+		for (index, assets) in path.windows(2).enumerate() {
+			<Pallet<T> as HumidefiCaller>::swap_in_for_exact_out(
+				who.clone(),
+				assets[1],
+				amounts[index + 1],
+				assets[0],
+				amounts[index],
+				None
+			)?;
+		}
+
+		Ok(())
+	}
+
+	fn transfer_asset(
+		who: Self::AccountId,
+		asset: Self::AssetId,
+		asset_balance: Self::AssetBalance,
+		account_id: Self::AccountId,
+	) -> Result<(), DispatchError> {
+		ensure!(
+			Self::check_asset_balance(
+				who.clone(),
+				NativeOrAssetId::Asset(asset),
+				asset_balance,
+			).is_ok(),
+			Error::<T>::CheckAssetBalanceError
+		);
+
+		<T::Fungibles as fungibles::Mutate<_>>::transfer(
+			asset,
+			&who.clone(),
+			&account_id.clone(),
+			asset_balance,
+			frame_support::traits::tokens::Preservation::Expendable,
+		)?;
+
+		Pallet::<T>::deposit_event(Event::AssetTransferred {
+			from: who,
+			to: account_id,
+			asset,
+			amount: asset_balance,
+		});
+
+		Ok(())
+	}
+
+	fn place_limit_order(
+		who: Self::AccountId,
+		asset_in: Self::AssetKind,
+		amount_in: Self::AssetBalance,
+		asset_out: Self::AssetKind,
+		limit_price: FixedU128,
+		expiry: Self::BlockNumber,
+	) -> Result<Self::LimitOrderId, DispatchError> {
+		ensure!(asset_in != asset_out, Error::<T>::IdenticalAssets);
+
+		if amount_in.is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
+
+		ensure!(!limit_price.is_zero(), Error::<T>::InvalidLimitPrice);
+		ensure!(frame_system::Pallet::<T>::block_number() <= expiry, Error::<T>::DeadlineExpired);
+
+		ensure!(
+			Self::get_liquidity_pool(AssetPairs::<T>::new(asset_in, asset_out)).is_some(),
+			Error::<T>::LiquidityPoolDoesNotExists
+		);
+
+		ensure!(
+			Self::check_asset_balance(who.clone(), asset_in, amount_in).is_ok(),
+			Error::<T>::CheckAssetSwapInBalanceError
+		);
+
+		let id = NextLimitOrderId::<T>::get();
+		NextLimitOrderId::<T>::put(id.checked_add(1).ok_or(Error::<T>::LimitOrderIdError)?);
+
+		AccountLimitOrders::<T>::try_mutate(who.clone(), |limit_order_ids| {
+			limit_order_ids.try_push(id).map_err(|_| Error::<T>::TooManyLimitOrders)
+		})?;
+
+		// Like a pool account, the escrow account is kept alive by the pallet instead of a deposit.
+		let escrow_account_id = Self::get_escrow_account();
+		if frame_system::Pallet::<T>::providers(&escrow_account_id).is_zero() {
+			frame_system::Pallet::<T>::inc_providers(&escrow_account_id);
+		}
+
+		Self::transfer_pool_asset(asset_in, who.clone(), escrow_account_id, amount_in)?;
+
+		LimitOrders::<T>::insert(id, LimitOrder {
+			owner: who.clone(),
+			asset_in,
+			amount_in,
+			asset_out,
+			limit_price,
+			expiry,
+		});
+
+		Pallet::<T>::deposit_event(Event::LimitOrderPlaced {
+			id,
+			owner: who,
+			asset_in,
+			amount_in,
+			asset_out,
+			limit_price,
+			expiry,
+		});
+
+		Ok(id)
+	}
+
+	fn cancel_limit_order(
+		who: Self::AccountId,
+		id: Self::LimitOrderId,
+	) -> Result<(), DispatchError> {
+		let limit_order = LimitOrders::<T>::get(id).ok_or(Error::<T>::LimitOrderDoesNotExist)?;
+		ensure!(limit_order.owner == who, Error::<T>::NotLimitOrderOwner);
+
+		Pallet::<T>::close_limit_order(id, &limit_order)?;
+
+		Pallet::<T>::deposit_event(Event::LimitOrderCancelled {
+			id,
+			owner: who,
+			amount_in: limit_order.amount_in,
+		});
+
+		Ok(())
+	}
+
+	fn schedule_dca(
+		who: Self::AccountId,
+		asset_in: Self::AssetKind,
+		asset_out: Self::AssetKind,
+		amount_per_period: Self::AssetBalance,
+		max_slippage: Perbill,
+		period: Self::BlockNumber,
+		runs: u32,
+	) -> Result<Self::DcaScheduleId, DispatchError> {
+		ensure!(asset_in != asset_out, Error::<T>::IdenticalAssets);
+
+		if amount_per_period.is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
+
+		ensure!(!period.is_zero() && !runs.is_zero(), Error::<T>::InvalidDcaSchedule);
+
+		ensure!(
+			Self::get_liquidity_pool(AssetPairs::<T>::new(asset_in, asset_out)).is_some(),
+			Error::<T>::LiquidityPoolDoesNotExists
+		);
+
+		let escrowed = amount_per_period.ensure_mul(runs.into())?;
+		ensure!(
+			Self::check_asset_balance(who.clone(), asset_in, escrowed).is_ok(),
+			Error::<T>::CheckAssetSwapInBalanceError
+		);
+
+		let id = NextDcaScheduleId::<T>::get();
+		NextDcaScheduleId::<T>::put(id.checked_add(1).ok_or(Error::<T>::DcaScheduleIdError)?);
+
+		AccountDcaSchedules::<T>::try_mutate(who.clone(), |dca_schedule_ids| {
+			dca_schedule_ids.try_push(id).map_err(|_| Error::<T>::TooManyDcaSchedules)
+		})?;
+
+		let next_run = Pallet::<T>::schedule_dca_run(
+			id,
+			frame_system::Pallet::<T>::block_number().saturating_add(1u32.into())
+		)?;
+
+		let escrow_account_id = Self::get_escrow_account();
+		if frame_system::Pallet::<T>::providers(&escrow_account_id).is_zero() {
+			frame_system::Pallet::<T>::inc_providers(&escrow_account_id);
+		}
+
+		Self::transfer_pool_asset(asset_in, who.clone(), escrow_account_id, escrowed)?;
+
+		DcaSchedules::<T>::insert(id, DcaSchedule {
+			owner: who.clone(),
+			asset_in,
+			asset_out,
+			amount_per_period,
+			max_slippage,
+			period,
+			remaining_runs: runs,
+			escrowed,
+			next_run,
+		});
+
+		Pallet::<T>::deposit_event(Event::DcaScheduled {
+			id,
+			owner: who,
+			asset_in,
+			asset_out,
+			amount_per_period,
+			max_slippage,
+			period,
+			runs,
+		});
+
+		Ok(id)
+	}
+
+	fn cancel_dca(
+		who: Self::AccountId,
+		id: Self::DcaScheduleId,
+	) -> Result<(), DispatchError> {
+		let dca_schedule = DcaSchedules::<T>::get(id).ok_or(Error::<T>::DcaScheduleDoesNotExist)?;
+		ensure!(dca_schedule.owner == who, Error::<T>::NotDcaScheduleOwner);
+
+		Pallet::<T>::close_dca_schedule(id, &dca_schedule)?;
+
+		Pallet::<T>::deposit_event(Event::DcaCancelled {
+			id,
+			owner: who,
+			refunded: dca_schedule.escrowed,
+		});
+
+		Ok(())
+	}
+}
+
+impl<T: Config> PriceOracle for Pallet<T> {
+	type AssetKind = NativeOrAssetIdOf<T>;
+	type BlockNumber = BlockNumberFor<T>;
+
+	// Price of `asset_a` in `asset_b` as of the pool's last reserve change.
+	fn spot_price(
+		asset_a: Self::AssetKind,
+		asset_b: Self::AssetKind,
+	) -> Option<FixedU128> {
+		let (reserve_a, reserve_b) = <Pallet<T> as HumidefiInspect>::reserves(asset_a, asset_b)?;
+		if reserve_a.is_zero() || reserve_b.is_zero() {
+			return None;
+		}
+
+		let asset_pair = AssetPairs::<T>::new(asset_a, asset_b);
+		match PoolKinds::<T>::get(asset_pair.clone()) {
+			PoolKind::ConstantProduct => Some(FixedU128::from_rational(reserve_b, reserve_a)),
+			// Off-balance reserves no longer give the marginal price, so read it off the curve.
+			_ => {
+				let price = Self::get_liquidity_pool(asset_pair.clone())?.price;
+				if asset_a == asset_pair.asset_x { Some(price) } else { price.reciprocal() }
+			},
+		}
+	}
+
+	fn twap(
+		asset_a: Self::AssetKind,
+		asset_b: Self::AssetKind,
+		window: Self::BlockNumber,
+	) -> Option<FixedU128> {
+		Pallet::<T>::twap(asset_a, asset_b, window)
+	}
+}
+
+impl<T: Config> HumidefiInspect for Pallet<T> {
+	type AssetBalance = AssetBalanceOf<T>;
+
+	// Reserves of the pool in the order the assets were given.
+	fn reserves(
+		asset_a: Self::AssetKind,
+		asset_b: Self::AssetKind,
+	) -> Option<(Self::AssetBalance, Self::AssetBalance)> {
+		if asset_a == asset_b {
+			return None;
+		}
+
+		let liquidity_pool = Self::get_liquidity_pool(AssetPairs::<T>::new(asset_a, asset_b))?;
+		let (asset_x_balance, asset_y_balance) = (
+			liquidity_pool.asset_x_balance.into_inner(),
+			liquidity_pool.asset_y_balance.into_inner(),
+		);
+
+		if asset_a == liquidity_pool.asset_pair.asset_x {
+			Some((asset_x_balance, asset_y_balance))
+		} else {
+			Some((asset_y_balance, asset_x_balance))
+		}
+	}
+
+	// Amount of `asset_out` received for selling exactly `amount_in`, after the swap fee.
+	fn quote(
+		asset_in: Self::AssetKind,
+		asset_out: Self::AssetKind,
+		amount_in: Self::AssetBalance,
+	) -> Option<Self::AssetBalance> {
+		if asset_in == asset_out {
+			return None;
+		}
+
+		Self::compute_swap_exact_in(asset_in, asset_out, amount_in).ok()
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	pub fn get_dex_account() -> T::AccountId {
+		HUMIDEFI.into_account_truncating()
+	}
+
+	pub fn get_pool_account(asset_pair: AssetPairs<T>) -> T::AccountId {
+		HUMIDEFI.into_sub_account_truncating(asset_pair.pool_id())
+	}
+
+	pub fn get_escrow_account() -> T::AccountId {
+		HUMIDEFI.into_sub_account_truncating(b"escrow")
+	}
+
+	pub(crate) fn get_asset_balance(
+		asset: NativeOrAssetIdOf<T>,
+		account_id: T::AccountId,
+	) -> AssetBalanceOf<T> {
+		match asset {
+			NativeOrAssetId::Native => <T::NativeBalance as fungible::Inspect<_>>::balance(&account_id),
+			NativeOrAssetId::Asset(asset) => <T::Fungibles as fungibles::Inspect<_>>::balance(asset, &account_id),
+		}
+	}
+
+	pub(crate) fn transfer_pool_asset(
+		asset: NativeOrAssetIdOf<T>,
+		source: T::AccountId,
+		dest: T::AccountId,
+		asset_balance: AssetBalanceOf<T>,
+	) -> Result<(), DispatchError> {
+		match asset {
+			NativeOrAssetId::Native => {
+				<T::NativeBalance as fungible::Mutate<_>>::transfer(
+					&source,
+					&dest,
+					asset_balance,
+					frame_support::traits::tokens::Preservation::Expendable,
+				)?;
+			},
+			NativeOrAssetId::Asset(asset) => {
+				<T::Fungibles as fungibles::Mutate<_>>::transfer(
+					asset,
+					&source,
+					&dest,
+					asset_balance,
+					frame_support::traits::tokens::Preservation::Expendable,
+				)?;
+			},
+		}
+
+		Ok(())
+	}
+
+	pub(crate) fn get_liquidity_pool(
+		asset_pair: AssetPairs<T>
+	) -> Option<LiquidityPool<T>> {
+		LiquidityPoolStorage::<T>::get(asset_pair.normalize())
+	}
+
+	pub(crate) fn get_account_liquidity_pools(
+		account_id: T::AccountId,
+		asset_pair: AssetPairs<T>,
+	) -> Option<BoundedVec<AccountLiquidityPool<T>, ConstU32<100>>> {
+		let storage_key = (account_id, asset_pair.normalize());
+		AccountLiquidityPoolStorage::<T>::get(storage_key)
+	}
+
+	pub(crate) fn get_account_liquidity_pool(
+		account_id: T::AccountId,
+		asset_pair: AssetPairs<T>,
+		lp_token: AssetIdOf<T>,
+		id: AccountLiquidityPoolId,
+	) -> Result<AccountLiquidityPool<T>, DispatchError> {
+		let account_liquidity_pools = Self::get_account_liquidity_pools(account_id, asset_pair)
+			.ok_or(Error::<T>::AccountLiquidityPoolDoesNotExists)?;
+
+		let account_liquidity_pool = account_liquidity_pools
+			.into_iter()
+			.find(|account_liquidity_pool| account_liquidity_pool.id == id)
+			.ok_or(Error::<T>::AccountLiquidityPoolIdDoesNotExists)?;
+
+		ensure!(
+			account_liquidity_pool.lp_token == lp_token,
+			Error::<T>::AccountLiquidityPoolTokenMismatch
+		);
+
+		Ok(account_liquidity_pool)
+	}
+
+	pub(crate) fn check_asset_balance(
+		account_id: T::AccountId,
+		asset: NativeOrAssetIdOf<T>,
+		asset_balance: AssetBalanceOf<T>,
+	) -> Result<(), DispatchError> {
+		let current_asset_balance = Self::get_asset_balance(asset, account_id.clone());
+
+		current_asset_balance
+			.ensure_sub(asset_balance)
+			.map_err(|_| Error::<T>::AssetDoesNotHaveEnoughBalance)?;
+
+		Ok(())
+	}
+
+	pub(crate) fn withdraw_liquidity(
+		account_id: T::AccountId,
+		asset_pair: AssetPairs<T>,
+		lp_token_balance: AssetBalanceOf<T>,
+	) -> Result<(AssetBalanceOf<T>, AssetBalanceOf<T>), DispatchError> {
+		let liquidity_pool = Self::get_liquidity_pool(asset_pair.clone())
+			.ok_or(Error::<T>::LiquidityPoolDoesNotExists)?;
+		Pallet::<T>::ensure_pool_status(&liquidity_pool, PoolStatus::SwapsPaused)?;
+		Pallet::<T>::update_price_accumulator(&liquidity_pool);
+
+		let pool_asset_pair = liquidity_pool.asset_pair;
+		let lp_token = liquidity_pool.lp_token;
+
+		// Whoever holds the LP tokens owns the share, regardless of who deposited.
+		ensure!(
+			Self::check_asset_balance(
+				account_id.clone(),
+				NativeOrAssetId::Asset(lp_token),
+				lp_token_balance,
+			).is_ok(),
+			Error::<T>::CheckAssetLiquidityPoolTokenBalanceError
+		);
+
+		let asset_xy_balances_result = Self::compute_redeem_assets(
+			asset_pair.clone(),
+			lp_token_balance
+		);
+
+		let (asset_x_balance, asset_y_balance, lp_token_balance) = match asset_xy_balances_result {
+			Ok(asset_xy_balances) => asset_xy_balances,
+			Err(_) => return Err(Error::<T>::ComputeXYBalancesError.into()),
+		};
+
+		let pool_account_id = Self::get_pool_account(pool_asset_pair.clone());
+
+		ensure!(
+			Self::check_asset_balance(
+				pool_account_id.clone(),
+				pool_asset_pair.clone().asset_x,
+				asset_x_balance,
+			).is_ok(),
+			Error::<T>::CheckAssetXBalanceError
+		);
+
+		ensure!(
+			Self::check_asset_balance(
+				pool_account_id.clone(),
+				pool_asset_pair.clone().asset_y,
+				asset_y_balance,
+			).is_ok(),
+			Error::<T>::CheckAssetYBalanceError
+		);
+
+		// A native remainder below the existential deposit would be dusted out of the pool account.
+		for (asset, asset_balance) in [
+			(pool_asset_pair.asset_x, asset_x_balance),
+			(pool_asset_pair.asset_y, asset_y_balance),
+		] {
+			if asset == NativeOrAssetId::Native {
+				let remaining_balance = Self::get_asset_balance(asset, pool_account_id.clone())
+					.saturating_sub(asset_balance);
+				ensure!(
+					remaining_balance.is_zero() ||
+						remaining_balance >= <T::NativeBalance as fungible::Inspect<_>>::minimum_balance(),
+					Error::<T>::NativeReserveBelowExistentialDeposit
+				);
+			}
+		}
+
+		Self::transfer_pool_asset(
+			pool_asset_pair.clone().asset_x,
+			pool_account_id.clone(),
+			account_id.clone(),
+			asset_x_balance,
+		)?;
+
+		Self::transfer_pool_asset(
+			pool_asset_pair.clone().asset_y,
+			pool_account_id.clone(),
+			account_id.clone(),
+			asset_y_balance,
+		)?;
+
+		<T::Fungibles as fungibles::Mutate<_>>::burn_from(
+			lp_token,
+			&account_id.clone(),
+			lp_token_balance,
+			frame_support::traits::tokens::Precision::Exact,
+			frame_support::traits::tokens::Fortitude::Polite,
+		)?;
+
+		let is_drained = LiquidityPoolStorage::<T>::mutate(pool_asset_pair.clone(), |query| {
+			if let Some(mutate_liquidity_pool) = query {
+				let update_asset_x_balance = mutate_liquidity_pool
+					.asset_x_balance
+					.sub(FixedU128::from_inner(asset_x_balance));
+
+				let update_asset_y_balance = mutate_liquidity_pool
+					.asset_y_balance
+					.sub(FixedU128::from_inner(asset_y_balance));
+
+				let update_price_result = Self::compute_pool_price(
+					pool_asset_pair.clone(),
+					update_asset_x_balance.into_inner(),
+					update_asset_y_balance.into_inner()
+				);
+
+				// A fully drained pool has no spot price until liquidity is added again.
+				let update_price = match update_price_result {
+					Ok(price) => price,
+					Err(_) => FixedU128::from_inner(0),
+				};
+
+				let update_lp_token_balance = mutate_liquidity_pool
+					.lp_token_balance
+					.sub(FixedU128::from_inner(lp_token_balance));
+
+				let liquidity_pool_payload = LiquidityPool::<T> {
+					asset_pair: pool_asset_pair.clone(),
+					asset_x_balance: update_asset_x_balance,
+					asset_y_balance: update_asset_y_balance,
+					price: update_price,
+					asset_x_fee: mutate_liquidity_pool.asset_x_fee,
+					asset_y_fee: mutate_liquidity_pool.asset_y_fee,
+					swap_fee: mutate_liquidity_pool.swap_fee,
+					lp_token: mutate_liquidity_pool.lp_token,
+					lp_token_balance: update_lp_token_balance,
+					status: mutate_liquidity_pool.status,
+				};
+
+				*query = Some(liquidity_pool_payload);
+
+				return update_lp_token_balance.is_zero()
+			}
+
+			false
+		});
+
+		// The last LP tokens take the whole pool with them, so the pool account gives up its provider.
+		if is_drained {
+			frame_system::Pallet::<T>::dec_providers(&pool_account_id)?;
+		}
+
+		Ok((asset_x_balance, asset_y_balance))
+	}
+
+	pub(crate) fn reduce_account_liquidity_pool(
+		account_id: T::AccountId,
+		asset_pair: AssetPairs<T>,
+		id: AccountLiquidityPoolId,
+		lp_token_balance: AssetBalanceOf<T>,
+	) -> Result<(), DispatchError> {
+		let storage_key = (account_id, asset_pair.normalize());
+		AccountLiquidityPoolStorage::<T>::try_mutate_exists(storage_key, |query| -> DispatchResult {
+			let account_liquidity_pools = query
+				.as_mut()
+				.ok_or(Error::<T>::AccountLiquidityPoolDoesNotExists)?;
+
+			let index = account_liquidity_pools
+				.iter()
+				.position(|account_liquidity_pool| account_liquidity_pool.id == id)
+				.ok_or(Error::<T>::AccountLiquidityPoolIdDoesNotExists)?;
+
+			let account_liquidity_pool = &mut account_liquidity_pools[index];
+			let account_lp_token_balance = account_liquidity_pool.lp_token_balance.into_inner();
+
+			ensure!(
+				lp_token_balance <= account_lp_token_balance,
+				Error::<T>::CheckAssetLiquidityPoolTokenBalanceError
+			);
+
+			if lp_token_balance == account_lp_token_balance {
+				account_liquidity_pools.remove(index);
+			} else {
+				// The position keeps the part of its recorded deposits that was not redeemed.
+				let redeem_asset_x_balance = multiply_by_rational_with_rounding(
+					account_liquidity_pool.asset_x_balance.into_inner(),
+					lp_token_balance,
+					account_lp_token_balance,
+					Rounding::Down,
+				).ok_or(Error::<T>::ComputeXYBalancesError)?;
+
+				let redeem_asset_y_balance = multiply_by_rational_with_rounding(
+					account_liquidity_pool.asset_y_balance.into_inner(),
+					lp_token_balance,
+					account_lp_token_balance,
+					Rounding::Down,
+				).ok_or(Error::<T>::ComputeXYBalancesError)?;
+
+				account_liquidity_pool.asset_x_balance = account_liquidity_pool
+					.asset_x_balance
+					.sub(FixedU128::from_inner(redeem_asset_x_balance));
+
+				account_liquidity_pool.asset_y_balance = account_liquidity_pool
+					.asset_y_balance
+					.sub(FixedU128::from_inner(redeem_asset_y_balance));
+
+				account_liquidity_pool.lp_token_balance = account_liquidity_pool
+					.lp_token_balance
+					.sub(FixedU128::from_inner(lp_token_balance));
+			}
+
+			if account_liquidity_pools.is_empty() {
+				*query = None;
+			}
+
+			Ok(())
+		})
+	}
+
+	pub(crate) fn compute_and_mint_lp_token(
+		asset_pair: AssetPairs<T>,
+		asset_x_balance: AssetBalanceOf<T>,
+		asset_y_balance: AssetBalanceOf<T>,
+	) -> Result<(AssetIdOf<T>, AssetBalanceOf<T>), DispatchError> {
+		let mut lp_token: AssetIdOf<T> = 1u32;
+		let humidefi_account_id = Self::get_dex_account();
+
+		let lp_token_balance = Self::compute_lp_token(
+			asset_pair.clone(),
+			asset_x_balance,
+			asset_y_balance,
+		)?;
+
+		match Self::get_liquidity_pool(asset_pair) {
+			Some(liquidity_pool) => {
+				lp_token = liquidity_pool.lp_token;
+			},
+			None => {
+				loop {
+					if !<T::Fungibles as fungibles::Inspect<_>>::asset_exists(lp_token) {
+						<T::Fungibles as fungibles::Create<_>>::create(
+							lp_token,
+							humidefi_account_id.clone(),
+							true,
+							1u128,
+						)?;
+
+						break;
+					}
+
+					lp_token += 1;
+				}
+			},
+		}
+
+		<T::Fungibles as fungibles::Mutate<_>>::mint_into(
+			lp_token,
+			&humidefi_account_id.clone(),
+			lp_token_balance,
+		)?;
+
+		Ok((lp_token, lp_token_balance))
+	}
+
+	pub(crate) fn compute_deposit_amounts(
+		asset_pair: AssetPairs<T>,
+		asset_x_balance: AssetBalanceOf<T>,
+		asset_y_balance: AssetBalanceOf<T>,
+	) -> Result<(AssetBalanceOf<T>, AssetBalanceOf<T>), DispatchError> {
+		let liquidity_pool = match Self::get_liquidity_pool(asset_pair.clone()) {
+			Some(liquidity_pool) if !liquidity_pool.lp_token_balance.is_zero()
+				&& !liquidity_pool.asset_x_balance.is_zero()
+				&& !liquidity_pool.asset_y_balance.is_zero() => liquidity_pool,
+			_ => return Ok((asset_x_balance, asset_y_balance)),
+		};
+
+		// StableSwap pools mint for the growth of their invariant and take unbalanced deposits whole.
+		match PoolKinds::<T>::get(asset_pair.normalize()) {
+			PoolKind::ConstantProduct | PoolKind::Weighted { .. } | PoolKind::Concentrated { .. } => {},
+			_ => return Ok((asset_x_balance, asset_y_balance)),
+		}
+
+		let asset_x_reserve = liquidity_pool.asset_x_balance.into_inner();
+		let asset_y_reserve = liquidity_pool.asset_y_balance.into_inner();
+
+		// Rounded up, so the pool never gives out a larger share than it takes in.
+		let optimal_asset_y_balance = multiply_by_rational_with_rounding(
+			asset_x_balance,
+			asset_y_reserve,
+			asset_x_reserve,
+			Rounding::Up,
+		).ok_or(Error::<T>::ComputeAndMintLiquidityPoolTokenError)?;
+
+		if optimal_asset_y_balance <= asset_y_balance {
+			return Ok((asset_x_balance, optimal_asset_y_balance));
+		}
+
+		let optimal_asset_x_balance = multiply_by_rational_with_rounding(
+			asset_y_balance,
+			asset_x_reserve,
+			asset_y_reserve,
+			Rounding::Up,
+		).ok_or(Error::<T>::ComputeAndMintLiquidityPoolTokenError)?;
+
+		Ok((optimal_asset_x_balance.min(asset_x_balance), asset_y_balance))
+	}
+
+	pub(crate) fn compute_lp_token(
+		asset_pair: AssetPairs<T>,
+		asset_x_balance: AssetBalanceOf<T>,
+		asset_y_balance: AssetBalanceOf<T>,
+	) -> Result<AssetBalanceOf<T>, DispatchError> {
+		let asset_pair_key = asset_pair.normalize();
+		let lp_token_balance = match Self::get_liquidity_pool(asset_pair) {
+			Some(liquidity_pool) if !liquidity_pool.lp_token_balance.is_zero()
+				&& !liquidity_pool.asset_x_balance.is_zero()
+				&& !liquidity_pool.asset_y_balance.is_zero() => {
+				// Later deposits mint in proportion to the share of the reserves they add.
+				let lp_token_balance_x = multiply_by_rational_with_rounding(
+					asset_x_balance,
+					liquidity_pool.lp_token_balance.into_inner(),
+					liquidity_pool.asset_x_balance.into_inner(),
+					Rounding::Down,
+				).ok_or(Error::<T>::ComputeAndMintLiquidityPoolTokenError)?;
+
+				let lp_token_balance_y = multiply_by_rational_with_rounding(
+					asset_y_balance,
+					liquidity_pool.lp_token_balance.into_inner(),
+					liquidity_pool.asset_y_balance.into_inner(),
+					Rounding::Down,
+				).ok_or(Error::<T>::ComputeAndMintLiquidityPoolTokenError)?;
+
+				match PoolKinds::<T>::get(asset_pair_key) {
+					PoolKind::ConstantProduct | PoolKind::Weighted { .. } | PoolKind::Concentrated { .. } => {
+						lp_token_balance_x.min(lp_token_balance_y)
+					},
+					// Mint for the growth of the invariant, so unbalanced deposits are not cut to the smaller side.
+					PoolKind::StableSwap { amplification } => {
+						let asset_x_reserve = liquidity_pool.asset_x_balance.into_inner();
+						let asset_y_reserve = liquidity_pool.asset_y_balance.into_inner();
+
+						let d = stable_swap::compute_d(asset_x_reserve, asset_y_reserve, amplification)
+							.ok_or(Error::<T>::ComputeAndMintLiquidityPoolTokenError)?;
+						let update_d = stable_swap::compute_d(
+							asset_x_reserve.ensure_add(asset_x_balance)?,
+							asset_y_reserve.ensure_add(asset_y_balance)?,
+							amplification
+						).ok_or(Error::<T>::ComputeAndMintLiquidityPoolTokenError)?;
+
+						multiply_by_rational_with_rounding(
+							liquidity_pool.lp_token_balance.into_inner(),
+							update_d.ensure_sub(d)?,
+							d,
+							Rounding::Down,
+						).ok_or(Error::<T>::ComputeAndMintLiquidityPoolTokenError)?
+					},
+				}
+			},
+			_ => match PoolKinds::<T>::get(asset_pair_key) {
+				// LP tokens of a concentrated pool are its full range liquidity.
+				PoolKind::ConstantProduct | PoolKind::Concentrated { .. } => {
+					FixedU128::from_inner(asset_x_balance)
+						.mul(FixedU128::from_inner(asset_y_balance))
+						.sqrt()
+						.into_inner()
+				},
+				// The first deposit mints the invariant itself, which is the pool's total value when balanced.
+				PoolKind::StableSwap { amplification } => {
+					stable_swap::compute_d(asset_x_balance, asset_y_balance, amplification)
+						.ok_or(Error::<T>::ComputeAndMintLiquidityPoolTokenError)?
+				},
+				PoolKind::Weighted { weight_x } => {
+					weighted_math::compute_invariant(asset_x_balance, asset_y_balance, weight_x)
+						.ok_or(Error::<T>::ComputeAndMintLiquidityPoolTokenError)?
+				},
+			},
+		};
+
+		if lp_token_balance.is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
+
+		Ok(lp_token_balance)
+	}
+
+	pub(crate) fn compute_price(
+		asset_x_balance: AssetBalanceOf<T>,
+		asset_y_balance: AssetBalanceOf<T>,
+	) -> Result<FixedU128, DispatchError> {
+		if FixedU128::from_inner(asset_x_balance).is_zero() || FixedU128::from_inner(asset_y_balance).is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
+
+		let price = FixedU128::from_rational(asset_y_balance, asset_x_balance);
+		Ok(price)
+	}
+
+	pub(crate) fn compute_pool_price(
+		asset_pair: AssetPairs<T>,
+		asset_x_balance: AssetBalanceOf<T>,
+		asset_y_balance: AssetBalanceOf<T>,
+	) -> Result<FixedU128, DispatchError> {
+		match PoolKinds::<T>::get(asset_pair.normalize()) {
+			PoolKind::ConstantProduct => Self::compute_price(asset_x_balance, asset_y_balance),
+			PoolKind::StableSwap { amplification } => {
+				stable_swap::compute_price(asset_x_balance, asset_y_balance, amplification)
+					.ok_or(Error::<T>::ComputePriceError.into())
+			},
+			PoolKind::Weighted { weight_x } => {
+				if asset_x_balance.is_zero() || asset_y_balance.is_zero() {
+					return Err(Error::<T>::CannotBeZero.into())
+				}
+
+				weighted_math::compute_price(asset_x_balance, asset_y_balance, weight_x)
+					.ok_or(Error::<T>::ComputePriceError.into())
+			},
+			// The reserves only approximate the price once ranges have taken part in swaps.
+			PoolKind::Concentrated { .. } => match ConcentratedPools::<T>::get(asset_pair.normalize()) {
+				Some(concentrated_pool) => {
+					concentrated_pool.sqrt_price
+						.ensure_mul(concentrated_pool.sqrt_price)
+						.map_err(|_| Error::<T>::ComputePriceError.into())
+				},
+				None => Self::compute_price(asset_x_balance, asset_y_balance),
+			},
+		}
+	}
+
+	pub(crate) fn compute_xy_assets(
+		account_id: T::AccountId,
+		asset_pair: AssetPairs<T>,
+		lp_token: AssetIdOf<T>,
+		id: AccountLiquidityPoolId,
+	) -> Result<(AssetBalanceOf<T>, AssetBalanceOf<T>, AssetBalanceOf<T>), DispatchError> {
+		let account_liquidity_pool = Self::get_account_liquidity_pool(
+			account_id,
+			asset_pair.clone(),
+			lp_token,
+			id
+		)?;
+
+		Self::compute_redeem_assets(asset_pair, account_liquidity_pool.lp_token_balance.into_inner())
+	}
+
+	pub(crate) fn compute_redeem_assets(
+		asset_pair: AssetPairs<T>,
+		lp_token_balance: AssetBalanceOf<T>,
+	) -> Result<(AssetBalanceOf<T>, AssetBalanceOf<T>, AssetBalanceOf<T>), DispatchError> {
+		let lp_token_balance = FixedU128::from_inner(lp_token_balance);
+
+		let liquidity_pool = Self::get_liquidity_pool(asset_pair.clone())
+			.ok_or(Error::<T>::LiquidityPoolDoesNotExists)?;
+
+		if liquidity_pool.lp_token_balance.is_zero() || lp_token_balance.is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
+
+		ensure!(
+			lp_token_balance <= liquidity_pool.lp_token_balance,
+			Error::<T>::CheckAssetLiquidityPoolTokenBalanceError
+		);
+
+		// Every LP token is a claim on an equal slice of both reserves.
+		let get_asset_x_balance = multiply_by_rational_with_rounding(
+			liquidity_pool.asset_x_balance.into_inner(),
+			lp_token_balance.into_inner(),
+			liquidity_pool.lp_token_balance.into_inner(),
+			Rounding::Down,
+		).ok_or(Error::<T>::ComputeXYBalancesError)?;
+
+		let get_asset_y_balance = multiply_by_rational_with_rounding(
+			liquidity_pool.asset_y_balance.into_inner(),
+			lp_token_balance.into_inner(),
+			liquidity_pool.lp_token_balance.into_inner(),
+			Rounding::Down,
+		).ok_or(Error::<T>::ComputeXYBalancesError)?;
+
+		let get_lp_token_balance = lp_token_balance;
+
+		Ok((
+			get_asset_x_balance,
+			get_asset_y_balance,
+			get_lp_token_balance.into_inner()
+		))
+	}
+
+	pub(crate) fn compute_swap_fee_exact_in(
+		asset_pair: AssetPairs<T>,
+		asset_balance: AssetBalanceOf<T>,
+	) -> Result<(AssetBalanceOf<T>, AssetBalanceOf<T>), DispatchError> {
+		let liquidity_pool = Self::get_liquidity_pool(asset_pair)
+			.ok_or(Error::<T>::LiquidityPoolDoesNotExists)?;
+
+		let swap_fee = liquidity_pool.swap_fee.mul_ceil(asset_balance);
+		let asset_balance_after_fee = asset_balance
+			.ensure_sub(swap_fee)
+			.map_err(|_| Error::<T>::ComputeSwapFeeError)?;
+
+		Ok((swap_fee, asset_balance_after_fee))
+	}
+
+	pub(crate) fn compute_swap_fee_exact_out(
+		asset_pair: AssetPairs<T>,
+		asset_balance: AssetBalanceOf<T>,
+	) -> Result<(AssetBalanceOf<T>, AssetBalanceOf<T>), DispatchError> {
+		let liquidity_pool = Self::get_liquidity_pool(asset_pair)
+			.ok_or(Error::<T>::LiquidityPoolDoesNotExists)?;
+
+		let fee_complement = Perbill::one().saturating_sub(liquidity_pool.swap_fee);
+		if fee_complement.deconstruct() == 0 {
+			return Err(Error::<T>::ComputeSwapFeeError.into())
+		}
+
+		// The input has to cover the fee as well, so gross it up by `1 / (1 - fee)`, rounded in the
+		// pool's favour.
+		let asset_balance_with_fee = multiply_by_rational_with_rounding(
+			asset_balance,
+			u128::from(Perbill::ACCURACY),
+			u128::from(fee_complement.deconstruct()),
+			Rounding::Up,
+		).ok_or(Error::<T>::ComputeSwapFeeError)?;
+
+		let swap_fee = asset_balance_with_fee
+			.ensure_sub(asset_balance)
+			.map_err(|_| Error::<T>::ComputeSwapFeeError)?;
+
+		Ok((swap_fee, asset_balance_with_fee))
+	}
+
+	pub(crate) fn compute_amount_out(
+		reserve_in: AssetBalanceOf<T>,
+		reserve_out: AssetBalanceOf<T>,
+		amount_in: AssetBalanceOf<T>,
+	) -> Result<AssetBalanceOf<T>, DispatchError> {
+		if reserve_in.is_zero() || reserve_out.is_zero() || amount_in.is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
+
+		// x * y = k  =>  amount_out = reserve_out * amount_in / (reserve_in + amount_in)
+		let update_reserve_in = reserve_in
+			.ensure_add(amount_in)
+			.map_err(|_| Error::<T>::ComputeSwapAmountError)?;
+
+		let amount_out = multiply_by_rational_with_rounding(
+			reserve_out,
+			amount_in,
+			update_reserve_in,
+			Rounding::Down,
+		).ok_or(Error::<T>::ComputeSwapAmountError)?;
+
+		if amount_out.is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
+
+		Ok(amount_out)
+	}
+
+	pub(crate) fn compute_amount_in(
+		reserve_in: AssetBalanceOf<T>,
+		reserve_out: AssetBalanceOf<T>,
+		amount_out: AssetBalanceOf<T>,
+	) -> Result<AssetBalanceOf<T>, DispatchError> {
+		if reserve_in.is_zero() || reserve_out.is_zero() || amount_out.is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
+
+		ensure!(amount_out < reserve_out, Error::<T>::InsufficientLiquidity);
+
+		// x * y = k  =>  amount_in = reserve_in * amount_out / (reserve_out - amount_out)
+		let update_reserve_out = reserve_out
+			.ensure_sub(amount_out)
+			.map_err(|_| Error::<T>::ComputeSwapAmountError)?;
+
+		let amount_in = multiply_by_rational_with_rounding(
+			reserve_in,
+			amount_out,
+			update_reserve_out,
+			Rounding::Up,
+		).ok_or(Error::<T>::ComputeSwapAmountError)?;
+
+		Ok(amount_in)
+	}
+
+	pub(crate) fn compute_swap_exact_in(
+		asset_in: NativeOrAssetIdOf<T>,
+		asset_out: NativeOrAssetIdOf<T>,
+		amount_in: AssetBalanceOf<T>,
+	) -> Result<AssetBalanceOf<T>, DispatchError> {
+		let asset_pair = AssetPairs::<T>::new(asset_in, asset_out);
+		let liquidity_pool = Self::get_liquidity_pool(asset_pair)
+			.ok_or(Error::<T>::LiquidityPoolDoesNotExists)?;
+
+		let (reserve_in, reserve_out) = if asset_in == liquidity_pool.asset_pair.asset_x {
+			(liquidity_pool.asset_x_balance.into_inner(), liquidity_pool.asset_y_balance.into_inner())
+		} else {
+			(liquidity_pool.asset_y_balance.into_inner(), liquidity_pool.asset_x_balance.into_inner())
+		};
+
+		let (_, amount_in_after_fee) = Self::compute_swap_fee_exact_in(
+			liquidity_pool.asset_pair.clone(),
+			amount_in
+		)?;
+
+		Self::compute_pool_amount_out(
+			asset_in,
+			asset_out,
+			reserve_in,
+			reserve_out,
+			amount_in_after_fee
+		)
+	}
+
+	pub(crate) fn compute_swap_exact_out(
+		asset_in: NativeOrAssetIdOf<T>,
+		asset_out: NativeOrAssetIdOf<T>,
+		amount_out: AssetBalanceOf<T>,
+	) -> Result<AssetBalanceOf<T>, DispatchError> {
+		let asset_pair = AssetPairs::<T>::new(asset_in, asset_out);
+		let liquidity_pool = Self::get_liquidity_pool(asset_pair)
+			.ok_or(Error::<T>::LiquidityPoolDoesNotExists)?;
+
+		let (reserve_in, reserve_out) = if asset_in == liquidity_pool.asset_pair.asset_x {
+			(liquidity_pool.asset_x_balance.into_inner(), liquidity_pool.asset_y_balance.into_inner())
+		} else {
+			(liquidity_pool.asset_y_balance.into_inner(), liquidity_pool.asset_x_balance.into_inner())
+		};
+
+		let amount_in = Self::compute_pool_amount_in(
+			asset_in,
+			asset_out,
+			reserve_in,
+			reserve_out,
+			amount_out
+		)?;
+
+		let (_, amount_in_with_fee) = Self::compute_swap_fee_exact_out(
+			liquidity_pool.asset_pair.clone(),
+			amount_in
+		)?;
+
+		Ok(amount_in_with_fee)
+	}
+
+	pub(crate) fn compute_swap_exact_in_path(
+		path: Vec<NativeOrAssetIdOf<T>>,
+		amount_in: AssetBalanceOf<T>,
+	) -> Result<Vec<AssetBalanceOf<T>>, DispatchError> {
+		Self::check_swap_path(&path)?;
+
+		let mut amounts = Vec::with_capacity(path.len());
+		amounts.push(amount_in);
+
+		let mut amount = amount_in;
+		for assets in path.windows(2) {
+			amount = Self::compute_swap_exact_in(assets[0], assets[1], amount)?;
+			amounts.push(amount);
+		}
+
+		Ok(amounts)
+	}
+
+	pub(crate) fn compute_swap_exact_out_path(
+		path: Vec<NativeOrAssetIdOf<T>>,
+		amount_out: AssetBalanceOf<T>,
+	) -> Result<Vec<AssetBalanceOf<T>>, DispatchError> {
+		Self::check_swap_path(&path)?;
+
+		// Walk the path backwards so every hop knows how much it has to deliver.
+		let mut amounts = Vec::with_capacity(path.len());
+		amounts.push(amount_out);
+
+		let mut amount = amount_out;
+		for assets in path.windows(2).rev() {
+			amount = Self::compute_swap_exact_out(assets[0], assets[1], amount)?;
+			amounts.push(amount);
+		}
+
+		amounts.reverse();
+		Ok(amounts)
+	}
+
+	pub(crate) fn check_constant_product(
+		asset_x_balance: AssetBalanceOf<T>,
+		asset_y_balance: AssetBalanceOf<T>,
+		update_asset_x_balance: AssetBalanceOf<T>,
+		update_asset_y_balance: AssetBalanceOf<T>,
+	) -> Result<(), DispatchError> {
+		if update_asset_x_balance.is_zero() || update_asset_y_balance.is_zero() {
+			return Err(Error::<T>::ConstantProductInvariantError.into())
+		}
+
+		// x * y <= x' * y' without overflowing u128: ceil(x * y / x') <= y'
+		let minimum_asset_y_balance = multiply_by_rational_with_rounding(
+			asset_x_balance,
+			asset_y_balance,
+			update_asset_x_balance,
+			Rounding::Up,
+		).ok_or(Error::<T>::ConstantProductInvariantError)?;
+
+		ensure!(
+			minimum_asset_y_balance <= update_asset_y_balance,
+			Error::<T>::ConstantProductInvariantError
+		);
+
+		Ok(())
+	}
+
+	pub(crate) fn compute_pool_amount_out(
+		asset_in: NativeOrAssetIdOf<T>,
+		asset_out: NativeOrAssetIdOf<T>,
+		reserve_in: AssetBalanceOf<T>,
+		reserve_out: AssetBalanceOf<T>,
+		amount_in: AssetBalanceOf<T>,
+	) -> Result<AssetBalanceOf<T>, DispatchError> {
+		let asset_pair = AssetPairs::<T>::new(asset_in, asset_out);
+		let pool_kind = PoolKinds::<T>::get(asset_pair.clone());
+		if pool_kind == PoolKind::ConstantProduct {
+			return Self::compute_amount_out(reserve_in, reserve_out, amount_in)
+		}
+
+		if reserve_in.is_zero() || reserve_out.is_zero() || amount_in.is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
+
+		let amount_out = match pool_kind {
+			PoolKind::StableSwap { amplification } => {
+				stable_swap::compute_amount_out(reserve_in, reserve_out, amount_in, amplification)
+			},
+			PoolKind::Weighted { weight_x } => {
+				let (weight_in, weight_out) = if asset_in == asset_pair.asset_x {
+					(weight_x, weight_x.left_from_one())
+				} else {
+					(weight_x.left_from_one(), weight_x)
+				};
+
+				weighted_math::compute_amount_out(reserve_in, reserve_out, amount_in, weight_in, weight_out)
+			},
+			PoolKind::Concentrated { .. } => Some(
+				Pallet::<T>::concentrated_swap(asset_pair, asset_in, reserve_in, reserve_out, amount_in, true, None)?.amount_out
+			),
+			PoolKind::ConstantProduct => None,
+		}.ok_or(Error::<T>::ComputeSwapAmountError)?;
+
+		if amount_out.is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
+
+		Ok(amount_out)
+	}
+
+	pub(crate) fn compute_pool_amount_in(
+		asset_in: NativeOrAssetIdOf<T>,
+		asset_out: NativeOrAssetIdOf<T>,
+		reserve_in: AssetBalanceOf<T>,
+		reserve_out: AssetBalanceOf<T>,
+		amount_out: AssetBalanceOf<T>,
+	) -> Result<AssetBalanceOf<T>, DispatchError> {
+		let asset_pair = AssetPairs::<T>::new(asset_in, asset_out);
+		let pool_kind = PoolKinds::<T>::get(asset_pair.clone());
+		if pool_kind == PoolKind::ConstantProduct {
+			return Self::compute_amount_in(reserve_in, reserve_out, amount_out)
+		}
+
+		if reserve_in.is_zero() || reserve_out.is_zero() || amount_out.is_zero() {
+			return Err(Error::<T>::CannotBeZero.into())
+		}
+
+		// Range positions can sell more than the reserves hold.
+		if !matches!(pool_kind, PoolKind::Concentrated { .. }) {
+			ensure!(amount_out < reserve_out, Error::<T>::InsufficientLiquidity);
+		}
+
+		match pool_kind {
+			PoolKind::StableSwap { amplification } => {
+				stable_swap::compute_amount_in(reserve_in, reserve_out, amount_out, amplification)
+			},
+			PoolKind::Weighted { weight_x } => {
+				let (weight_in, weight_out) = if asset_in == asset_pair.asset_x {
+					(weight_x, weight_x.left_from_one())
+				} else {
+					(weight_x.left_from_one(), weight_x)
+				};
+
+				weighted_math::compute_amount_in(reserve_in, reserve_out, amount_out, weight_in, weight_out)
+			},
+			PoolKind::Concentrated { .. } => Some(
+				Pallet::<T>::concentrated_swap(asset_pair, asset_in, reserve_in, reserve_out, amount_out, false, None)?.amount_in
+			),
+			PoolKind::ConstantProduct => None,
+		}.ok_or(Error::<T>::ComputeSwapAmountError.into())
+	}
+
+	pub(crate) fn check_pool_invariant(
+		asset_pair: AssetPairs<T>,
+		asset_x_balance: AssetBalanceOf<T>,
+		asset_y_balance: AssetBalanceOf<T>,
+		update_asset_x_balance: AssetBalanceOf<T>,
+		update_asset_y_balance: AssetBalanceOf<T>,
+	) -> Result<(), DispatchError> {
+		match PoolKinds::<T>::get(asset_pair.normalize()) {
+			PoolKind::ConstantProduct => Self::check_constant_product(
+				asset_x_balance,
+				asset_y_balance,
+				update_asset_x_balance,
+				update_asset_y_balance
+			),
+			PoolKind::StableSwap { amplification } => {
+				if update_asset_x_balance.is_zero() || update_asset_y_balance.is_zero() {
+					return Err(Error::<T>::ConstantProductInvariantError.into())
+				}
+
+				let d = stable_swap::compute_d(asset_x_balance, asset_y_balance, amplification)
+					.ok_or(Error::<T>::ConstantProductInvariantError)?;
+				let update_d = stable_swap::compute_d(update_asset_x_balance, update_asset_y_balance, amplification)
+					.ok_or(Error::<T>::ConstantProductInvariantError)?;
+
+				ensure!(d <= update_d, Error::<T>::ConstantProductInvariantError);
+
+				Ok(())
+			},
+			PoolKind::Weighted { weight_x } => {
+				if update_asset_x_balance.is_zero() || update_asset_y_balance.is_zero() {
+					return Err(Error::<T>::ConstantProductInvariantError.into())
+				}
+
+				let invariant_holds = weighted_math::invariant_holds(
+					asset_x_balance,
+					asset_y_balance,
+					update_asset_x_balance,
+					update_asset_y_balance,
+					weight_x
+				).ok_or(Error::<T>::ConstantProductInvariantError)?;
+
+				ensure!(invariant_holds, Error::<T>::ConstantProductInvariantError);
+
+				Ok(())
+			},
+			// Swaps follow the pool's square root price rather than the reserves' own ratio, so
+			// `apply_swap_to_pool` checks their virtual reserves against the price before and after.
+			// The fee only adds to that, and neither reserve may be emptied.
+			PoolKind::Concentrated { .. } => {
+				if update_asset_x_balance.is_zero() || update_asset_y_balance.is_zero() {
+					return Err(Error::<T>::ConstantProductInvariantError.into())
+				}
+
+				ensure!(
+					update_asset_x_balance >= asset_x_balance || update_asset_y_balance >= asset_y_balance,
+					Error::<T>::ConstantProductInvariantError
+				);
+
+				Ok(())
+			},
+		}
+	}
+
+	/// The stricter of the pool's own status and the pallet-wide one.
+	pub fn pool_status(liquidity_pool: &LiquidityPool<T>) -> PoolStatus {
+		liquidity_pool.status.max(GlobalPoolStatus::<T>::get())
+	}
+
+	pub fn ensure_pool_status(liquidity_pool: &LiquidityPool<T>, allowed: PoolStatus) -> DispatchResult {
+		ensure!(Self::pool_status(liquidity_pool) <= allowed, Error::<T>::PoolPaused);
+		Ok(())
+	}
+
+	/// Rejects a swap that would move the pool's price by more than `MaxPriceMovePerBlock` since
+	/// the start of the block. Pausing the pool afterwards is left to `PauseOrigin`.
+	pub fn check_price_move(liquidity_pool: &LiquidityPool<T>, price: FixedU128) -> DispatchResult {
+		let max_price_move = T::MaxPriceMovePerBlock::get();
+		if max_price_move.deconstruct() == 0 {
+			return Ok(());
+		}
+
+		let asset_pair = liquidity_pool.asset_pair.clone();
+		let block_number = frame_system::Pallet::<T>::block_number();
+		let opening_price = match BlockOpeningPrice::<T>::get(asset_pair.clone()) {
+			Some((opening_block_number, opening_price)) if opening_block_number == block_number => opening_price,
+			_ => liquidity_pool.price,
+		};
+
+		let price_move = if price > opening_price {
+			price.sub(opening_price)
+		} else {
+			opening_price.sub(price)
+		};
+
+		ensure!(
+			price_move.into_inner() <= max_price_move.mul_floor(opening_price.into_inner()),
+			Error::<T>::CircuitBreakerTripped
+		);
+
+		BlockOpeningPrice::<T>::insert(asset_pair, (block_number, opening_price));
+
+		Ok(())
+	}
+
+	pub(crate) fn set_pool_status(asset_pair: AssetPairs<T>, status: PoolStatus) -> DispatchResult {
+		let asset_pair = asset_pair.normalize();
 
-				if asset_min_in == liquidity_pool.asset_pair.asset_x {
-					update_asset_x_balance = liquidity_pool
-						.asset_x_balance
-						.add(asset_min_in_balance);
+		LiquidityPoolStorage::<T>::try_mutate(asset_pair.clone(), |query| -> DispatchResult {
+			let liquidity_pool = query.as_mut().ok_or(Error::<T>::LiquidityPoolDoesNotExists)?;
+			liquidity_pool.status = status;
+			Ok(())
+		})?;
 
-					update_asset_y_balance = liquidity_pool
-						.asset_y_balance
-						.sub(FixedU128::from_inner(asset_exact_out_balance));
-				}
+		Self::deposit_event(Event::PoolStatusUpdated { pair: asset_pair, status });
+		Ok(())
+	}
+
+	/// Carries the pool's accumulator forward to the current block. Must be called before its
+	/// reserves change, so each block is weighted by the price it started with.
+	pub fn update_price_accumulator(liquidity_pool: &LiquidityPool<T>) {
+		let asset_pair = liquidity_pool.asset_pair.clone();
+		let block_number = frame_system::Pallet::<T>::block_number();
+
+		let price_accumulator = match PriceAccumulators::<T>::get(asset_pair.clone()) {
+			Some(price_accumulator) if price_accumulator.block_number >= block_number => return,
+			Some(price_accumulator) => Self::accumulate_price(price_accumulator, liquidity_pool, block_number),
+			None => PriceAccumulator { block_number, ..Default::default() },
+		};
+
+		PriceAccumulators::<T>::insert(asset_pair.clone(), price_accumulator);
 
-				if asset_min_in == liquidity_pool.asset_pair.asset_y {
-					update_asset_x_balance = liquidity_pool
-						.asset_x_balance
-						.sub(FixedU128::from_inner(asset_exact_out_balance));
+		PriceObservations::<T>::mutate(asset_pair, |price_observations| {
+			let is_due = price_observations.last().map_or(true, |last_observation| {
+				block_number.saturating_sub(last_observation.block_number) >= T::PriceObservationPeriod::get()
+			});
 
-					update_asset_y_balance = liquidity_pool
-						.asset_y_balance
-						.add(asset_min_in_balance);
+			if is_due {
+				if price_observations.len() as u32 >= T::MaxPriceObservations::get() {
+					price_observations.remove(0);
 				}
 
-				let update_price_result = <Pallet<T> as HumidefiHelpers>::compute_price(
-					update_asset_x_balance.into_inner(),
-					update_asset_y_balance.into_inner()
-				);
+				let _ = price_observations.try_push(price_accumulator);
+			}
+		});
+	}
 
-				let update_price = match update_price_result {
-					Ok(price) => price,
-					Err(_) => return Err(Error::<T>::ComputePriceError.into()),
-				};
+	fn accumulate_price(
+		price_accumulator: PriceAccumulator<BlockNumberFor<T>>,
+		liquidity_pool: &LiquidityPool<T>,
+		block_number: BlockNumberFor<T>,
+	) -> PriceAccumulator<BlockNumberFor<T>> {
+		let elapsed: u128 = block_number.saturating_sub(price_accumulator.block_number).saturated_into();
+
+		let inverse_price = if liquidity_pool.asset_y_balance.into_inner() == 0 {
+			FixedU128::from_inner(0)
+		} else if PoolKinds::<T>::get(liquidity_pool.asset_pair.normalize()) == PoolKind::ConstantProduct {
+			FixedU128::from_rational(
+				liquidity_pool.asset_x_balance.into_inner(),
+				liquidity_pool.asset_y_balance.into_inner()
+			)
+		} else {
+			liquidity_pool.price.reciprocal().unwrap_or(FixedU128::from_inner(0))
+		};
 
-				LiquidityPoolStorage::<T>::mutate(asset_pair.clone(), |query| {
-					let liquidity_pool_payload = LiquidityPool::<T> {
-						asset_pair: asset_pair.clone(),
-						asset_x_balance: update_asset_x_balance,
-						asset_y_balance: update_asset_y_balance,
-						price: update_price,
-						asset_x_fee: liquidity_pool.asset_x_fee,
-						asset_y_fee: liquidity_pool.asset_y_fee,
-						lp_token: liquidity_pool.lp_token,
-						lp_token_balance: liquidity_pool.lp_token_balance,
-					};
+		PriceAccumulator {
+			block_number: block_number.max(price_accumulator.block_number),
+			price_cumulative: price_accumulator.price_cumulative
+				.wrapping_add(liquidity_pool.price.into_inner().wrapping_mul(elapsed)),
+			inverse_price_cumulative: price_accumulator.inverse_price_cumulative
+				.wrapping_add(inverse_price.into_inner().wrapping_mul(elapsed)),
+		}
+	}
 
-					*query = Some(liquidity_pool_payload);
-				});
-			},
-			None => {
-				return Err(Error::<T>::LiquidityPoolDoesNotExists.into())
+	/// Time-weighted average price of `asset_a` in `asset_b` over at least the last `window`
+	/// blocks, measured from the newest observation that is `window` or more blocks old.
+	pub fn twap(
+		asset_a: NativeOrAssetIdOf<T>,
+		asset_b: NativeOrAssetIdOf<T>,
+		window: BlockNumberFor<T>,
+	) -> Option<FixedU128> {
+		let block_number = frame_system::Pallet::<T>::block_number();
+		if asset_a == asset_b || window.is_zero() || block_number < window {
+			return None;
+		}
+
+		let liquidity_pool = Self::get_liquidity_pool(
+			AssetPairs::<T>::new(asset_a, asset_b)
+		)?;
+		let asset_pair = liquidity_pool.asset_pair.clone();
+
+		let price_accumulator = Self::accumulate_price(
+			PriceAccumulators::<T>::get(asset_pair.clone())?,
+			&liquidity_pool,
+			block_number
+		);
+
+		let window_start = block_number - window;
+		let price_observation = PriceObservations::<T>::get(asset_pair.clone())
+			.into_iter()
+			.rev()
+			.find(|price_observation| price_observation.block_number <= window_start)?;
+
+		let (current_cumulative, observed_cumulative) = if asset_a == asset_pair.asset_x {
+			(price_accumulator.price_cumulative, price_observation.price_cumulative)
+		} else {
+			(price_accumulator.inverse_price_cumulative, price_observation.inverse_price_cumulative)
+		};
+
+		let elapsed: u128 = block_number.saturating_sub(price_observation.block_number).saturated_into();
+		Some(FixedU128::from_inner(current_cumulative.wrapping_sub(observed_cumulative) / elapsed))
+	}
+
+	/// Fee growth per unit of liquidity inside `[lower_tick, upper_tick)` over the life of the pool.
+	/// Only differences between two readings are meaningful.
+	pub fn fee_growth_inside(
+		asset_pair: AssetPairs<T>,
+		concentrated_pool: &ConcentratedPool,
+		lower_tick: i32,
+		upper_tick: i32,
+	) -> (u128, u128) {
+		let lower = Ticks::<T>::get(asset_pair.clone(), lower_tick).unwrap_or_default();
+		let upper = Ticks::<T>::get(asset_pair, upper_tick).unwrap_or_default();
+		let (fee_growth_x, fee_growth_y) = (concentrated_pool.fee_growth_x, concentrated_pool.fee_growth_y);
+
+		let (below_x, below_y) = if concentrated_pool.tick >= lower_tick {
+			(lower.fee_growth_outside_x, lower.fee_growth_outside_y)
+		} else {
+			(fee_growth_x.wrapping_sub(lower.fee_growth_outside_x), fee_growth_y.wrapping_sub(lower.fee_growth_outside_y))
+		};
+
+		let (above_x, above_y) = if concentrated_pool.tick < upper_tick {
+			(upper.fee_growth_outside_x, upper.fee_growth_outside_y)
+		} else {
+			(fee_growth_x.wrapping_sub(upper.fee_growth_outside_x), fee_growth_y.wrapping_sub(upper.fee_growth_outside_y))
+		};
+
+		(
+			fee_growth_x.wrapping_sub(below_x).wrapping_sub(above_x),
+			fee_growth_y.wrapping_sub(below_y).wrapping_sub(above_y),
+		)
+	}
+
+	/// Adds `liquidity_delta` to the ranges that start at `tick`, or end there with `is_upper`,
+	/// initializing the tick on first use and clearing it once no range refers to it.
+	pub fn update_tick(
+		asset_pair: AssetPairs<T>,
+		concentrated_pool: &ConcentratedPool,
+		tick: i32,
+		liquidity_delta: i128,
+		is_upper: bool,
+	) -> DispatchResult {
+		let mut tick_info = Ticks::<T>::get(asset_pair.clone(), tick).unwrap_or_default();
+
+		if tick_info.liquidity_gross.is_zero() {
+			// All fees so far count as earned below a tick at or below the price.
+			if tick <= concentrated_pool.tick {
+				tick_info.fee_growth_outside_x = concentrated_pool.fee_growth_x;
+				tick_info.fee_growth_outside_y = concentrated_pool.fee_growth_y;
 			}
+
+			InitializedTicks::<T>::try_mutate(asset_pair.clone(), |initialized_ticks| {
+				match initialized_ticks.binary_search(&tick) {
+					Ok(_) => Ok(()),
+					Err(index) => initialized_ticks.try_insert(index, tick).map_err(|_| Error::<T>::TooManyTicks),
+				}
+			})?;
+		}
+
+		let liquidity_net_delta = if is_upper { liquidity_delta.checked_neg() } else { Some(liquidity_delta) };
+
+		tick_info.liquidity_gross = concentrated_math::add_liquidity_delta(tick_info.liquidity_gross, liquidity_delta)
+			.ok_or(Error::<T>::ComputeRangeLiquidityError)?;
+		tick_info.liquidity_net = liquidity_net_delta
+			.and_then(|liquidity_net_delta| tick_info.liquidity_net.checked_add(liquidity_net_delta))
+			.ok_or(Error::<T>::ComputeRangeLiquidityError)?;
+
+		if tick_info.liquidity_gross.is_zero() {
+			Ticks::<T>::remove(asset_pair.clone(), tick);
+			InitializedTicks::<T>::mutate(asset_pair, |initialized_ticks| {
+				if let Ok(index) = initialized_ticks.binary_search(&tick) {
+					initialized_ticks.remove(index);
+				}
+			});
+		} else {
+			Ticks::<T>::insert(asset_pair, tick, tick_info);
 		}
 
 		Ok(())
 	}
 
-	fn transfer_asset(
-		who: Self::AccountId,
-		asset: Self::AssetId,
-		asset_balance: Self::AssetBalance,
-		account_id: Self::AccountId,
-	) -> Result<(), DispatchError> {
+	/// Swaps `amount` of `asset_in`, or buys `amount` of the other asset without `exact_in`, along
+	/// the price of a concentrated pool. The reserves act as a range over every price next to the
+	/// range positions, with the liquidity they back at the pool's price, crossing initialized
+	/// ticks as the price moves.
+	///
+	/// With `fee`, the swap fee and the input it was charged on, the pool's range state is updated
+	/// and the range positions' share of the fee is added to their fee growth. Without it the swap
+	/// is only quoted.
+	pub fn concentrated_swap(
+		asset_pair: AssetPairs<T>,
+		asset_in: NativeOrAssetIdOf<T>,
+		reserve_in: u128,
+		reserve_out: u128,
+		amount: u128,
+		exact_in: bool,
+		fee: Option<(u128, u128)>,
+	) -> Result<ConcentratedSwap, DispatchError> {
+		let asset_pair = asset_pair.normalize();
+		let mut concentrated_pool = ConcentratedPools::<T>::get(asset_pair.clone())
+			.ok_or(Error::<T>::NotConcentratedPool)?;
+		let initialized_ticks = InitializedTicks::<T>::get(asset_pair.clone());
+
+		let (swap_fee, swap_fee_basis) = fee.unwrap_or_default();
+
+		// Selling `asset_x` moves the price down towards lower ticks.
+		let x_in = asset_in == asset_pair.asset_x;
+		let (reserve_x, reserve_y) = if x_in { (reserve_in, reserve_out) } else { (reserve_out, reserve_in) };
+		let full_range_liquidity = concentrated_math::full_range_liquidity(concentrated_pool.sqrt_price, reserve_x, reserve_y)
+			.ok_or(Error::<T>::ComputeSwapAmountError)?;
+		let bound_tick = if x_in { concentrated_math::MIN_TICK } else { concentrated_math::MAX_TICK };
+
+		let mut concentrated_swap = ConcentratedSwap::default();
+		let mut amount_remaining = amount;
+		while !amount_remaining.is_zero() {
+			let next_tick = if x_in {
+				initialized_ticks.iter().rev().find(|tick| **tick <= concentrated_pool.tick).copied()
+			} else {
+				initialized_ticks.iter().find(|tick| **tick > concentrated_pool.tick).copied()
+			};
+
+			let sqrt_price_target = concentrated_math::sqrt_price_at_tick(next_tick.unwrap_or(bound_tick))
+				.ok_or(Error::<T>::ComputeSwapAmountError)?;
+			let liquidity = full_range_liquidity
+				.checked_add(concentrated_pool.liquidity)
+				.ok_or(Error::<T>::ComputeSwapAmountError)?;
+
+			let (next_sqrt_price, step_amount_in, step_amount_out) = if liquidity.is_zero() {
+				(sqrt_price_target, 0, 0)
+			} else {
+				concentrated_math::swap_step(
+					concentrated_pool.sqrt_price,
+					sqrt_price_target,
+					liquidity,
+					amount_remaining,
+					exact_in
+				).ok_or(Error::<T>::ComputeSwapAmountError)?
+			};
+
+			amount_remaining = amount_remaining.saturating_sub(if exact_in { step_amount_in } else { step_amount_out });
+
+			if !liquidity.is_zero() {
+				// The reserves take the share of their liquidity, rounded in their favour.
+				let reserve_amount_in = multiply_by_rational_with_rounding(step_amount_in, full_range_liquidity, liquidity, Rounding::Up)
+					.ok_or(Error::<T>::ComputeSwapAmountError)?;
+				let reserve_amount_out = multiply_by_rational_with_rounding(step_amount_out, full_range_liquidity, liquidity, Rounding::Down)
+					.ok_or(Error::<T>::ComputeSwapAmountError)?;
+
+				concentrated_swap.amount_in = concentrated_swap.amount_in.saturating_add(step_amount_in);
+				concentrated_swap.amount_out = concentrated_swap.amount_out.saturating_add(step_amount_out);
+				concentrated_swap.reserve_amount_in = concentrated_swap.reserve_amount_in.saturating_add(reserve_amount_in);
+				concentrated_swap.reserve_amount_out = concentrated_swap.reserve_amount_out.saturating_add(reserve_amount_out);
+			}
+
+			if fee.is_some() && !concentrated_pool.liquidity.is_zero() && !swap_fee_basis.is_zero() {
+				let step_fee = multiply_by_rational_with_rounding(swap_fee, step_amount_in, swap_fee_basis, Rounding::Down)
+					.ok_or(Error::<T>::ComputeSwapFeeError)?;
+				let range_fee = multiply_by_rational_with_rounding(step_fee, concentrated_pool.liquidity, liquidity, Rounding::Down)
+					.ok_or(Error::<T>::ComputeSwapFeeError)?;
+				let fee_growth = multiply_by_rational_with_rounding(range_fee, FixedU128::DIV, concentrated_pool.liquidity, Rounding::Down)
+					.ok_or(Error::<T>::ComputeSwapFeeError)?;
+
+				if x_in {
+					concentrated_pool.fee_growth_x = concentrated_pool.fee_growth_x.wrapping_add(fee_growth);
+				} else {
+					concentrated_pool.fee_growth_y = concentrated_pool.fee_growth_y.wrapping_add(fee_growth);
+				}
+
+				concentrated_swap.range_fee = concentrated_swap.range_fee.saturating_add(range_fee);
+			}
+
+			concentrated_pool.sqrt_price = next_sqrt_price;
+			if next_sqrt_price != sqrt_price_target {
+				concentrated_pool.tick = concentrated_math::tick_at_sqrt_price(next_sqrt_price)
+					.ok_or(Error::<T>::ComputeSwapAmountError)?;
+				continue;
+			}
+
+			let tick = match next_tick {
+				Some(tick) => tick,
+				None => {
+					concentrated_pool.tick = bound_tick;
+					break;
+				},
+			};
+
+			// Crossing a tick flips which side of it counts as outside.
+			let mut tick_info = Ticks::<T>::get(asset_pair.clone(), tick).unwrap_or_default();
+			tick_info.fee_growth_outside_x = concentrated_pool.fee_growth_x.wrapping_sub(tick_info.fee_growth_outside_x);
+			tick_info.fee_growth_outside_y = concentrated_pool.fee_growth_y.wrapping_sub(tick_info.fee_growth_outside_y);
+
+			let liquidity_net = if x_in { tick_info.liquidity_net.checked_neg() } else { Some(tick_info.liquidity_net) };
+			concentrated_pool.liquidity = liquidity_net
+				.and_then(|liquidity_net| concentrated_math::add_liquidity_delta(concentrated_pool.liquidity, liquidity_net))
+				.ok_or(Error::<T>::ComputeSwapAmountError)?;
+			concentrated_pool.tick = if x_in { tick.saturating_sub(1) } else { tick };
+
+			if fee.is_some() {
+				Ticks::<T>::insert(asset_pair.clone(), tick, tick_info);
+			}
+		}
+
+		ensure!(amount_remaining.is_zero(), Error::<T>::InsufficientLiquidity);
+
+		if fee.is_some() {
+			ConcentratedPools::<T>::insert(asset_pair, concentrated_pool);
+		}
+
+		Ok(concentrated_swap)
+	}
+
+	/// Applies a quoted swap to the pool and returns how much of the input, output and fee its
+	/// reserves take. Only concentrated pools share swaps with range positions.
+	pub fn apply_swap_to_pool(
+		liquidity_pool: &LiquidityPool<T>,
+		asset_in: NativeOrAssetIdOf<T>,
+		amount_in: u128,
+		amount_out: u128,
+		exact_in: bool,
+		swap_fee: u128,
+	) -> Result<(u128, u128, u128), DispatchError> {
+		if !matches!(PoolKinds::<T>::get(liquidity_pool.asset_pair.normalize()), PoolKind::Concentrated { .. }) {
+			return Ok((amount_in, amount_out, swap_fee));
+		}
+
+		let is_asset_x_in = asset_in == liquidity_pool.asset_pair.asset_x;
+		let (reserve_in, reserve_out) = if is_asset_x_in {
+			(liquidity_pool.asset_x_balance.into_inner(), liquidity_pool.asset_y_balance.into_inner())
+		} else {
+			(liquidity_pool.asset_y_balance.into_inner(), liquidity_pool.asset_x_balance.into_inner())
+		};
+
+		let sqrt_price = ConcentratedPools::<T>::get(liquidity_pool.asset_pair.normalize())
+			.ok_or(Error::<T>::NotConcentratedPool)?
+			.sqrt_price;
+
+		let concentrated_swap = Self::concentrated_swap(
+			liquidity_pool.asset_pair.clone(),
+			asset_in,
+			reserve_in,
+			reserve_out,
+			if exact_in { amount_in } else { amount_out },
+			exact_in,
+			Some((swap_fee, amount_in))
+		)?;
+
+		// The swap cannot pay out more than the liquidity in range along its path, and the reserves
+		// cannot pay more than their share of it.
 		ensure!(
-			<Pallet<T> as HumidefiHelpers>::check_asset_balance(
-				who.clone(),
-				asset,
-				asset_balance,
-			).is_ok(),
-			Error::<T>::CheckAssetBalanceError
+			amount_out <= concentrated_swap.amount_out && concentrated_swap.amount_in <= amount_in,
+			Error::<T>::ComputeSwapAmountError
+		);
+		ensure!(
+			concentrated_swap.reserve_amount_in <= amount_in &&
+				concentrated_swap.reserve_amount_out <= amount_out &&
+				concentrated_swap.reserve_amount_out < reserve_out,
+			Error::<T>::InsufficientLiquidity
 		);
 
-		<T::Fungibles as fungibles::Mutate<_>>::transfer(
-			asset,
-			&who.clone(),
-			&account_id.clone(),
-			asset_balance,
-			frame_support::traits::tokens::Preservation::Expendable,
-		)?;
+		let update_reserve_in = reserve_in
+			.checked_add(concentrated_swap.reserve_amount_in)
+			.ok_or(Error::<T>::ComputeSwapAmountError)?;
+		let update_reserve_out = reserve_out - concentrated_swap.reserve_amount_out;
+		let (reserve_x, reserve_y, update_reserve_x, update_reserve_y) = if is_asset_x_in {
+			(reserve_in, reserve_out, update_reserve_in, update_reserve_out)
+		} else {
+			(reserve_out, reserve_in, update_reserve_out, update_reserve_in)
+		};
 
-		Ok(())
-	}
-}
+		let update_sqrt_price = ConcentratedPools::<T>::get(liquidity_pool.asset_pair.normalize())
+			.ok_or(Error::<T>::NotConcentratedPool)?
+			.sqrt_price;
 
-impl<T: Config> HumidefiHelpers for Pallet<T> {
-	type AccountId = T::AccountId;
-	type AssetId = <T::Fungibles as fungibles::Inspect<Self::AccountId>>::AssetId;
-	type AssetBalance = <T::Fungibles as fungibles::Inspect<Self::AccountId>>::Balance;
-	type AccountLiquidityPoolId = u64;
-	type AssetPairs = <AssetPairs<T> as AssetPairsTrait>::AssetPairs;
-	type LiquidityPool = <LiquidityPool<T> as LiquidityPoolTrait>::LiquidityPool;
-	type AccountLiquidityPool = <AccountLiquidityPool<T> as AccountLiquidityPoolTrait>::AccountLiquidityPool;
-	type AssetBalanceResult = Result<(Self::AssetBalance, Self::AssetBalance, Self::AssetBalance), DispatchError>;
+		// The reserves' virtual reserves move along the price, so the liquidity they back at the
+		// new price must be at least what the swap was priced with.
+		let liquidity = concentrated_math::full_range_liquidity(sqrt_price, reserve_x, reserve_y)
+			.ok_or(Error::<T>::ConstantProductInvariantError)?;
+		let update_liquidity = concentrated_math::full_range_liquidity(update_sqrt_price, update_reserve_x, update_reserve_y)
+			.ok_or(Error::<T>::ConstantProductInvariantError)?;
 
-	fn get_dex_account() -> Self::AccountId {
-		HUMIDEFI.into_account_truncating()
-	}
+		ensure!(liquidity <= update_liquidity, Error::<T>::ConstantProductInvariantError);
 
-	fn get_asset_balance(
-		asset: Self::AssetId,
-		account_id: Self::AccountId,
-	) -> Self::AssetBalance {
-		<T::Fungibles as fungibles::Inspect<_>>::balance(asset, &account_id)
+		Ok((
+			concentrated_swap.reserve_amount_in,
+			concentrated_swap.reserve_amount_out,
+			swap_fee.saturating_sub(concentrated_swap.range_fee),
+		))
 	}
 
-	fn get_liquidity_pool(
-		asset_pair: Self::AssetPairs
-	) -> Option<LiquidityPool<T>> {
-		let existing_liquidity_pool = LiquidityPoolStorage::<T>::get(asset_pair.clone());
-		match existing_liquidity_pool {
-			Some(liquidity_pool) => Some(liquidity_pool),
-			None => {
-				let swap_asset_pair = AssetPairs::<T> {
-					asset_x: asset_pair.clone().asset_y,
-					asset_y: asset_pair.clone().asset_x,
-				};
+	/// Checks open limit orders while `remaining_weight` allows, carrying on from `LimitOrderCursor`
+	/// so that every order gets its turn over consecutive blocks.
+	pub fn process_limit_orders(remaining_weight: Weight) -> Weight {
+		let limit_order_weight = T::WeightInfo::process_limit_order();
+		let mut used_weight = T::DbWeight::get().reads_writes(1, 1);
+		if remaining_weight.any_lt(used_weight.saturating_add(limit_order_weight)) {
+			return Weight::zero()
+		}
 
-				let liquidity_pool_swap_pair = LiquidityPoolStorage::<T>::get(swap_asset_pair);
-				if let Some(liquidity_pool) = liquidity_pool_swap_pair {
-					return Some(liquidity_pool)
-				}
+		let block_number = frame_system::Pallet::<T>::block_number();
+		let mut limit_orders = match LimitOrderCursor::<T>::get() {
+			Some(cursor) => LimitOrders::<T>::iter_from(LimitOrders::<T>::hashed_key_for(cursor)),
+			None => LimitOrders::<T>::iter(),
+		};
 
-				None
-			},
+		loop {
+			if remaining_weight.any_lt(used_weight.saturating_add(limit_order_weight)) {
+				break
+			}
+
+			match limit_orders.next() {
+				Some((id, limit_order)) => {
+					used_weight.saturating_accrue(limit_order_weight);
+					LimitOrderCursor::<T>::put(id);
+
+					Self::process_limit_order(id, limit_order, block_number);
+				},
+				None => {
+					LimitOrderCursor::<T>::kill();
+					break
+				},
+			}
 		}
+
+		used_weight
 	}
 
-	fn get_account_liquidity_pools(
-		account_id: Self::AccountId,
-		asset_pair: Self::AssetPairs,
-	) -> Option<BoundedVec<AccountLiquidityPool<T>, ConstU32<100>>> {
-		let storage_key = (account_id.clone(), asset_pair.clone());
-		let existing_account_liquidity_pools = AccountLiquidityPoolStorage::<T>::get(storage_key);
-		match existing_account_liquidity_pools {
-			Some(account_liquidity_pools) => Some(account_liquidity_pools),
-			None => {
-				let swap_asset_pair = AssetPairs::<T> {
-					asset_x: asset_pair.clone().asset_y,
-					asset_y: asset_pair.clone().asset_x,
-				};
+	/// Refunds the order if it expired, otherwise fills it if the pool pays at least its limit price.
+	fn process_limit_order(id: LimitOrderId, limit_order: LimitOrder<T>, block_number: BlockNumberFor<T>) {
+		if limit_order.expiry < block_number {
+			if Self::close_limit_order(id, &limit_order).is_ok() {
+				Self::deposit_event(Event::LimitOrderExpired {
+					id,
+					owner: limit_order.owner,
+					amount_in: limit_order.amount_in,
+				});
+			}
 
-				let storage_key_swap_pair = (account_id.clone(), swap_asset_pair.clone());
-				let account_liquidity_pools_swap_pair =
-					AccountLiquidityPoolStorage::<T>::get(storage_key_swap_pair);
+			return
+		}
 
-				if let Some(account_liquidity_pool) = account_liquidity_pools_swap_pair {
-					return Some(account_liquidity_pool)
-				}
+		// A swap never pays more than the spot price, so most orders stop here without a quote.
+		let spot_price = <Pallet<T> as interfaces::humidefi::PriceOracle>::spot_price(
+			limit_order.asset_in,
+			limit_order.asset_out
+		);
+
+		if spot_price.map_or(true, |spot_price| spot_price < limit_order.limit_price) {
+			return
+		}
+
+		let min_amount_out = limit_order.limit_price.saturating_mul_int(limit_order.amount_in);
+		let amount_out = match Self::compute_swap_exact_in(
+			limit_order.asset_in,
+			limit_order.asset_out,
+			limit_order.amount_in
+		) {
+			Ok(amount_out) if !amount_out.is_zero() && amount_out >= min_amount_out => amount_out,
+			_ => return,
+		};
 
+		let escrow_account_id = Self::get_escrow_account();
+
+		// Hooks are not transactional, so a fill that fails half way must not leave the swap behind.
+		let fill_result = frame_support::storage::with_storage_layer(|| -> DispatchResult {
+			<Pallet<T> as interfaces::humidefi::HumidefiCaller>::swap_exact_in_for_out(
+				escrow_account_id.clone(),
+				limit_order.asset_in,
+				limit_order.amount_in,
+				limit_order.asset_out,
+				amount_out,
 				None
-			},
+			)?;
+
+			Self::transfer_pool_asset(
+				limit_order.asset_out,
+				escrow_account_id.clone(),
+				limit_order.owner.clone(),
+				amount_out
+			)?;
+
+			Self::remove_limit_order(id, &limit_order.owner);
+			Ok(())
+		});
+
+		if fill_result.is_ok() {
+			Self::deposit_event(Event::LimitOrderFilled {
+				id,
+				owner: limit_order.owner,
+				amount_in: limit_order.amount_in,
+				amount_out,
+			});
 		}
 	}
 
-	fn check_asset_balance(
-		account_id: Self::AccountId,
-		asset: Self::AssetId,
-		asset_balance: Self::AssetBalance,
-	) -> Result<(), DispatchError> {
-		let current_asset_balance = Self::get_asset_balance(asset, account_id.clone());
-
-		current_asset_balance
-			.ensure_sub(asset_balance)
-			.map_err(|_| Error::<T>::AssetDoesNotHaveEnoughBalance)?;
+	/// Returns the escrowed funds of an order to its owner and removes it.
+	pub fn close_limit_order(id: LimitOrderId, limit_order: &LimitOrder<T>) -> DispatchResult {
+		Self::transfer_pool_asset(
+			limit_order.asset_in,
+			Self::get_escrow_account(),
+			limit_order.owner.clone(),
+			limit_order.amount_in
+		)?;
 
+		Self::remove_limit_order(id, &limit_order.owner);
 		Ok(())
 	}
 
-	fn compute_and_mint_lp_token(
-		asset_pair: Self::AssetPairs,
-		asset_x_balance: Self::AssetBalance,
-		asset_y_balance: Self::AssetBalance,
-	) -> Result<(AssetIdOf<T>, AssetBalanceOf<T>), DispatchError> {
-		let mut lp_token: AssetIdOf<T> = 1u32;
-		let humidefi_account_id = Self::get_dex_account();
+	fn remove_limit_order(id: LimitOrderId, owner: &<T as frame_system::Config>::AccountId) {
+		LimitOrders::<T>::remove(id);
+		AccountLimitOrders::<T>::mutate(owner, |limit_order_ids| {
+			limit_order_ids.retain(|limit_order_id| *limit_order_id != id);
+		});
+	}
 
-		let existing_liquidity_pool = Self::get_liquidity_pool(asset_pair.clone());
+	/// Executes the DCA runs due in `block_number`.
+	pub fn execute_dca_runs(block_number: BlockNumberFor<T>) -> Weight {
+		let dca_schedule_ids = DcaAgenda::<T>::take(block_number);
+		let weight = T::WeightInfo::execute_dca_run()
+			.saturating_mul(dca_schedule_ids.len() as u64)
+			.saturating_add(T::DbWeight::get().reads_writes(1, 1));
 
-		match existing_liquidity_pool {
-			Some(liquidity_pool) => {
-				lp_token = liquidity_pool.lp_token;
-			},
-			None => {
-				loop {
-					if !<T::Fungibles as fungibles::Inspect<_>>::asset_exists(lp_token) {
-						<T::Fungibles as fungibles::Create<_>>::create(
-							lp_token,
-							humidefi_account_id.clone(),
-							true,
-							1u128,
-						)?;
+		for id in dca_schedule_ids {
+			Self::execute_dca_run(id, block_number);
+		}
 
-						break;
-					}
+		weight
+	}
 
-					lp_token += 1;
-				}
+	fn execute_dca_run(id: DcaScheduleId, block_number: BlockNumberFor<T>) {
+		let mut dca_schedule = match DcaSchedules::<T>::get(id) {
+			Some(dca_schedule) => dca_schedule,
+			None => return,
+		};
+
+		let escrow_account_id = Self::get_escrow_account();
+
+		// Hooks are not transactional, so a run that fails half way must not leave the swap behind.
+		let run_result = frame_support::storage::with_storage_layer(|| -> Result<AssetBalanceOf<T>, DispatchError> {
+			// A price pushed around in the blocks before the run barely moves the TWAP.
+			let reference_price = Self::twap(dca_schedule.asset_in, dca_schedule.asset_out, dca_schedule.period)
+				.or_else(|| <Pallet<T> as interfaces::humidefi::PriceOracle>::spot_price(
+					dca_schedule.asset_in,
+					dca_schedule.asset_out
+				))
+				.ok_or(Error::<T>::ComputePriceError)?;
+			let min_amount_out = dca_schedule.max_slippage
+				.left_from_one()
+				.mul_floor(reference_price.saturating_mul_int(dca_schedule.amount_per_period));
+
+			let amount_out = Self::compute_swap_exact_in(
+				dca_schedule.asset_in,
+				dca_schedule.asset_out,
+				dca_schedule.amount_per_period
+			)?;
+			ensure!(amount_out >= min_amount_out, Error::<T>::SlippageExceeded);
+
+			<Pallet<T> as interfaces::humidefi::HumidefiCaller>::swap_exact_in_for_out(
+				escrow_account_id.clone(),
+				dca_schedule.asset_in,
+				dca_schedule.amount_per_period,
+				dca_schedule.asset_out,
+				amount_out,
+				None
+			)?;
+
+			Self::transfer_pool_asset(
+				dca_schedule.asset_out,
+				escrow_account_id.clone(),
+				dca_schedule.owner.clone(),
+				amount_out
+			)?;
+
+			Ok(amount_out)
+		});
+
+		dca_schedule.remaining_runs = dca_schedule.remaining_runs.saturating_sub(1);
+
+		match run_result {
+			Ok(amount_out) => {
+				dca_schedule.escrowed = dca_schedule.escrowed.saturating_sub(dca_schedule.amount_per_period);
+
+				Self::deposit_event(Event::DcaExecuted {
+					id,
+					owner: dca_schedule.owner.clone(),
+					amount_in: dca_schedule.amount_per_period,
+					amount_out,
+					remaining_runs: dca_schedule.remaining_runs,
+				});
+			},
+			Err(error) => {
+				Self::deposit_event(Event::DcaRunFailed {
+					id,
+					owner: dca_schedule.owner.clone(),
+					error,
+					remaining_runs: dca_schedule.remaining_runs,
+				});
 			},
 		}
 
-		let mul_xy_assets = FixedU128::from_inner(asset_x_balance).mul(FixedU128::from_inner(asset_y_balance));
-		if mul_xy_assets.is_zero() {
-			return Err(Error::<T>::CannotBeZero.into())
+		if !dca_schedule.remaining_runs.is_zero() {
+			let next_run = block_number.saturating_add(dca_schedule.period);
+			if let Ok(next_run) = Self::schedule_dca_run(id, next_run) {
+				dca_schedule.next_run = next_run;
+				DcaSchedules::<T>::insert(id, dca_schedule);
+				return
+			}
 		}
 
-		let lp_token_balance = mul_xy_assets.sqrt().into_inner();
+		if Self::close_dca_schedule(id, &dca_schedule).is_ok() {
+			Self::deposit_event(Event::DcaCompleted {
+				id,
+				owner: dca_schedule.owner,
+				refunded: dca_schedule.escrowed,
+			});
+		}
+	}
 
-		<T::Fungibles as fungibles::Mutate<_>>::mint_into(
-			lp_token,
-			&humidefi_account_id.clone(),
-			lp_token_balance,
-		)?;
+	/// Adds the schedule to the agenda of `block_number`, or of one of the few blocks after it
+	/// when that block is full, and returns the block it ended up in.
+	pub fn schedule_dca_run(
+		id: DcaScheduleId,
+		block_number: BlockNumberFor<T>,
+	) -> Result<BlockNumberFor<T>, DispatchError> {
+		for offset in 0..DCA_AGENDA_LOOKAHEAD {
+			let run_block_number = block_number.saturating_add(offset.into());
+			if DcaAgenda::<T>::try_mutate(run_block_number, |dca_schedule_ids| dca_schedule_ids.try_push(id)).is_ok() {
+				return Ok(run_block_number)
+			}
+		}
 
-		Ok((lp_token, lp_token_balance))
+		Err(Error::<T>::DcaAgendaFull.into())
 	}
 
-	fn compute_price(
-		asset_x_balance: Self::AssetBalance,
-		asset_y_balance: Self::AssetBalance,
-	) -> Result<FixedU128, DispatchError> {
-		if FixedU128::from_inner(asset_x_balance).is_zero() || FixedU128::from_inner(asset_y_balance).is_zero() {
-			return Err(Error::<T>::CannotBeZero.into())
+	/// Returns the escrowed funds of a schedule to its owner and removes it.
+	pub fn close_dca_schedule(id: DcaScheduleId, dca_schedule: &DcaSchedule<T>) -> DispatchResult {
+		if !dca_schedule.escrowed.is_zero() {
+			Self::transfer_pool_asset(
+				dca_schedule.asset_in,
+				Self::get_escrow_account(),
+				dca_schedule.owner.clone(),
+				dca_schedule.escrowed
+			)?;
 		}
 
-		let price = FixedU128::from_rational(asset_y_balance, asset_x_balance);
-		Ok(price)
+		DcaSchedules::<T>::remove(id);
+		DcaAgenda::<T>::mutate_exists(dca_schedule.next_run, |maybe_dca_schedule_ids| {
+			if let Some(dca_schedule_ids) = maybe_dca_schedule_ids.as_mut() {
+				dca_schedule_ids.retain(|dca_schedule_id| *dca_schedule_id != id);
+			}
+
+			if maybe_dca_schedule_ids.as_ref().map_or(false, |dca_schedule_ids| dca_schedule_ids.is_empty()) {
+				*maybe_dca_schedule_ids = None;
+			}
+		});
+		AccountDcaSchedules::<T>::mutate(&dca_schedule.owner, |dca_schedule_ids| {
+			dca_schedule_ids.retain(|dca_schedule_id| *dca_schedule_id != id);
+		});
+
+		Ok(())
 	}
 
-	fn compute_xy_assets(
-		account_id: Self::AccountId,
-		asset_pair: Self::AssetPairs,
-		lp_token: Self::AssetId,
-		id: Self::AccountLiquidityPoolId,
-	) -> Result<(AssetBalanceOf<T>, AssetBalanceOf<T>, AssetBalanceOf<T>), DispatchError> {
-		let existing_account_liquidity_pools = Self::get_account_liquidity_pools(account_id.clone(), asset_pair.clone());
-		if existing_account_liquidity_pools.is_none() {
-			return Err(Error::<T>::AccountLiquidityPoolDoesNotExists.into())
-		}
+	pub fn list_pools(
+		offset: u32,
+		limit: u32,
+	) -> Vec<PoolInfo<NativeOrAssetIdOf<T>, AssetIdOf<T>, AssetBalanceOf<T>>> {
+		LiquidityPoolStorage::<T>::iter_keys()
+			.skip(offset as usize)
+			.take(limit.min(MAX_LIST_POOLS_LIMIT) as usize)
+			.filter_map(|asset_pair| {
+				Self::get_liquidity_pool(asset_pair)
+			})
+			.map(|liquidity_pool| PoolInfo {
+				asset_x: liquidity_pool.asset_pair.asset_x,
+				asset_y: liquidity_pool.asset_pair.asset_y,
+				asset_x_balance: liquidity_pool.asset_x_balance.into_inner(),
+				asset_y_balance: liquidity_pool.asset_y_balance.into_inner(),
+				price: liquidity_pool.price,
+				lp_token: liquidity_pool.lp_token,
+				lp_token_supply: liquidity_pool.lp_token_balance.into_inner(),
+			})
+			.collect()
+	}
 
-		let mut lp_token_balance = FixedU128::from_inner(0);
-		if let Some(account_liquidity_pools) = existing_account_liquidity_pools {
-			if !account_liquidity_pools.to_vec().is_empty() {
-				for account_liquidity_pool in account_liquidity_pools {
-					if account_liquidity_pool.lp_token == lp_token && account_liquidity_pool.id == id {
-						lp_token_balance = account_liquidity_pool.lp_token_balance;
-						break;
-					}
-				}
+	pub fn account_positions(
+		account_id: <T as frame_system::Config>::AccountId,
+	) -> Vec<PositionInfo<NativeOrAssetIdOf<T>, AssetIdOf<T>, AssetBalanceOf<T>>> {
+		// Positions are keyed by the hash of (account, pair), so look the account up in each pool
+		// rather than walking every account's positions.
+		LiquidityPoolStorage::<T>::iter_keys()
+			.filter_map(|asset_pair| {
+				Self::get_account_liquidity_pools(account_id.clone(), asset_pair)
+			})
+			.flat_map(|account_liquidity_pools| account_liquidity_pools.into_inner())
+			.map(|account_liquidity_pool| PositionInfo {
+				id: account_liquidity_pool.id,
+				asset_x: account_liquidity_pool.asset_pair.asset_x,
+				asset_y: account_liquidity_pool.asset_pair.asset_y,
+				asset_x_balance: account_liquidity_pool.asset_x_balance.into_inner(),
+				asset_y_balance: account_liquidity_pool.asset_y_balance.into_inner(),
+				lp_token: account_liquidity_pool.lp_token,
+				lp_token_balance: account_liquidity_pool.lp_token_balance.into_inner(),
+			})
+			.collect()
+	}
+
+	/// Checks that pool accounting agrees with the pool accounts, LP issuance and positions.
+	#[cfg(any(feature = "try-runtime", test))]
+	pub fn do_try_state() -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+		let mut position_lp_token_balances = BTreeMap::<Vec<u8>, u128>::new();
+		for ((_, asset_pair), account_liquidity_pools) in AccountLiquidityPoolStorage::<T>::iter() {
+			let liquidity_pool = LiquidityPoolStorage::<T>::get(asset_pair.clone())
+				.ok_or("Liquidity position refers to a pool that does not exist")?;
+
+			let position_lp_token_balance = position_lp_token_balances.entry(asset_pair.encode()).or_default();
+			for account_liquidity_pool in account_liquidity_pools {
+				ensure!(
+					account_liquidity_pool.lp_token == liquidity_pool.lp_token,
+					"Liquidity position refers to the wrong LP token"
+				);
+
+				*position_lp_token_balance = position_lp_token_balance
+					.saturating_add(account_liquidity_pool.lp_token_balance.into_inner());
 			}
-		};
+		}
 
-		let mut price = FixedU128::from_inner(0);
-		let existing_liquidity_pool = LiquidityPoolStorage::<T>::get(asset_pair.clone());
-		if let Some(liquidity_pool) = existing_liquidity_pool {
-			price = liquidity_pool.price;
+		for (_, liquidity_pool) in LiquidityPoolStorage::<T>::iter() {
+			let asset_pair = liquidity_pool.asset_pair.clone();
+			let pool_account_id = Self::get_pool_account(asset_pair.clone());
+
+			// Reserves of a pool, swap fees included, are held by its own account.
+			ensure!(
+				liquidity_pool.asset_x_balance.into_inner() <=
+					Self::get_asset_balance(asset_pair.asset_x, pool_account_id.clone()),
+				"Pool asset x balance exceeds what the pool account holds"
+			);
+
+			ensure!(
+				liquidity_pool.asset_y_balance.into_inner() <=
+					Self::get_asset_balance(asset_pair.asset_y, pool_account_id.clone()),
+				"Pool asset y balance exceeds what the pool account holds"
+			);
+
+			let lp_token_issuance = <T::Fungibles as fungibles::Inspect<_>>::total_issuance(liquidity_pool.lp_token);
+			ensure!(
+				liquidity_pool.lp_token_balance.into_inner() == lp_token_issuance,
+				"Pool LP token balance does not match the LP token issuance"
+			);
+
+			// The minimum liquidity locked in the pool account backs no position. Pools migrated
+			// from before the lock have none.
+			let locked_lp_token_balance = Self::get_asset_balance(
+				NativeOrAssetId::Asset(liquidity_pool.lp_token),
+				pool_account_id
+			).min(MINIMUM_LIQUIDITY);
+
+			ensure!(
+				position_lp_token_balances.get(&asset_pair.encode()).copied().unwrap_or_default() <=
+					lp_token_issuance.saturating_sub(locked_lp_token_balance),
+				"Liquidity positions hold more LP tokens than the pool has issued"
+			);
 		}
 
-		if price.is_zero() {
-			return Err(Error::<T>::CannotBeZero.into())
+		for (asset_pair, initialized_ticks) in InitializedTicks::<T>::iter() {
+			ensure!(
+				ConcentratedPools::<T>::contains_key(asset_pair.clone()),
+				"Initialized ticks refer to a pool without range state"
+			);
+
+			for tick in initialized_ticks {
+				let tick_info = Ticks::<T>::get(asset_pair.clone(), tick)
+					.ok_or("Initialized tick has no tick info")?;
+
+				ensure!(!tick_info.liquidity_gross.is_zero(), "Initialized tick has no liquidity");
+			}
 		}
 
-		let get_asset_x_balance = lp_token_balance.div(FixedU128::from_inner(price.into_inner()).sqrt());
-		let get_asset_y_balance = lp_token_balance.mul(FixedU128::from_inner(price.into_inner()).sqrt());
-		let get_lp_token_balance = lp_token_balance;
+		for (id, limit_order) in LimitOrders::<T>::iter() {
+			ensure!(
+				AccountLimitOrders::<T>::get(limit_order.owner).contains(&id),
+				"Limit order is missing from its owner's orders"
+			);
+		}
 
-		Ok((
-			get_asset_x_balance.into_inner(),
-			get_asset_y_balance.into_inner(),
-			get_lp_token_balance.into_inner()
-		))
-		// Ok((get_asset_x_balance.into_inner(), get_asset_y_balance.into_inner(), get_lp_token_balance.into_inner()))
+		for (id, dca_schedule) in DcaSchedules::<T>::iter() {
+			ensure!(
+				AccountDcaSchedules::<T>::get(&dca_schedule.owner).contains(&id),
+				"DCA schedule is missing from its owner's schedules"
+			);
+			ensure!(
+				DcaAgenda::<T>::get(dca_schedule.next_run).contains(&id),
+				"DCA schedule is missing from the agenda of its next run"
+			);
+		}
+
+		Ok(())
 	}
 }
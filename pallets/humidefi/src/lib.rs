@@ -8,7 +8,17 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
 mod impls;
+mod concentrated_math;
+mod stable_swap;
+mod weighted_math;
+
+pub mod migrations;
+pub mod weights;
+pub use weights::*;
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -16,16 +26,41 @@ pub mod pallet {
 	use frame_support::{
 		// dispatch::DispatchResultWithPostInfo,
 		pallet_prelude::*,
-		sp_runtime::FixedU128,
+		sp_runtime::{
+			helpers_128bit::multiply_by_rational_with_rounding,
+			traits::Zero,
+			FixedPointNumber,
+			FixedU128,
+			PerThing,
+			Perbill,
+			Rounding,
+		},
 		traits::{fungible, fungibles},
+		sp_std::vec::Vec,
 	};
 	use frame_system::pallet_prelude::*;
 
+	/// Upper bound on the number of pools returned by a single `list_pools` page.
+	pub const MAX_LIST_POOLS_LIMIT: u32 = 100;
+
+	/// How many blocks past its due block a DCA run may be moved when that block's agenda is full.
+	pub const DCA_AGENDA_LOOKAHEAD: u32 = 8;
+
+	/// LP tokens of a pool's first deposit that stay with the pool account for good, so its LP
+	/// supply can never be redeemed down to a few units and re-priced by a donation.
+	pub const MINIMUM_LIQUIDITY: u128 = 1_000;
+
+	/// Upper bound on the amplification coefficient of StableSwap pools.
+	pub const MAX_AMPLIFICATION: u32 = 1_000_000;
+
+	/// Lower bound on either asset's weight in a weighted pool, in percent.
+	pub const MIN_WEIGHT_PERCENT: u32 = 1;
+
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
-		type NativeBalance: fungible::Inspect<Self::AccountId>
+		type NativeBalance: fungible::Inspect<Self::AccountId, Balance = u128>
 			+ fungible::Mutate<Self::AccountId>
 			+ fungible::hold::Inspect<Self::AccountId>
 			+ fungible::hold::Mutate<Self::AccountId>
@@ -35,9 +70,64 @@ pub mod pallet {
 		type Fungibles: fungibles::Inspect<Self::AccountId, AssetId = u32, Balance = u128>
 			+ fungibles::Mutate<Self::AccountId>
 			+ fungibles::Create<Self::AccountId>;
+
+		/// The swap fee charged on the input side of a swap for newly created pools.
+		#[pallet::constant]
+		type DefaultSwapFee: Get<Perbill>;
+
+		/// The maximum number of assets in a routed swap path.
+		#[pallet::constant]
+		type MaxSwapPathLength: Get<u32>;
+
+		/// Origin allowed to create new pools. Adding liquidity to an existing pool only needs a signed origin.
+		type PoolCreateOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
+
+		/// Origin allowed to manage swap fees and the asset listing.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Origin allowed to pause and unpause pools.
+		type PauseOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The largest price move a pool may make within one block. Swaps that would move it further
+		/// are rejected. Zero disables the breaker.
+		#[pallet::constant]
+		type MaxPriceMovePerBlock: Get<Perbill>;
+
+		/// Minimum number of blocks between two stored price observations of a pool.
+		#[pallet::constant]
+		type PriceObservationPeriod: Get<BlockNumberFor<Self>>;
+
+		/// The number of price observations kept per pool. Together with `PriceObservationPeriod`
+		/// this bounds the longest window a TWAP can be taken over.
+		#[pallet::constant]
+		type MaxPriceObservations: Get<u32>;
+
+		/// The number of initialized ticks a concentrated liquidity pool may have, which also bounds the
+		/// ticks a single swap can cross.
+		#[pallet::constant]
+		type MaxTicksPerPool: Get<u32>;
+
+		/// The number of open limit orders an account may have.
+		#[pallet::constant]
+		type MaxLimitOrdersPerAccount: Get<u32>;
+
+		/// The number of active DCA schedules an account may have.
+		#[pallet::constant]
+		type MaxDcaSchedulesPerAccount: Get<u32>;
+
+		/// The number of DCA runs that can be due in a single block.
+		#[pallet::constant]
+		type MaxDcaRunsPerBlock: Get<u32>;
+
+		/// Weight information for the extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
+	/// The current storage version.
+	pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	pub type BalanceOf<T> = <<T as Config>::NativeBalance as fungible::Inspect<
@@ -54,11 +144,80 @@ pub mod pallet {
 
 	pub type AccountLiquidityPoolId = u64;
 
+	pub type LimitOrderId = u64;
+
+	pub type DcaScheduleId = u64;
+
+	/// Either the native XON token or an asset held in `Fungibles`.
+	#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, RuntimeDebug, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub enum NativeOrAssetId<AssetId> {
+		Native,
+		Asset(AssetId),
+	}
+
+	pub type NativeOrAssetIdOf<T> = NativeOrAssetId<AssetIdOf<T>>;
+
 	#[derive(Clone, Eq, PartialEq, DebugNoBound, TypeInfo, Encode, Decode, MaxEncodedLen)]
 	#[scale_info(skip_type_params(T))]
 	pub struct AssetPairs<T: Config> {
-		pub asset_x: AssetIdOf<T>,
-		pub asset_y: AssetIdOf<T>,
+		pub asset_x: NativeOrAssetIdOf<T>,
+		pub asset_y: NativeOrAssetIdOf<T>,
+	}
+
+	pub type PoolId<T> = (NativeOrAssetIdOf<T>, NativeOrAssetIdOf<T>);
+
+	impl<T: Config> AssetPairs<T> {
+		/// Builds the pair in canonical order, i.e. with `asset_x < asset_y` and native first.
+		pub fn new(asset_a: NativeOrAssetIdOf<T>, asset_b: NativeOrAssetIdOf<T>) -> Self {
+			if asset_a <= asset_b {
+				AssetPairs { asset_x: asset_a, asset_y: asset_b }
+			} else {
+				AssetPairs { asset_x: asset_b, asset_y: asset_a }
+			}
+		}
+
+		pub fn is_canonical(&self) -> bool {
+			self.asset_x < self.asset_y
+		}
+
+		pub fn normalize(&self) -> Self {
+			Self::new(self.asset_x, self.asset_y)
+		}
+
+		pub fn pool_id(&self) -> PoolId<T> {
+			let asset_pair = self.normalize();
+			(asset_pair.asset_x, asset_pair.asset_y)
+		}
+	}
+
+	/// The invariant a pool prices its swaps with.
+	#[derive(Clone, Copy, Eq, PartialEq, Default, RuntimeDebug, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub enum PoolKind {
+		/// `x * y = k`.
+		#[default]
+		ConstantProduct,
+		/// Curve-style invariant for pegged assets. Higher `amplification` keeps the price flatter
+		/// around the peg.
+		StableSwap { amplification: u32 },
+		/// Balancer-style `x^w_x · y^w_y = k` where `weight_x` is the share of the pool's value held in
+		/// `asset_x` and the rest is held in `asset_y`.
+		Weighted { weight_x: Perbill },
+		/// Uniswap v3-style ranges on top of the full-range liquidity of LP token holders. Range
+		/// positions can only start and end on multiples of `tick_spacing`.
+		Concentrated { tick_spacing: u32 },
+	}
+
+	/// Ordered from least to most restrictive.
+	#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Default, RuntimeDebug, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	pub enum PoolStatus {
+		#[default]
+		Active,
+		/// Swaps and new liquidity are blocked, redemptions are still allowed.
+		SwapsPaused,
+		/// Every operation on the pool is blocked.
+		Paused,
 	}
 
 	#[derive(Clone, Eq, PartialEq, DebugNoBound, TypeInfo, Encode, Decode, MaxEncodedLen)]
@@ -68,10 +227,71 @@ pub mod pallet {
 		pub asset_x_balance: FixedU128,
 		pub asset_y_balance: FixedU128,
 		pub price: FixedU128,
+		/// Running totals of the swap fees taken into the reserves, for reporting only.
 		pub asset_x_fee: FixedU128,
 		pub asset_y_fee: FixedU128,
+		pub swap_fee: Perbill,
 		pub lp_token: AssetIdOf<T>,
 		pub lp_token_balance: FixedU128,
+		pub status: PoolStatus,
+	}
+
+	/// Sums of a pool's prices weighted by the number of blocks each one was in effect, as raw
+	/// `FixedU128` values. Both sums wrap on overflow, only differences between them are meaningful.
+	#[derive(Clone, Copy, Eq, PartialEq, Default, RuntimeDebug, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	pub struct PriceAccumulator<BlockNumber> {
+		pub block_number: BlockNumber,
+		/// Price of `asset_x` in `asset_y`.
+		pub price_cumulative: u128,
+		/// Price of `asset_y` in `asset_x`.
+		pub inverse_price_cumulative: u128,
+	}
+
+	/// Range state of a concentrated liquidity pool. Fee growth is fees per unit of range liquidity
+	/// as raw `FixedU128` values and wraps on overflow like the price accumulators.
+	#[derive(Clone, Copy, Eq, PartialEq, Default, RuntimeDebug, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	pub struct ConcentratedPool {
+		pub sqrt_price: FixedU128,
+		/// The greatest tick at or below `sqrt_price`.
+		pub tick: i32,
+		/// Range liquidity in effect at the current tick, not counting the full-range liquidity.
+		pub liquidity: u128,
+		pub fee_growth_x: u128,
+		pub fee_growth_y: u128,
+	}
+
+	#[derive(Clone, Copy, Eq, PartialEq, Default, RuntimeDebug, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	pub struct TickInfo {
+		/// Liquidity of all ranges that start or end at the tick.
+		pub liquidity_gross: u128,
+		/// Liquidity added when the price crosses the tick going up.
+		pub liquidity_net: i128,
+		/// Fee growth on the side of the tick away from the current price.
+		pub fee_growth_outside_x: u128,
+		pub fee_growth_outside_y: u128,
+	}
+
+	/// How a swap through a concentrated pool divides between the pool's reserves and its ranges.
+	#[derive(Clone, Copy, Eq, PartialEq, Default, RuntimeDebug)]
+	pub struct ConcentratedSwap {
+		pub amount_in: u128,
+		pub amount_out: u128,
+		/// The parts of the input and output taken by the full range reserves.
+		pub reserve_amount_in: u128,
+		pub reserve_amount_out: u128,
+		/// The part of the swap fee credited to range positions through fee growth.
+		pub range_fee: u128,
+	}
+
+	/// A position that only provides liquidity while the price is within `[lower_tick, upper_tick)`.
+	#[derive(Clone, Copy, Eq, PartialEq, Default, RuntimeDebug, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	pub struct LiquidityRange {
+		pub lower_tick: i32,
+		pub upper_tick: i32,
+		pub liquidity: u128,
+		/// Fee growth inside the range when the position was opened.
+		pub fee_growth_inside_x: u128,
+		pub fee_growth_inside_y: u128,
 	}
 
 	#[derive(Clone, Eq, PartialEq, DebugNoBound, TypeInfo, Encode, Decode, MaxEncodedLen)]
@@ -84,6 +304,64 @@ pub mod pallet {
 		pub asset_y_balance: FixedU128,
 		pub lp_token: AssetIdOf<T>,
 		pub lp_token_balance: FixedU128,
+		/// Set for concentrated liquidity positions, which hold no LP tokens.
+		pub range: Option<LiquidityRange>,
+	}
+
+	/// Sells `amount_in` once the pool pays at least `limit_price` of `asset_out` per `asset_in`.
+	/// `amount_in` is held by the escrow account until the order is filled, cancelled or expires.
+	#[derive(Clone, Eq, PartialEq, DebugNoBound, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct LimitOrder<T: Config> {
+		pub owner: <T as frame_system::Config>::AccountId,
+		pub asset_in: NativeOrAssetIdOf<T>,
+		pub amount_in: AssetBalanceOf<T>,
+		pub asset_out: NativeOrAssetIdOf<T>,
+		pub limit_price: FixedU128,
+		/// The last block in which the order can be filled.
+		pub expiry: BlockNumberFor<T>,
+	}
+
+	/// Swaps `amount_per_period` of `asset_in` for `asset_out` every `period` blocks, `remaining_runs`
+	/// more times. `escrowed` is what is left of the funds taken up front for all runs.
+	///
+	/// A run is skipped when it would pay more than `max_slippage` below the pool's TWAP over the
+	/// last `period` blocks, or below its spot price while the pool has no such history.
+	#[derive(Clone, Eq, PartialEq, DebugNoBound, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct DcaSchedule<T: Config> {
+		pub owner: <T as frame_system::Config>::AccountId,
+		pub asset_in: NativeOrAssetIdOf<T>,
+		pub asset_out: NativeOrAssetIdOf<T>,
+		pub amount_per_period: AssetBalanceOf<T>,
+		pub max_slippage: Perbill,
+		pub period: BlockNumberFor<T>,
+		pub remaining_runs: u32,
+		pub escrowed: AssetBalanceOf<T>,
+		pub next_run: BlockNumberFor<T>,
+	}
+
+	#[derive(Clone, Eq, PartialEq, DebugNoBound, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub enum RedeemLiquidityAmount<T: Config> {
+		LpToken(AssetBalanceOf<T>),
+		Portion(Perbill),
+	}
+
+	/// How `AssetListing` is interpreted when a new pool is created.
+	#[derive(Clone, Copy, Eq, PartialEq, Default, RuntimeDebug, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	pub enum ListingMode {
+		/// Every asset may be pooled unless it is `Denied`.
+		#[default]
+		DenyList,
+		/// Only `Allowed` assets may be pooled.
+		AllowList,
+	}
+
+	#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	pub enum AssetListingStatus {
+		Allowed,
+		Denied,
 	}
 
 	#[pallet::storage]
@@ -106,13 +384,326 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn listing_mode)]
+	pub type AssetListingMode<T> = StorageValue<_, ListingMode, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn asset_listing)]
+	pub type AssetListing<T> = StorageMap<
+		_,
+		Blake2_128Concat,
+		NativeOrAssetIdOf<T>,
+		AssetListingStatus,
+		OptionQuery,
+	>;
+
+	/// Pools without an entry are `PoolKind::ConstantProduct`.
+	#[pallet::storage]
+	#[pallet::getter(fn pool_kind)]
+	pub type PoolKinds<T> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AssetPairs<T>,
+		PoolKind,
+		ValueQuery,
+	>;
+
+	/// LP tokens of their own that reversed duplicate pools had before being merged into the pool
+	/// of their canonical pair, with how many of that pool's LP tokens each of them converts into.
+	#[pallet::storage]
+	#[pallet::getter(fn merged_lp_token)]
+	pub type MergedLpTokens<T> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AssetIdOf<T>,
+		(AssetPairs<T>, FixedU128),
+		OptionQuery,
+	>;
+
+	/// Applies to every pool on top of its own status.
+	#[pallet::storage]
+	#[pallet::getter(fn global_pool_status)]
+	pub type GlobalPoolStatus<T> = StorageValue<_, PoolStatus, ValueQuery>;
+
+	/// The price of each pool before its first swap in the given block, used by the circuit breaker.
+	/// Cleared at the start of every block.
+	#[pallet::storage]
+	pub type BlockOpeningPrice<T> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AssetPairs<T>,
+		(BlockNumberFor<T>, FixedU128),
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	pub type PriceAccumulators<T> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AssetPairs<T>,
+		PriceAccumulator<BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// Snapshots of `PriceAccumulators`, oldest first.
+	#[pallet::storage]
+	pub type PriceObservations<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AssetPairs<T>,
+		BoundedVec<PriceAccumulator<BlockNumberFor<T>>, T::MaxPriceObservations>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn concentrated_pool)]
+	pub type ConcentratedPools<T> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AssetPairs<T>,
+		ConcentratedPool,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	pub type Ticks<T> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		AssetPairs<T>,
+		Twox64Concat,
+		i32,
+		TickInfo,
+		OptionQuery,
+	>;
+
+	/// The initialized ticks of each concentrated pool in ascending order.
+	#[pallet::storage]
+	pub type InitializedTicks<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AssetPairs<T>,
+		BoundedVec<i32, T::MaxTicksPerPool>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	pub type NextLimitOrderId<T> = StorageValue<_, LimitOrderId, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn limit_order)]
+	pub type LimitOrders<T> = StorageMap<
+		_,
+		Twox64Concat,
+		LimitOrderId,
+		LimitOrder<T>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn account_limit_orders)]
+	pub type AccountLimitOrders<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		<T as frame_system::Config>::AccountId,
+		BoundedVec<LimitOrderId, T::MaxLimitOrdersPerAccount>,
+		ValueQuery,
+	>;
+
+	/// The last limit order checked by `on_idle`, where the next block carries on from.
+	#[pallet::storage]
+	pub type LimitOrderCursor<T> = StorageValue<_, LimitOrderId, OptionQuery>;
+
+	#[pallet::storage]
+	pub type NextDcaScheduleId<T> = StorageValue<_, DcaScheduleId, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn dca_schedule)]
+	pub type DcaSchedules<T> = StorageMap<
+		_,
+		Twox64Concat,
+		DcaScheduleId,
+		DcaSchedule<T>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn account_dca_schedules)]
+	pub type AccountDcaSchedules<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		<T as frame_system::Config>::AccountId,
+		BoundedVec<DcaScheduleId, T::MaxDcaSchedulesPerAccount>,
+		ValueQuery,
+	>;
+
+	/// The DCA schedules with a run due in each block, executed in `on_initialize`.
+	#[pallet::storage]
+	#[pallet::getter(fn dca_agenda)]
+	pub type DcaAgenda<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		BoundedVec<DcaScheduleId, T::MaxDcaRunsPerBlock>,
+		ValueQuery,
+	>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		LiquidityAddedSuccessfully,
-		LiquidityRedeemedSuccessfully,
-		SwapExecutedSuccessfully,
-		TransferExecutedSuccessfully
+		/// A pool was created for the pair together with its LP token.
+		PoolCreated {
+			pair: AssetPairs<T>,
+			lp_token: AssetIdOf<T>,
+		},
+		LiquidityAdded {
+			who: <T as frame_system::Config>::AccountId,
+			pair: AssetPairs<T>,
+			amount_x: AssetBalanceOf<T>,
+			amount_y: AssetBalanceOf<T>,
+			lp_minted: AssetBalanceOf<T>,
+			position_id: AccountLiquidityPoolId,
+		},
+		/// Amounts include the share of swap fees paid out with the redemption.
+		/// `position_id` is only set when a specific position was redeemed.
+		LiquidityRedeemed {
+			who: <T as frame_system::Config>::AccountId,
+			pair: AssetPairs<T>,
+			amount_x: AssetBalanceOf<T>,
+			amount_y: AssetBalanceOf<T>,
+			lp_burned: AssetBalanceOf<T>,
+			position_id: Option<AccountLiquidityPoolId>,
+		},
+		/// `amount_in` includes `fee`, which is charged in `asset_in`.
+		Swapped {
+			who: <T as frame_system::Config>::AccountId,
+			asset_in: NativeOrAssetIdOf<T>,
+			amount_in: AssetBalanceOf<T>,
+			asset_out: NativeOrAssetIdOf<T>,
+			amount_out: AssetBalanceOf<T>,
+			fee: AssetBalanceOf<T>,
+		},
+		/// `legacy_lp_burned` of a merged pool's LP token were exchanged for `lp_received` of
+		/// `lp_token`, the LP token of the pool it was merged into.
+		MergedLpTokenConverted {
+			who: <T as frame_system::Config>::AccountId,
+			legacy_lp_token: AssetIdOf<T>,
+			lp_token: AssetIdOf<T>,
+			legacy_lp_burned: AssetBalanceOf<T>,
+			lp_received: AssetBalanceOf<T>,
+		},
+		AssetTransferred {
+			from: <T as frame_system::Config>::AccountId,
+			to: <T as frame_system::Config>::AccountId,
+			asset: AssetIdOf<T>,
+			amount: AssetBalanceOf<T>,
+		},
+		SwapFeeUpdated {
+			pair: AssetPairs<T>,
+			swap_fee: Perbill,
+		},
+		ListingModeUpdated {
+			mode: ListingMode,
+		},
+		PoolStatusUpdated {
+			pair: AssetPairs<T>,
+			status: PoolStatus,
+		},
+		GlobalPoolStatusUpdated {
+			status: PoolStatus,
+		},
+		PoolKindUpdated {
+			pair: AssetPairs<T>,
+			kind: PoolKind,
+		},
+		/// `status` is `None` when the asset was removed from the listing.
+		AssetListingUpdated {
+			asset: NativeOrAssetIdOf<T>,
+			status: Option<AssetListingStatus>,
+		},
+		RangeLiquidityAdded {
+			who: <T as frame_system::Config>::AccountId,
+			pair: AssetPairs<T>,
+			position_id: AccountLiquidityPoolId,
+			lower_tick: i32,
+			upper_tick: i32,
+			liquidity: u128,
+			amount_x: AssetBalanceOf<T>,
+			amount_y: AssetBalanceOf<T>,
+		},
+		/// Amounts exclude the swap fees earned in range, which are paid out as `fee_x` and `fee_y`.
+		RangeLiquidityRemoved {
+			who: <T as frame_system::Config>::AccountId,
+			pair: AssetPairs<T>,
+			position_id: AccountLiquidityPoolId,
+			liquidity: u128,
+			amount_x: AssetBalanceOf<T>,
+			amount_y: AssetBalanceOf<T>,
+			fee_x: AssetBalanceOf<T>,
+			fee_y: AssetBalanceOf<T>,
+		},
+		LimitOrderPlaced {
+			id: LimitOrderId,
+			owner: <T as frame_system::Config>::AccountId,
+			asset_in: NativeOrAssetIdOf<T>,
+			amount_in: AssetBalanceOf<T>,
+			asset_out: NativeOrAssetIdOf<T>,
+			limit_price: FixedU128,
+			expiry: BlockNumberFor<T>,
+		},
+		LimitOrderFilled {
+			id: LimitOrderId,
+			owner: <T as frame_system::Config>::AccountId,
+			amount_in: AssetBalanceOf<T>,
+			amount_out: AssetBalanceOf<T>,
+		},
+		/// The escrowed `amount_in` was returned to the owner.
+		LimitOrderCancelled {
+			id: LimitOrderId,
+			owner: <T as frame_system::Config>::AccountId,
+			amount_in: AssetBalanceOf<T>,
+		},
+		/// The order was not filled before its expiry and `amount_in` was returned to the owner.
+		LimitOrderExpired {
+			id: LimitOrderId,
+			owner: <T as frame_system::Config>::AccountId,
+			amount_in: AssetBalanceOf<T>,
+		},
+		DcaScheduled {
+			id: DcaScheduleId,
+			owner: <T as frame_system::Config>::AccountId,
+			asset_in: NativeOrAssetIdOf<T>,
+			asset_out: NativeOrAssetIdOf<T>,
+			amount_per_period: AssetBalanceOf<T>,
+			max_slippage: Perbill,
+			period: BlockNumberFor<T>,
+			runs: u32,
+		},
+		DcaExecuted {
+			id: DcaScheduleId,
+			owner: <T as frame_system::Config>::AccountId,
+			amount_in: AssetBalanceOf<T>,
+			amount_out: AssetBalanceOf<T>,
+			remaining_runs: u32,
+		},
+		/// The run's swap failed and its `amount_per_period` stays in escrow until the schedule ends.
+		DcaRunFailed {
+			id: DcaScheduleId,
+			owner: <T as frame_system::Config>::AccountId,
+			error: DispatchError,
+			remaining_runs: u32,
+		},
+		/// The schedule has no runs left, or no room to schedule them, and `refunded` went back to the owner.
+		DcaCompleted {
+			id: DcaScheduleId,
+			owner: <T as frame_system::Config>::AccountId,
+			refunded: AssetBalanceOf<T>,
+		},
+		DcaCancelled {
+			id: DcaScheduleId,
+			owner: <T as frame_system::Config>::AccountId,
+			refunded: AssetBalanceOf<T>,
+		},
 	}
 
 	#[pallet::error]
@@ -128,6 +719,11 @@ pub mod pallet {
 		ComputeAndMintLiquidityPoolTokenError,
 		ComputePriceError,
 		ComputeXYBalancesError,
+		ComputeSwapFeeError,
+		ComputeFeeShareError,
+		ComputeSwapAmountError,
+		ConstantProductInvariantError,
+		InsufficientLiquidity,
 		CannotBeZero,
 
 		LiquidityPoolDoesNotExists,
@@ -135,36 +731,96 @@ pub mod pallet {
 		AccountLiquidityPoolBoundedVecError,
 		AccountLiquidityPoolIdError,
 		AccountLiquidityPoolDoesNotExists,
+		AccountLiquidityPoolIdDoesNotExists,
+		AccountLiquidityPoolTokenMismatch,
+
+		SlippageExceeded,
+		DeadlineExpired,
+		InvalidSwapPath,
+		IdenticalAssets,
+		AssetNotListed,
+		PoolPaused,
+		/// The swap would move the pool's price more than `MaxPriceMovePerBlock` within one block.
+		CircuitBreakerTripped,
+		LiquidityPoolAlreadyExists,
+		InvalidAmplification,
+		NotStableSwapPool,
+		InvalidWeights,
+		InvalidTickSpacing,
+		InvalidTickRange,
+		TooManyTicks,
+		NotConcentratedPool,
+		/// Range positions are closed with `remove_range_liquidity`.
+		RangePosition,
+		NotRangePosition,
+		ComputeRangeLiquidityError,
+		InvalidLimitPrice,
+		TooManyLimitOrders,
+		LimitOrderDoesNotExist,
+		NotLimitOrderOwner,
+		LimitOrderIdError,
+		InvalidDcaSchedule,
+		TooManyDcaSchedules,
+		DcaScheduleDoesNotExist,
+		NotDcaScheduleOwner,
+		DcaScheduleIdError,
+		DcaAgendaFull,
+		LpTokenNotMerged,
+		/// The withdrawal would leave the pool less native balance than the existential deposit.
+		NativeReserveBelowExistentialDeposit,
 	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+			// Opening prices only hold for the block they were taken in.
+			let removal = BlockOpeningPrice::<T>::clear(u32::MAX, None);
+
+			T::DbWeight::get()
+				.reads_writes(removal.loops.into(), removal.unique.into())
+				.saturating_add(Self::execute_dca_runs(n))
+		}
+
+		fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Self::process_limit_orders(remaining_weight)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: BlockNumberFor<T>) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+			Self::do_try_state()
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::call_index(0)]
-		#[pallet::weight(Weight::default())]
+		#[pallet::weight(T::WeightInfo::new_liquidity())]
 		pub fn new_liquidity(
 			origin: OriginFor<T>,
 			asset_pair: AssetPairs<T>,
 			asset_x_balance: AssetBalanceOf<T>,
 			asset_y_balance: AssetBalanceOf<T>,
+			min_lp_token: AssetBalanceOf<T>,
 		) -> DispatchResult {
-			let who = ensure_signed(origin)?;
+			// Only creating a pool is gated; topping up an existing one is open to anyone.
+			let who = match Self::get_liquidity_pool(asset_pair.clone()) {
+				Some(_) => ensure_signed(origin)?,
+				None => T::PoolCreateOrigin::ensure_origin(origin)?,
+			};
 
 			<Pallet<T> as interfaces::humidefi::HumidefiCaller>::new_liquidity(
 				who,
 				asset_pair,
 				asset_x_balance,
-				asset_y_balance
+				asset_y_balance,
+				min_lp_token
 			)?;
 
-			Self::deposit_event(Event::LiquidityAddedSuccessfully);
 			Ok(())
 		}
 
 		#[pallet::call_index(1)]
-		#[pallet::weight(Weight::default())]
+		#[pallet::weight(T::WeightInfo::redeem_liquidity())]
 		pub fn redeem_liquidity(
 			origin: OriginFor<T>,
 			asset_pair: AssetPairs<T>,
@@ -180,17 +836,79 @@ pub mod pallet {
 				id
 			)?;
 
-			Self::deposit_event(Event::LiquidityRedeemedSuccessfully);
+			Ok(())
+		}
+
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::redeem_liquidity_partial())]
+		pub fn redeem_liquidity_partial(
+			origin: OriginFor<T>,
+			asset_pair: AssetPairs<T>,
+			lp_token: AssetIdOf<T>,
+			id: AccountLiquidityPoolId,
+			redeem_amount: RedeemLiquidityAmount<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			<Pallet<T> as interfaces::humidefi::HumidefiCaller>::redeem_liquidity_partial(
+				who,
+				asset_pair,
+				lp_token,
+				id,
+				redeem_amount
+			)?;
+
+			Ok(())
+		}
+
+		/// Burns LP tokens held by the caller and pays out their share of the pool.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::redeem_lp_token())]
+		pub fn redeem_lp_token(
+			origin: OriginFor<T>,
+			asset_pair: AssetPairs<T>,
+			lp_token_balance: AssetBalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			<Pallet<T> as interfaces::humidefi::HumidefiCaller>::redeem_lp_token(
+				who,
+				asset_pair,
+				lp_token_balance
+			)?;
+
+			Ok(())
+		}
+
+		/// Exchanges LP tokens of a pool that was merged into the pool of its canonical pair for
+		/// LP tokens of that pool.
+		#[pallet::call_index(23)]
+		#[pallet::weight(T::WeightInfo::convert_merged_lp_token())]
+		pub fn convert_merged_lp_token(
+			origin: OriginFor<T>,
+			legacy_lp_token: AssetIdOf<T>,
+			lp_token_balance: AssetBalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			<Pallet<T> as interfaces::humidefi::HumidefiCaller>::convert_merged_lp_token(
+				who,
+				legacy_lp_token,
+				lp_token_balance
+			)?;
+
 			Ok(())
 		}
 
 		#[pallet::call_index(2)]
-		#[pallet::weight(Weight::default())]
+		#[pallet::weight(T::WeightInfo::swap_exact_in_for_out())]
 		pub fn swap_exact_in_for_out(
 			origin: OriginFor<T>,
-			asset_exact_in: AssetIdOf<T>,
+			asset_exact_in: NativeOrAssetIdOf<T>,
 			asset_exact_in_balance: AssetBalanceOf<T>,
-			asset_max_out: AssetIdOf<T>,
+			asset_max_out: NativeOrAssetIdOf<T>,
+			min_amount_out: AssetBalanceOf<T>,
+			deadline: Option<BlockNumberFor<T>>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
@@ -198,20 +916,23 @@ pub mod pallet {
 				who,
 				asset_exact_in,
 				asset_exact_in_balance,
-				asset_max_out
+				asset_max_out,
+				min_amount_out,
+				deadline
 			)?;
 
-			Self::deposit_event(Event::SwapExecutedSuccessfully);
 			Ok(())
 		}
 
 		#[pallet::call_index(3)]
-		#[pallet::weight(Weight::default())]
+		#[pallet::weight(T::WeightInfo::swap_in_for_exact_out())]
 		pub fn swap_in_for_exact_out(
 			origin: OriginFor<T>,
-			asset_exact_out: AssetIdOf<T>,
+			asset_exact_out: NativeOrAssetIdOf<T>,
 			asset_exact_out_balance: AssetBalanceOf<T>,
-			asset_min_in: AssetIdOf<T>,
+			asset_min_in: NativeOrAssetIdOf<T>,
+			max_amount_in: AssetBalanceOf<T>,
+			deadline: Option<BlockNumberFor<T>>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
@@ -219,15 +940,60 @@ pub mod pallet {
 				who,
 				asset_exact_out,
 				asset_exact_out_balance,
-				asset_min_in
+				asset_min_in,
+				max_amount_in,
+				deadline
+			)?;
+
+			Ok(())
+		}
+
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::swap_exact_in_for_out_path(path.len() as u32))]
+		pub fn swap_exact_in_for_out_path(
+			origin: OriginFor<T>,
+			path: BoundedVec<NativeOrAssetIdOf<T>, T::MaxSwapPathLength>,
+			asset_exact_in_balance: AssetBalanceOf<T>,
+			min_amount_out: AssetBalanceOf<T>,
+			deadline: Option<BlockNumberFor<T>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			<Pallet<T> as interfaces::humidefi::HumidefiCaller>::swap_exact_in_for_out_path(
+				who,
+				path.into_inner(),
+				asset_exact_in_balance,
+				min_amount_out,
+				deadline
+			)?;
+
+			Ok(())
+		}
+
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::swap_in_for_exact_out_path(path.len() as u32))]
+		pub fn swap_in_for_exact_out_path(
+			origin: OriginFor<T>,
+			path: BoundedVec<NativeOrAssetIdOf<T>, T::MaxSwapPathLength>,
+			asset_exact_out_balance: AssetBalanceOf<T>,
+			max_amount_in: AssetBalanceOf<T>,
+			deadline: Option<BlockNumberFor<T>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			<Pallet<T> as interfaces::humidefi::HumidefiCaller>::swap_in_for_exact_out_path(
+				who,
+				path.into_inner(),
+				asset_exact_out_balance,
+				max_amount_in,
+				deadline
 			)?;
 
-			Self::deposit_event(Event::SwapExecutedSuccessfully);
 			Ok(())
 		}
 
 		#[pallet::call_index(4)]
-		#[pallet::weight(Weight::default())]
+		#[pallet::weight(T::WeightInfo::transfer_asset())]
 		pub fn transfer_asset(
 			origin: OriginFor<T>,
 			asset: AssetIdOf<T>,
@@ -243,14 +1009,415 @@ pub mod pallet {
 				account_id
 			)?;
 
-			Self::deposit_event(Event::TransferExecutedSuccessfully);
 			Ok(())
 		}
+
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::set_swap_fee())]
+		pub fn set_swap_fee(
+			origin: OriginFor<T>,
+			asset_pair: AssetPairs<T>,
+			swap_fee: Perbill,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			let liquidity_pool = Self::get_liquidity_pool(asset_pair)
+				.ok_or(Error::<T>::LiquidityPoolDoesNotExists)?;
+
+			LiquidityPoolStorage::<T>::mutate(liquidity_pool.asset_pair.clone(), |query| {
+				if let Some(mutate_liquidity_pool) = query {
+					mutate_liquidity_pool.swap_fee = swap_fee;
+				}
+			});
+
+			Self::deposit_event(Event::SwapFeeUpdated {
+				pair: liquidity_pool.asset_pair,
+				swap_fee,
+			});
+			Ok(())
+		}
+
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::set_listing_mode())]
+		pub fn set_listing_mode(
+			origin: OriginFor<T>,
+			mode: ListingMode,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			AssetListingMode::<T>::put(mode);
+
+			Self::deposit_event(Event::ListingModeUpdated { mode });
+			Ok(())
+		}
+
+		/// Passing `None` as `status` removes the asset from the listing.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::set_asset_listing())]
+		pub fn set_asset_listing(
+			origin: OriginFor<T>,
+			asset: NativeOrAssetIdOf<T>,
+			status: Option<AssetListingStatus>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			AssetListing::<T>::set(asset, status);
+
+			Self::deposit_event(Event::AssetListingUpdated { asset, status });
+			Ok(())
+		}
+
+		/// Creates a pool priced by `pool_kind` and seeds it with the caller's first deposit.
+		#[pallet::call_index(15)]
+		#[pallet::weight(T::WeightInfo::create_pool())]
+		pub fn create_pool(
+			origin: OriginFor<T>,
+			asset_pair: AssetPairs<T>,
+			pool_kind: PoolKind,
+			asset_x_balance: AssetBalanceOf<T>,
+			asset_y_balance: AssetBalanceOf<T>,
+		) -> DispatchResult {
+			let who = T::PoolCreateOrigin::ensure_origin(origin)?;
+
+			<Pallet<T> as interfaces::humidefi::HumidefiCaller>::create_pool(
+				who,
+				asset_pair,
+				pool_kind,
+				asset_x_balance,
+				asset_y_balance
+			)?;
+
+			Ok(())
+		}
+
+		/// Changing the amplification moves the price of the pool at once, without ramping.
+		#[pallet::call_index(16)]
+		#[pallet::weight(T::WeightInfo::set_amplification())]
+		pub fn set_amplification(
+			origin: OriginFor<T>,
+			asset_pair: AssetPairs<T>,
+			amplification: u32,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			let liquidity_pool = Self::get_liquidity_pool(asset_pair)
+				.ok_or(Error::<T>::LiquidityPoolDoesNotExists)?;
+			let asset_pair = liquidity_pool.asset_pair.clone();
+
+			ensure!(
+				matches!(PoolKinds::<T>::get(asset_pair.clone()), PoolKind::StableSwap { .. }),
+				Error::<T>::NotStableSwapPool
+			);
+
+			let kind = PoolKind::StableSwap { amplification };
+			Self::ensure_valid_pool_kind(kind)?;
+			PoolKinds::<T>::insert(asset_pair.clone(), kind);
+
+			// Keep the stored spot price in line with the new curve.
+			let price = Self::compute_pool_price(
+				asset_pair.clone(),
+				liquidity_pool.asset_x_balance.into_inner(),
+				liquidity_pool.asset_y_balance.into_inner()
+			).map_err(|_| Error::<T>::ComputePriceError)?;
+
+			Self::update_price_accumulator(&liquidity_pool);
+			LiquidityPoolStorage::<T>::mutate(asset_pair.clone(), |query| {
+				if let Some(mutate_liquidity_pool) = query {
+					mutate_liquidity_pool.price = price;
+				}
+			});
+
+			Self::deposit_event(Event::PoolKindUpdated { pair: asset_pair, kind });
+			Ok(())
+		}
+
+		/// Provides liquidity to a concentrated pool only while its price is between the two ticks.
+		/// Ticks are prices of the canonical pair's `asset_x` in `asset_y`.
+		#[pallet::call_index(17)]
+		#[pallet::weight(T::WeightInfo::add_range_liquidity())]
+		pub fn add_range_liquidity(
+			origin: OriginFor<T>,
+			asset_pair: AssetPairs<T>,
+			lower_tick: i32,
+			upper_tick: i32,
+			asset_x_balance: AssetBalanceOf<T>,
+			asset_y_balance: AssetBalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			<Pallet<T> as interfaces::humidefi::HumidefiCaller>::add_range_liquidity(
+				who,
+				asset_pair,
+				lower_tick,
+				upper_tick,
+				asset_x_balance,
+				asset_y_balance
+			)?;
+
+			Ok(())
+		}
+
+		/// Closes a range position and pays out its assets together with the fees it earned.
+		#[pallet::call_index(18)]
+		#[pallet::weight(T::WeightInfo::remove_range_liquidity())]
+		pub fn remove_range_liquidity(
+			origin: OriginFor<T>,
+			asset_pair: AssetPairs<T>,
+			id: AccountLiquidityPoolId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			<Pallet<T> as interfaces::humidefi::HumidefiCaller>::remove_range_liquidity(who, asset_pair, id)?;
+
+			Ok(())
+		}
+
+		/// Escrows `amount_in` until the pool pays at least `limit_price` of `asset_out` per unit of
+		/// `asset_in`. Orders are checked with spare block weight, so a reached price is filled on a
+		/// best-effort basis.
+		#[pallet::call_index(19)]
+		#[pallet::weight(T::WeightInfo::place_limit_order())]
+		pub fn place_limit_order(
+			origin: OriginFor<T>,
+			asset_in: NativeOrAssetIdOf<T>,
+			amount_in: AssetBalanceOf<T>,
+			asset_out: NativeOrAssetIdOf<T>,
+			limit_price: FixedU128,
+			expiry: BlockNumberFor<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			<Pallet<T> as interfaces::humidefi::HumidefiCaller>::place_limit_order(
+				who,
+				asset_in,
+				amount_in,
+				asset_out,
+				limit_price,
+				expiry
+			)?;
+
+			Ok(())
+		}
+
+		#[pallet::call_index(20)]
+		#[pallet::weight(T::WeightInfo::cancel_limit_order())]
+		pub fn cancel_limit_order(
+			origin: OriginFor<T>,
+			id: LimitOrderId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			<Pallet<T> as interfaces::humidefi::HumidefiCaller>::cancel_limit_order(who, id)?;
+
+			Ok(())
+		}
+
+		/// Escrows `amount_per_period * runs` of `asset_in` and swaps `amount_per_period` of it for
+		/// `asset_out` every `period` blocks, starting in the next block. Runs that would lose more
+		/// than `max_slippage`, swap fee included, against the pool's TWAP are skipped.
+		#[pallet::call_index(21)]
+		#[pallet::weight(T::WeightInfo::schedule_dca())]
+		pub fn schedule_dca(
+			origin: OriginFor<T>,
+			asset_in: NativeOrAssetIdOf<T>,
+			asset_out: NativeOrAssetIdOf<T>,
+			amount_per_period: AssetBalanceOf<T>,
+			max_slippage: Perbill,
+			period: BlockNumberFor<T>,
+			runs: u32,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			<Pallet<T> as interfaces::humidefi::HumidefiCaller>::schedule_dca(
+				who,
+				asset_in,
+				asset_out,
+				amount_per_period,
+				max_slippage,
+				period,
+				runs
+			)?;
+
+			Ok(())
+		}
+
+		/// Stops the remaining runs and returns what is left in escrow.
+		#[pallet::call_index(22)]
+		#[pallet::weight(T::WeightInfo::cancel_dca())]
+		pub fn cancel_dca(
+			origin: OriginFor<T>,
+			id: DcaScheduleId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			<Pallet<T> as interfaces::humidefi::HumidefiCaller>::cancel_dca(who, id)?;
+
+			Ok(())
+		}
+
+		/// With `allow_redemptions` liquidity providers can still withdraw from the pool.
+		#[pallet::call_index(12)]
+		#[pallet::weight(T::WeightInfo::pause_pool())]
+		pub fn pause_pool(
+			origin: OriginFor<T>,
+			asset_pair: AssetPairs<T>,
+			allow_redemptions: bool,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			let status = if allow_redemptions { PoolStatus::SwapsPaused } else { PoolStatus::Paused };
+			Self::set_pool_status(asset_pair, status)
+		}
+
+		#[pallet::call_index(13)]
+		#[pallet::weight(T::WeightInfo::pause_all())]
+		pub fn pause_all(
+			origin: OriginFor<T>,
+			allow_redemptions: bool,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			let status = if allow_redemptions { PoolStatus::SwapsPaused } else { PoolStatus::Paused };
+			GlobalPoolStatus::<T>::put(status);
+
+			Self::deposit_event(Event::GlobalPoolStatusUpdated { status });
+			Ok(())
+		}
+
+		/// Reactivates a single pool, or lifts `pause_all` when `asset_pair` is `None`.
+		#[pallet::call_index(14)]
+		#[pallet::weight(T::WeightInfo::unpause())]
+		pub fn unpause(
+			origin: OriginFor<T>,
+			asset_pair: Option<AssetPairs<T>>,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			match asset_pair {
+				Some(asset_pair) => Self::set_pool_status(asset_pair, PoolStatus::Active),
+				None => {
+					GlobalPoolStatus::<T>::put(PoolStatus::Active);
+
+					Self::deposit_event(Event::GlobalPoolStatusUpdated { status: PoolStatus::Active });
+					Ok(())
+				},
+			}
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
-		pub fn get_dex_account() -> <T as frame_system::Config>::AccountId {
-			<Pallet<T> as interfaces::humidefi::HumidefiHelpers>::get_dex_account()
+		pub fn ensure_valid_pool_kind(pool_kind: PoolKind) -> DispatchResult {
+			match pool_kind {
+				PoolKind::ConstantProduct => {},
+				PoolKind::StableSwap { amplification } => {
+					ensure!(
+						(1..=MAX_AMPLIFICATION).contains(&amplification),
+						Error::<T>::InvalidAmplification
+					);
+				},
+				PoolKind::Weighted { weight_x } => {
+					let min_weight = Perbill::from_percent(MIN_WEIGHT_PERCENT);
+					ensure!(
+						min_weight <= weight_x && min_weight <= weight_x.left_from_one(),
+						Error::<T>::InvalidWeights
+					);
+				},
+				PoolKind::Concentrated { tick_spacing } => {
+					ensure!(
+						(1..=concentrated_math::MAX_TICK_SPACING).contains(&tick_spacing),
+						Error::<T>::InvalidTickSpacing
+					);
+				},
+			}
+
+			Ok(())
+		}
+
+		/// Adds a position to the account's positions in the pool under the next free id.
+		pub fn push_account_liquidity_pool(
+			who: <T as frame_system::Config>::AccountId,
+			asset_pair: AssetPairs<T>,
+			mut account_liquidity_pool: AccountLiquidityPool<T>,
+		) -> Result<AccountLiquidityPoolId, DispatchError> {
+			let storage_key = (who, asset_pair);
+			let mut account_liquidity_pools = AccountLiquidityPoolStorage::<T>::get(storage_key.clone()).unwrap_or_default();
+
+			let last_id = account_liquidity_pools.last().map_or(0u64, |last_account_liquidity_pool| last_account_liquidity_pool.id);
+			account_liquidity_pool.id = last_id.checked_add(1).ok_or(Error::<T>::AccountLiquidityPoolIdError)?;
+
+			let id = account_liquidity_pool.id;
+			account_liquidity_pools
+				.try_push(account_liquidity_pool)
+				.map_err(|_| Error::<T>::AccountLiquidityPoolBoundedVecError)?;
+
+			AccountLiquidityPoolStorage::<T>::insert(storage_key, account_liquidity_pools);
+
+			Ok(id)
+		}
+
+		/// Whether a new pool may be created with `asset`. The native token is always listed.
+		pub fn is_asset_listed(asset: NativeOrAssetIdOf<T>) -> bool {
+			if asset == NativeOrAssetId::Native {
+				return true;
+			}
+
+			match (AssetListingMode::<T>::get(), AssetListing::<T>::get(asset)) {
+				(_, Some(AssetListingStatus::Denied)) => false,
+				(ListingMode::DenyList, _) => true,
+				(ListingMode::AllowList, status) => status == Some(AssetListingStatus::Allowed),
+			}
+		}
+
+		pub fn check_swap_path(path: &[NativeOrAssetIdOf<T>]) -> DispatchResult {
+			ensure!(
+				path.len() >= 2 && path.len() <= T::MaxSwapPathLength::get() as usize,
+				Error::<T>::InvalidSwapPath
+			);
+
+			// Each pool may only be crossed once, otherwise the hop quotes would be stale.
+			for (index, asset) in path.iter().enumerate() {
+				ensure!(!path[index + 1..].contains(asset), Error::<T>::InvalidSwapPath);
+			}
+
+			Ok(())
+		}
+
+		pub fn quote_exact_in(
+			path: Vec<NativeOrAssetIdOf<T>>,
+			amount_in: AssetBalanceOf<T>,
+		) -> Option<AssetBalanceOf<T>> {
+			Self::compute_swap_exact_in_path(path, amount_in)
+				.ok()
+				.and_then(|amounts| amounts.last().copied())
+		}
+
+		pub fn quote_exact_out(
+			path: Vec<NativeOrAssetIdOf<T>>,
+			amount_out: AssetBalanceOf<T>,
+		) -> Option<AssetBalanceOf<T>> {
+			Self::compute_swap_exact_out_path(path, amount_out)
+				.ok()
+				.and_then(|amounts| amounts.first().copied())
+		}
+
+		pub fn quote_add_liquidity(
+			asset_x: NativeOrAssetIdOf<T>,
+			asset_y: NativeOrAssetIdOf<T>,
+			amount_x: AssetBalanceOf<T>,
+			amount_y: AssetBalanceOf<T>,
+		) -> Option<AssetBalanceOf<T>> {
+			if asset_x == asset_y {
+				return None
+			}
+
+			let asset_pair = AssetPairs::<T>::new(asset_x, asset_y);
+			let (amount_x, amount_y) = if asset_pair.asset_x == asset_x {
+				(amount_x, amount_y)
+			} else {
+				(amount_y, amount_x)
+			};
+
+			Self::compute_lp_token(asset_pair, amount_x, amount_y).ok()
 		}
 	}
 }
@@ -0,0 +1,862 @@
+//! Storage migrations for pallet-humidefi.
+
+use super::*;
+use frame_support::{
+	pallet_prelude::*,
+	sp_runtime::{
+		helpers_128bit::multiply_by_rational_with_rounding,
+		traits::{EnsureAdd, EnsureMul, EnsureSub, Saturating, Zero},
+		ArithmeticError,
+		FixedPointNumber,
+		FixedU128,
+		Perbill,
+		Rounding,
+	},
+	sp_std::vec::Vec,
+	storage::with_storage_layer,
+	traits::{fungibles, GetStorageVersion, OnRuntimeUpgrade},
+};
+
+#[cfg(feature = "try-runtime")]
+use frame_support::{
+	sp_runtime::TryRuntimeError,
+	sp_std::collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	storage::{KeyPrefixIterator, StoragePrefixedMap},
+};
+
+const LOG_TARGET: &str = "runtime::humidefi";
+
+/// Counts every entry under a map's prefix, including the ones that no longer decode.
+#[cfg(feature = "try-runtime")]
+fn count_raw_keys(prefix: [u8; 32]) -> u64 {
+	KeyPrefixIterator::new(prefix.to_vec(), prefix.to_vec(), |_| Ok(())).count() as u64
+}
+
+/// Layout of the first release, which keyed pools by pairs of `Fungibles` asset ids and charged
+/// no swap fee.
+pub mod v0 {
+	use super::*;
+	use frame_support::{storage_alias, Blake2_128Concat};
+
+	#[derive(Clone, Eq, PartialEq, DebugNoBound, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct AssetPairs<T: Config> {
+		pub asset_x: AssetIdOf<T>,
+		pub asset_y: AssetIdOf<T>,
+	}
+
+	#[derive(Clone, Eq, PartialEq, DebugNoBound, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct LiquidityPool<T: Config> {
+		pub asset_pair: AssetPairs<T>,
+		pub asset_x_balance: FixedU128,
+		pub asset_y_balance: FixedU128,
+		pub price: FixedU128,
+		pub asset_x_fee: FixedU128,
+		pub asset_y_fee: FixedU128,
+		pub lp_token: AssetIdOf<T>,
+		pub lp_token_balance: FixedU128,
+	}
+
+	#[derive(Clone, Eq, PartialEq, DebugNoBound, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct AccountLiquidityPool<T: Config> {
+		pub id: AccountLiquidityPoolId,
+		pub account_id: <T as frame_system::Config>::AccountId,
+		pub asset_pair: AssetPairs<T>,
+		pub asset_x_balance: FixedU128,
+		pub asset_y_balance: FixedU128,
+		pub lp_token: AssetIdOf<T>,
+		pub lp_token_balance: FixedU128,
+	}
+
+	#[storage_alias]
+	pub type LiquidityPoolStorage<T: Config> = StorageMap<
+		Pallet<T>,
+		Blake2_128Concat,
+		AssetPairs<T>,
+		LiquidityPool<T>,
+		OptionQuery,
+	>;
+
+	#[storage_alias]
+	pub type AccountLiquidityPoolStorage<T: Config> = StorageMap<
+		Pallet<T>,
+		Blake2_128Concat,
+		(<T as frame_system::Config>::AccountId, AssetPairs<T>),
+		BoundedVec<AccountLiquidityPool<T>, ConstU32<100>>,
+		OptionQuery,
+	>;
+}
+
+pub mod v1 {
+	use super::*;
+	use frame_support::{storage_alias, Blake2_128Concat};
+
+	/// Pool layout before `status` was added in storage version 3.
+	#[derive(Clone, Eq, PartialEq, DebugNoBound, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct LiquidityPool<T: Config> {
+		pub asset_pair: AssetPairs<T>,
+		pub asset_x_balance: FixedU128,
+		pub asset_y_balance: FixedU128,
+		pub price: FixedU128,
+		pub asset_x_fee: FixedU128,
+		pub asset_y_fee: FixedU128,
+		pub swap_fee: Perbill,
+		pub lp_token: AssetIdOf<T>,
+		pub lp_token_balance: FixedU128,
+	}
+
+	/// Position layout before `range` was added in storage version 4.
+	#[derive(Clone, Eq, PartialEq, DebugNoBound, TypeInfo, Encode, Decode, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct AccountLiquidityPool<T: Config> {
+		pub id: AccountLiquidityPoolId,
+		pub account_id: <T as frame_system::Config>::AccountId,
+		pub asset_pair: AssetPairs<T>,
+		pub asset_x_balance: FixedU128,
+		pub asset_y_balance: FixedU128,
+		pub lp_token: AssetIdOf<T>,
+		pub lp_token_balance: FixedU128,
+	}
+
+	#[storage_alias]
+	pub type LiquidityPoolStorage<T: Config> = StorageMap<
+		Pallet<T>,
+		Blake2_128Concat,
+		AssetPairs<T>,
+		LiquidityPool<T>,
+		OptionQuery,
+	>;
+
+	#[storage_alias]
+	pub type AccountLiquidityPoolStorage<T: Config> = StorageMap<
+		Pallet<T>,
+		Blake2_128Concat,
+		(<T as frame_system::Config>::AccountId, AssetPairs<T>),
+		BoundedVec<AccountLiquidityPool<T>, ConstU32<100>>,
+		OptionQuery,
+	>;
+
+	/// Re-keys every pool and position under its canonical pair of `NativeOrAssetId`s, and
+	/// gives every pool the default swap fee. A pool that was stored in both orders is merged
+	/// into one, see `rebuild_liquidity_pools` and `MergedLpTokens`.
+	pub struct MigrateToNativeOrAssetIds<T>(PhantomData<T>);
+
+	impl<T: Config> MigrateToNativeOrAssetIds<T> {
+		/// The canonical pair, and whether the baseline pair was in the reverse order.
+		fn migrate_asset_pair(asset_pair: v0::AssetPairs<T>) -> (AssetPairs<T>, bool) {
+			let asset_pair = AssetPairs::<T> {
+				asset_x: NativeOrAssetId::Asset(asset_pair.asset_x),
+				asset_y: NativeOrAssetId::Asset(asset_pair.asset_y),
+			};
+			let canonical_asset_pair = asset_pair.normalize();
+			let is_reversed = canonical_asset_pair != asset_pair;
+
+			(canonical_asset_pair, is_reversed)
+		}
+
+		/// Baseline pools held their fees apart until redemption. They are folded into the reserves,
+		/// which now take every swap fee, so `asset_x_fee` and `asset_y_fee` only count them.
+		fn migrate_liquidity_pool(liquidity_pool: v0::LiquidityPool<T>) -> LiquidityPool<T> {
+			let (asset_pair, is_reversed) = Self::migrate_asset_pair(liquidity_pool.asset_pair);
+
+			let asset_x_balance = liquidity_pool.asset_x_balance.saturating_add(liquidity_pool.asset_x_fee);
+			let asset_y_balance = liquidity_pool.asset_y_balance.saturating_add(liquidity_pool.asset_y_fee);
+
+			if !is_reversed {
+				return LiquidityPool::<T> {
+					asset_pair,
+					asset_x_balance,
+					asset_y_balance,
+					price: Pallet::<T>::compute_price(asset_x_balance.into_inner(), asset_y_balance.into_inner())
+						.unwrap_or(liquidity_pool.price),
+					asset_x_fee: liquidity_pool.asset_x_fee,
+					asset_y_fee: liquidity_pool.asset_y_fee,
+					swap_fee: T::DefaultSwapFee::get(),
+					lp_token: liquidity_pool.lp_token,
+					lp_token_balance: liquidity_pool.lp_token_balance,
+				}
+			}
+
+			// The price is quoted in `asset_y` per `asset_x`, so a flipped pool quotes its inverse.
+			LiquidityPool::<T> {
+				asset_pair,
+				asset_x_balance: asset_y_balance,
+				asset_y_balance: asset_x_balance,
+				price: Pallet::<T>::compute_price(asset_y_balance.into_inner(), asset_x_balance.into_inner())
+					.unwrap_or(liquidity_pool.price.reciprocal().unwrap_or_default()),
+				asset_x_fee: liquidity_pool.asset_y_fee,
+				asset_y_fee: liquidity_pool.asset_x_fee,
+				swap_fee: T::DefaultSwapFee::get(),
+				lp_token: liquidity_pool.lp_token,
+				lp_token_balance: liquidity_pool.lp_token_balance,
+			}
+		}
+
+		/// Folds a reversed duplicate with an LP token of its own into the pool of its canonical
+		/// pair. Its LP tokens are worth the share by which its reserves grow the pool's invariant,
+		/// minted to the DEX account for `convert_merged_lp_token`.
+		fn merge_liquidity_pool(
+			mut liquidity_pool: LiquidityPool<T>,
+			duplicate_liquidity_pool: LiquidityPool<T>,
+		) -> DispatchResult {
+			let invariant = liquidity_pool.asset_x_balance
+				.ensure_mul(liquidity_pool.asset_y_balance)?
+				.sqrt();
+
+			let asset_x_balance = liquidity_pool.asset_x_balance.ensure_add(duplicate_liquidity_pool.asset_x_balance)?;
+			let asset_y_balance = liquidity_pool.asset_y_balance.ensure_add(duplicate_liquidity_pool.asset_y_balance)?;
+			let merged_invariant = asset_x_balance.ensure_mul(asset_y_balance)?.sqrt();
+
+			let legacy_lp_token_balance = duplicate_liquidity_pool.lp_token_balance.into_inner();
+			let lp_token_balance = if liquidity_pool.lp_token_balance.is_zero() || invariant.is_zero() {
+				// Nothing to share with, so the duplicate's holders take the pool over one to one.
+				legacy_lp_token_balance
+			} else {
+				multiply_by_rational_with_rounding(
+					liquidity_pool.lp_token_balance.into_inner(),
+					merged_invariant.ensure_sub(invariant)?.into_inner(),
+					invariant.into_inner(),
+					Rounding::Down,
+				).ok_or(ArithmeticError::Overflow)?
+			};
+
+			let lp_token_rate = if legacy_lp_token_balance.is_zero() {
+				FixedU128::zero()
+			} else {
+				FixedU128::checked_from_rational(lp_token_balance, legacy_lp_token_balance)
+					.ok_or(ArithmeticError::Overflow)?
+			};
+
+			if !lp_token_balance.is_zero() {
+				<T::Fungibles as fungibles::Mutate<_>>::mint_into(
+					liquidity_pool.lp_token,
+					&Pallet::<T>::get_dex_account(),
+					lp_token_balance,
+				)?;
+			}
+
+			MergedLpTokens::<T>::insert(
+				duplicate_liquidity_pool.lp_token,
+				(liquidity_pool.asset_pair.clone(), lp_token_rate),
+			);
+
+			liquidity_pool.price = Pallet::<T>::compute_price(asset_x_balance.into_inner(), asset_y_balance.into_inner())
+				.unwrap_or(liquidity_pool.price);
+			liquidity_pool.asset_x_balance = asset_x_balance;
+			liquidity_pool.asset_y_balance = asset_y_balance;
+			liquidity_pool.asset_x_fee = liquidity_pool.asset_x_fee.ensure_add(duplicate_liquidity_pool.asset_x_fee)?;
+			liquidity_pool.asset_y_fee = liquidity_pool.asset_y_fee.ensure_add(duplicate_liquidity_pool.asset_y_fee)?;
+			liquidity_pool.lp_token_balance = liquidity_pool.lp_token_balance
+				.ensure_add(FixedU128::from_inner(lp_token_balance))?;
+
+			LiquidityPoolStorage::<T>::insert(liquidity_pool.asset_pair.clone(), liquidity_pool);
+
+			Ok(())
+		}
+
+		/// The baseline topped a pool up through either order of its pair and wrote the result
+		/// under the order used, so a reversed duplicate sharing the pool's LP token counts the
+		/// same deposits again, with its sides mixed up. Neither entry can be trusted, so such a
+		/// pool's reserves are rebuilt from what the DEX account holds beyond every other pool's
+		/// reserves, split by the stored reserves where several rebuilt pools hold the same asset.
+		/// Its LP token balance is what has been issued.
+		fn rebuild_liquidity_pools(asset_pairs: &[AssetPairs<T>]) -> (u64, u64) {
+			let humidefi_account_id = Pallet::<T>::get_dex_account();
+			let liquidity_pools = LiquidityPoolStorage::<T>::iter_values().collect::<Vec<_>>();
+			let reserves = |liquidity_pool: &LiquidityPool<T>| [
+				(liquidity_pool.asset_pair.asset_x, liquidity_pool.asset_x_balance.into_inner()),
+				(liquidity_pool.asset_pair.asset_y, liquidity_pool.asset_y_balance.into_inner()),
+			];
+
+			let mut reads = liquidity_pools.len() as u64;
+			let mut writes = 0u64;
+
+			for liquidity_pool in liquidity_pools.iter().filter(|liquidity_pool| asset_pairs.contains(&liquidity_pool.asset_pair)) {
+				let mut rebuilt_reserves = [0u128; 2];
+
+				for (index, (asset, asset_balance)) in reserves(liquidity_pool).into_iter().enumerate() {
+					let mut held_balance = Pallet::<T>::get_asset_balance(asset, humidefi_account_id.clone());
+					let mut rebuilt_balance = 0u128;
+
+					for other_liquidity_pool in liquidity_pools.iter() {
+						let is_rebuilt = asset_pairs.contains(&other_liquidity_pool.asset_pair);
+
+						for (other_asset, other_asset_balance) in reserves(other_liquidity_pool) {
+							if other_asset != asset {
+								continue
+							}
+
+							if is_rebuilt {
+								rebuilt_balance = rebuilt_balance.saturating_add(other_asset_balance);
+							} else {
+								held_balance = held_balance.saturating_sub(other_asset_balance);
+							}
+						}
+					}
+
+					rebuilt_reserves[index] = if rebuilt_balance == asset_balance {
+						held_balance
+					} else {
+						multiply_by_rational_with_rounding(held_balance, asset_balance, rebuilt_balance, Rounding::Down)
+							.unwrap_or_default()
+					};
+					reads += 1;
+				}
+
+				let [asset_x_balance, asset_y_balance] = rebuilt_reserves;
+				let lp_token_balance = <T::Fungibles as fungibles::Inspect<_>>::total_issuance(liquidity_pool.lp_token);
+				reads += 1;
+
+				frame_support::log::info!(
+					target: LOG_TARGET,
+					"Pool {:?} was stored in both orders, rebuilt its reserves as {:?} and {:?}",
+					liquidity_pool.asset_pair,
+					asset_x_balance,
+					asset_y_balance
+				);
+
+				LiquidityPoolStorage::<T>::insert(liquidity_pool.asset_pair.clone(), LiquidityPool::<T> {
+					asset_pair: liquidity_pool.asset_pair.clone(),
+					asset_x_balance: FixedU128::from_inner(asset_x_balance),
+					asset_y_balance: FixedU128::from_inner(asset_y_balance),
+					price: Pallet::<T>::compute_price(asset_x_balance, asset_y_balance).unwrap_or(liquidity_pool.price),
+					asset_x_fee: liquidity_pool.asset_x_fee,
+					asset_y_fee: liquidity_pool.asset_y_fee,
+					swap_fee: liquidity_pool.swap_fee,
+					lp_token: liquidity_pool.lp_token,
+					lp_token_balance: FixedU128::from_inner(lp_token_balance),
+				});
+				writes += 1;
+			}
+
+			(reads, writes)
+		}
+
+		/// Points a position in a merged duplicate at the canonical pool and its LP token.
+		fn merge_account_liquidity_pool(
+			mut account_liquidity_pool: AccountLiquidityPool<T>,
+		) -> AccountLiquidityPool<T> {
+			let merged_lp_token = MergedLpTokens::<T>::get(account_liquidity_pool.lp_token);
+
+			if let Some((asset_pair, lp_token_rate)) = merged_lp_token {
+				if let Some(liquidity_pool) = LiquidityPoolStorage::<T>::get(asset_pair) {
+					account_liquidity_pool.lp_token = liquidity_pool.lp_token;
+					account_liquidity_pool.lp_token_balance = lp_token_rate
+						.saturating_mul(account_liquidity_pool.lp_token_balance);
+				}
+			}
+
+			account_liquidity_pool
+		}
+
+		/// Pairs already in canonical order come first, so their pool and positions are the ones
+		/// a reversed duplicate is merged into.
+		fn canonical_first<K>(keys: Vec<K>, asset_pair: impl Fn(&K) -> v0::AssetPairs<T>) -> Vec<K> {
+			let (mut canonical_keys, reversed_keys): (Vec<_>, Vec<_>) = keys
+				.into_iter()
+				.partition(|key| !Self::migrate_asset_pair(asset_pair(key)).1);
+
+			canonical_keys.extend(reversed_keys);
+			canonical_keys
+		}
+
+		fn migrate_account_liquidity_pool(
+			account_liquidity_pool: v0::AccountLiquidityPool<T>,
+		) -> AccountLiquidityPool<T> {
+			let (asset_pair, is_reversed) = Self::migrate_asset_pair(account_liquidity_pool.asset_pair);
+
+			let (asset_x_balance, asset_y_balance) = if is_reversed {
+				(account_liquidity_pool.asset_y_balance, account_liquidity_pool.asset_x_balance)
+			} else {
+				(account_liquidity_pool.asset_x_balance, account_liquidity_pool.asset_y_balance)
+			};
+
+			AccountLiquidityPool::<T> {
+				id: account_liquidity_pool.id,
+				account_id: account_liquidity_pool.account_id,
+				asset_pair,
+				asset_x_balance,
+				asset_y_balance,
+				lp_token: account_liquidity_pool.lp_token,
+				lp_token_balance: account_liquidity_pool.lp_token_balance,
+			}
+		}
+	}
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToNativeOrAssetIds<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut reads = 1u64;
+			let mut writes = 1u64;
+
+			let mut rebuilt_asset_pairs = Vec::new();
+
+			// Entries are only moved once they decode, the rest stay where `post_upgrade` finds them.
+			let asset_pairs = Self::canonical_first(
+				v0::LiquidityPoolStorage::<T>::iter_keys().collect::<Vec<_>>(),
+				|asset_pair| asset_pair.clone(),
+			);
+
+			for asset_pair in asset_pairs {
+				reads += 2;
+
+				let liquidity_pool = match v0::LiquidityPoolStorage::<T>::get(&asset_pair) {
+					Some(liquidity_pool) => Self::migrate_liquidity_pool(liquidity_pool),
+					None => {
+						frame_support::log::error!(
+							target: LOG_TARGET,
+							"Pool {:?} does not decode, leaving it in place",
+							asset_pair
+						);
+						continue
+					},
+				};
+
+				match LiquidityPoolStorage::<T>::get(&liquidity_pool.asset_pair) {
+					// Both entries count the same deposits, so the duplicate is dropped and the pool
+					// rebuilt once every pool is in place.
+					Some(canonical_liquidity_pool) if canonical_liquidity_pool.lp_token == liquidity_pool.lp_token => {
+						if !rebuilt_asset_pairs.contains(&liquidity_pool.asset_pair) {
+							rebuilt_asset_pairs.push(liquidity_pool.asset_pair.clone());
+						}
+					},
+					Some(canonical_liquidity_pool) => {
+						reads += 4;
+						writes += 5;
+
+						let merge_result = with_storage_layer(|| {
+							Self::merge_liquidity_pool(canonical_liquidity_pool, liquidity_pool.clone())
+						});
+
+						if let Err(error) = merge_result {
+							frame_support::log::error!(
+								target: LOG_TARGET,
+								"Pool {:?} could not be merged into {:?}, leaving it in place: {:?}",
+								asset_pair,
+								liquidity_pool.asset_pair,
+								error
+							);
+							continue
+						}
+					},
+					None => {
+						LiquidityPoolStorage::<T>::insert(liquidity_pool.asset_pair.clone(), liquidity_pool);
+					},
+				}
+
+				v0::LiquidityPoolStorage::<T>::remove(&asset_pair);
+				writes += 2;
+			}
+
+			let (rebuild_reads, rebuild_writes) = Self::rebuild_liquidity_pools(&rebuilt_asset_pairs);
+			reads += rebuild_reads;
+			writes += rebuild_writes;
+
+			let storage_keys = Self::canonical_first(
+				v0::AccountLiquidityPoolStorage::<T>::iter_keys().collect::<Vec<_>>(),
+				|(_, asset_pair)| asset_pair.clone(),
+			);
+
+			for (account_id, asset_pair) in storage_keys {
+				reads += 3;
+
+				let storage_key = (account_id.clone(), asset_pair.clone());
+				let account_liquidity_pools = match v0::AccountLiquidityPoolStorage::<T>::get(&storage_key) {
+					Some(account_liquidity_pools) => account_liquidity_pools
+						.into_iter()
+						.map(Self::migrate_account_liquidity_pool)
+						.map(Self::merge_account_liquidity_pool)
+						.collect::<Vec<_>>(),
+					None => {
+						frame_support::log::error!(
+							target: LOG_TARGET,
+							"Positions of {:?} in {:?} do not decode, leaving them in place",
+							account_id,
+							asset_pair
+						);
+						continue
+					},
+				};
+
+				let (migrated_asset_pair, _) = Self::migrate_asset_pair(asset_pair);
+				let migrated_storage_key = (account_id.clone(), migrated_asset_pair);
+
+				// Positions held in both orders share one list, numbered on from the ones already in it.
+				let mut merged_account_liquidity_pools = AccountLiquidityPoolStorage::<T>::get(&migrated_storage_key)
+					.unwrap_or_default();
+				let is_merged = !merged_account_liquidity_pools.is_empty();
+				let mut is_full = false;
+
+				for mut account_liquidity_pool in account_liquidity_pools {
+					if is_merged {
+						let last_id = merged_account_liquidity_pools
+							.last()
+							.map_or(0u64, |last_account_liquidity_pool| last_account_liquidity_pool.id);
+						account_liquidity_pool.id = last_id.saturating_add(1);
+					}
+
+					if merged_account_liquidity_pools.try_push(account_liquidity_pool).is_err() {
+						is_full = true;
+						break
+					}
+				}
+
+				if is_full {
+					frame_support::log::error!(
+						target: LOG_TARGET,
+						"Positions of {:?} in {:?} do not fit next to {:?}, leaving them in place",
+						account_id,
+						storage_key.1,
+						migrated_storage_key.1
+					);
+					continue
+				}
+
+				v0::AccountLiquidityPoolStorage::<T>::remove(&storage_key);
+				AccountLiquidityPoolStorage::<T>::insert(migrated_storage_key, merged_account_liquidity_pools);
+				writes += 2;
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			let liquidity_pools = v0::LiquidityPoolStorage::<T>::iter_values().collect::<Vec<_>>();
+			ensure!(
+				count_raw_keys(v0::LiquidityPoolStorage::<T>::final_prefix()) == liquidity_pools.len() as u64,
+				"Some pools do not decode as the baseline layout"
+			);
+
+			let storage_keys = v0::AccountLiquidityPoolStorage::<T>::iter()
+				.map(|(storage_key, _)| storage_key)
+				.collect::<Vec<_>>();
+			ensure!(
+				count_raw_keys(v0::AccountLiquidityPoolStorage::<T>::final_prefix()) == storage_keys.len() as u64,
+				"Some positions do not decode as the baseline layout"
+			);
+
+			// Pools and positions in reversed duplicates end up under the same canonical key.
+			let pool_count = liquidity_pools
+				.into_iter()
+				.map(|liquidity_pool| Self::migrate_asset_pair(liquidity_pool.asset_pair).0.encode())
+				.collect::<BTreeSet<_>>()
+				.len() as u64;
+
+			let position_count = storage_keys
+				.into_iter()
+				.map(|(account_id, asset_pair)| (account_id, Self::migrate_asset_pair(asset_pair).0).encode())
+				.collect::<BTreeSet<_>>()
+				.len() as u64;
+
+			Ok((pool_count, position_count).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 1,
+				"Storage version was not updated"
+			);
+
+			let (pool_count, position_count) = <(u64, u64)>::decode(&mut &state[..])
+				.map_err(|_| "Failed to decode pool and position counts")?;
+
+			// Anything left under a baseline key is counted by the raw keys but does not decode.
+			ensure!(
+				LiquidityPoolStorage::<T>::iter_values().count() as u64 == pool_count,
+				"Some pools failed to migrate"
+			);
+			ensure!(
+				count_raw_keys(LiquidityPoolStorage::<T>::final_prefix()) == pool_count,
+				"Some pools were left under their baseline key"
+			);
+
+			ensure!(
+				AccountLiquidityPoolStorage::<T>::iter_values().count() as u64 == position_count,
+				"Some positions failed to migrate"
+			);
+			ensure!(
+				count_raw_keys(AccountLiquidityPoolStorage::<T>::final_prefix()) == position_count,
+				"Some positions were left under their baseline key"
+			);
+
+			for (_, (asset_pair, _)) in MergedLpTokens::<T>::iter() {
+				ensure!(
+					LiquidityPoolStorage::<T>::contains_key(asset_pair),
+					"Merged LP token refers to a pool that does not exist"
+				);
+			}
+
+			// Every reserve is still held by the DEX account, which `v2` moves them out of.
+			let mut asset_balances = BTreeMap::<NativeOrAssetIdOf<T>, u128>::new();
+			for liquidity_pool in LiquidityPoolStorage::<T>::iter_values() {
+				for (asset, asset_balance) in [
+					(liquidity_pool.asset_pair.asset_x, liquidity_pool.asset_x_balance),
+					(liquidity_pool.asset_pair.asset_y, liquidity_pool.asset_y_balance),
+				] {
+					let total_asset_balance = asset_balances.entry(asset).or_default();
+					*total_asset_balance = total_asset_balance.saturating_add(asset_balance.into_inner());
+				}
+			}
+
+			let humidefi_account_id = Pallet::<T>::get_dex_account();
+			for (asset, total_asset_balance) in asset_balances {
+				ensure!(
+					Pallet::<T>::get_asset_balance(asset, humidefi_account_id.clone()) >= total_asset_balance,
+					"Pool reserves exceed what the DEX account holds"
+				);
+			}
+
+			Ok(())
+		}
+	}
+}
+
+pub mod v2 {
+	use super::*;
+
+	/// Moves the reserves of every pool, fees included since v1, out of the shared DEX account
+	/// and into the pool's own sub-account.
+	pub struct MigrateToPoolAccounts<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToPoolAccounts<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let humidefi_account_id = Pallet::<T>::get_dex_account();
+			let mut reads = 1u64;
+			let mut writes = 1u64;
+			let mut failed_asset_pairs = Vec::new();
+
+			for (_, liquidity_pool) in v1::LiquidityPoolStorage::<T>::iter() {
+				let asset_pair = liquidity_pool.asset_pair.clone();
+				let pool_account_id = Pallet::<T>::get_pool_account(asset_pair.clone());
+				reads += 7;
+				writes += 7;
+
+				// Both assets move or neither does, so a pool is never split across two accounts.
+				let move_result = with_storage_layer(|| -> DispatchResult {
+					frame_system::Pallet::<T>::inc_providers(&pool_account_id);
+
+					for (asset, asset_balance) in [
+						(asset_pair.asset_x, liquidity_pool.asset_x_balance),
+						(asset_pair.asset_y, liquidity_pool.asset_y_balance),
+					] {
+						if asset_balance.is_zero() {
+							continue
+						}
+
+						Pallet::<T>::transfer_pool_asset(
+							asset,
+							humidefi_account_id.clone(),
+							pool_account_id.clone(),
+							asset_balance.into_inner(),
+						)?;
+					}
+
+					Ok(())
+				});
+
+				if let Err(error) = move_result {
+					frame_support::log::error!(
+						target: LOG_TARGET,
+						"Reserves of pool {:?} could not move to its pool account: {:?}",
+						asset_pair,
+						error
+					);
+					failed_asset_pairs.push(asset_pair);
+				}
+			}
+
+			// Failed pools keep their reserves on the DEX account, which `post_upgrade` rejects.
+			if !failed_asset_pairs.is_empty() {
+				frame_support::log::error!(
+					target: LOG_TARGET,
+					"{} pools were left on the DEX account: {:?}",
+					failed_asset_pairs.len(),
+					failed_asset_pairs
+				);
+			}
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			let pool_count = v1::LiquidityPoolStorage::<T>::iter_values().count() as u64;
+			ensure!(
+				count_raw_keys(v1::LiquidityPoolStorage::<T>::final_prefix()) == pool_count,
+				"Some pools do not decode"
+			);
+
+			Ok(pool_count.encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 2,
+				"Storage version was not updated"
+			);
+
+			let pool_count = u64::decode(&mut &state[..]).map_err(|_| "Failed to decode pool count")?;
+			let mut migrated_pool_count = 0u64;
+
+			for liquidity_pool in v1::LiquidityPoolStorage::<T>::iter_values() {
+				let pool_account_id = Pallet::<T>::get_pool_account(liquidity_pool.asset_pair.clone());
+
+				for (asset, asset_balance) in [
+					(liquidity_pool.asset_pair.asset_x, liquidity_pool.asset_x_balance),
+					(liquidity_pool.asset_pair.asset_y, liquidity_pool.asset_y_balance),
+				] {
+					ensure!(
+						Pallet::<T>::get_asset_balance(asset, pool_account_id.clone()) >= asset_balance.into_inner(),
+						"Some pool reserves were left on the DEX account"
+					);
+				}
+
+				migrated_pool_count += 1;
+			}
+
+			ensure!(migrated_pool_count == pool_count, "Some pools failed to migrate");
+
+			Ok(())
+		}
+	}
+}
+
+pub mod v3 {
+	use super::*;
+
+	/// Adds `status` to every pool, starting out as `PoolStatus::Active`.
+	pub struct AddPoolStatus<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for AddPoolStatus<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 2 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated = 0u64;
+			LiquidityPoolStorage::<T>::translate::<v1::LiquidityPool<T>, _>(|_, liquidity_pool| {
+				translated += 1;
+
+				Some(LiquidityPool::<T> {
+					asset_pair: liquidity_pool.asset_pair,
+					asset_x_balance: liquidity_pool.asset_x_balance,
+					asset_y_balance: liquidity_pool.asset_y_balance,
+					price: liquidity_pool.price,
+					asset_x_fee: liquidity_pool.asset_x_fee,
+					asset_y_fee: liquidity_pool.asset_y_fee,
+					swap_fee: liquidity_pool.swap_fee,
+					lp_token: liquidity_pool.lp_token,
+					lp_token_balance: liquidity_pool.lp_token_balance,
+					status: PoolStatus::Active,
+				})
+			});
+
+			StorageVersion::new(3).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			Ok((v1::LiquidityPoolStorage::<T>::iter_keys().count() as u64).encode())
+		}
+
+		// Positions are only decodable again after `v4`, which runs `do_try_state` once done.
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 3,
+				"Storage version was not updated"
+			);
+
+			let pool_count = u64::decode(&mut &state[..]).map_err(|_| "Failed to decode pool count")?;
+			ensure!(
+				LiquidityPoolStorage::<T>::iter_values().count() as u64 == pool_count,
+				"Some pools failed to migrate"
+			);
+
+			Ok(())
+		}
+	}
+}
+
+pub mod v4 {
+	use super::*;
+
+	/// Adds `range` to every liquidity position. Existing positions are all backed by LP tokens.
+	pub struct AddPositionRanges<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for AddPositionRanges<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 3 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated = 0u64;
+			AccountLiquidityPoolStorage::<T>::translate::<BoundedVec<v1::AccountLiquidityPool<T>, ConstU32<100>>, _>(
+				|_, account_liquidity_pools| {
+					translated += 1;
+
+					let account_liquidity_pools = account_liquidity_pools
+						.into_iter()
+						.map(|account_liquidity_pool| AccountLiquidityPool::<T> {
+							id: account_liquidity_pool.id,
+							account_id: account_liquidity_pool.account_id,
+							asset_pair: account_liquidity_pool.asset_pair,
+							asset_x_balance: account_liquidity_pool.asset_x_balance,
+							asset_y_balance: account_liquidity_pool.asset_y_balance,
+							lp_token: account_liquidity_pool.lp_token,
+							lp_token_balance: account_liquidity_pool.lp_token_balance,
+							range: None,
+						})
+						.collect::<Vec<_>>();
+
+					// Same bound as before, so every position fits.
+					BoundedVec::try_from(account_liquidity_pools).ok()
+				}
+			);
+
+			STORAGE_VERSION.put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			Ok((AccountLiquidityPoolStorage::<T>::iter_keys().count() as u64).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == STORAGE_VERSION,
+				"Storage version was not updated"
+			);
+
+			let position_count = u64::decode(&mut &state[..]).map_err(|_| "Failed to decode position count")?;
+			ensure!(
+				AccountLiquidityPoolStorage::<T>::iter_values().count() as u64 == position_count,
+				"Some positions failed to migrate"
+			);
+
+			Pallet::<T>::do_try_state()
+		}
+	}
+}
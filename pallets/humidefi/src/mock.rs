@@ -1,11 +1,15 @@
 use crate as pallet_dex;
-use frame_support::traits::{AsEnsureOriginWithArg, ConstU128, ConstU16, ConstU32, ConstU64};
+use frame_support::{
+	parameter_types,
+	traits::{AsEnsureOriginWithArg, ConstU128, ConstU16, ConstU32, ConstU64},
+};
 use frame_system::{EnsureRoot, EnsureSigned};
 use sp_core::H256;
 use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup},
 	BuildStorage,
-	FixedU128
+	FixedU128,
+	Perbill,
 };
 use sp_runtime::traits::Convert;
 use pallet_dex::AssetBalanceOf;
@@ -20,7 +24,7 @@ frame_support::construct_runtime!(
 		System: frame_system,
 		Balances: pallet_balances,
 		Assets: pallet_assets,
-		Humidefi: pallet_humidefi,
+		Humidefi: pallet_dex,
 	}
 );
 
@@ -50,11 +54,15 @@ impl frame_system::Config for Test {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+parameter_types! {
+	pub static ExistentialDeposit: Balance = 1;
+}
+
 impl pallet_balances::Config for Test {
 	type Balance = Balance;
 	type DustRemoval = ();
 	type RuntimeEvent = RuntimeEvent;
-	type ExistentialDeposit = ConstU128<1>;
+	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
 	type WeightInfo = ();
 	type MaxLocks = ConstU32<10>;
@@ -89,10 +97,28 @@ impl pallet_assets::Config for Test {
 	type BenchmarkHelper = ();
 }
 
+parameter_types! {
+	pub const DefaultSwapFee: Perbill = Perbill::from_parts(3_000_000);
+	pub static MaxPriceMovePerBlock: Perbill = Perbill::zero();
+}
+
 impl pallet_dex::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type NativeBalance = Balances;
 	type Fungibles = Assets;
+	type DefaultSwapFee = DefaultSwapFee;
+	type MaxSwapPathLength = ConstU32<4>;
+	type PoolCreateOrigin = EnsureSigned<u64>;
+	type AdminOrigin = EnsureRoot<u64>;
+	type PauseOrigin = EnsureRoot<u64>;
+	type MaxPriceMovePerBlock = MaxPriceMovePerBlock;
+	type PriceObservationPeriod = ConstU64<10>;
+	type MaxPriceObservations = ConstU32<24>;
+	type MaxTicksPerPool = ConstU32<64>;
+	type MaxLimitOrdersPerAccount = ConstU32<16>;
+	type MaxDcaSchedulesPerAccount = ConstU32<16>;
+	type MaxDcaRunsPerBlock = ConstU32<8>;
+	type WeightInfo = ();
 }
 
 pub struct AssetBalanceConverter;
@@ -108,6 +134,12 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap().into();
 
 	RuntimeGenesisConfig {
+		balances: BalancesConfig {
+			balances: vec![
+				(1, 1_000_000_000_000_000_000_000_000_000),
+				(2, 50_000_000_000_000_000_000_000_000),
+			],
+		},
 		assets: AssetsConfig {
 			assets: vec![
 				(1, 1, true, 1_000_000_000_000_000_000_000),
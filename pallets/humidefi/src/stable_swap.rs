@@ -0,0 +1,159 @@
+//! Curve-style StableSwap math for two-asset pools.
+//!
+//! The invariant is `A·n^n·(x + y) + D = A·D·n^n + D^(n+1) / (n^n·x·y)` with `n = 2`. Reserves of
+//! both assets are compared one to one, so they should use the same number of decimals.
+
+use frame_support::sp_runtime::{
+	traits::{CheckedAdd, CheckedDiv, CheckedMul},
+	FixedPointNumber,
+	FixedU128,
+};
+use sp_core::U256;
+
+const N_COINS: u32 = 2;
+const MAX_ITERATIONS: usize = 255;
+
+/// `A·n^n`, the amplification as it appears in the invariant.
+fn ann(amplification: u32) -> Option<U256> {
+	U256::from(amplification).checked_mul(U256::from(N_COINS.pow(N_COINS)))
+}
+
+fn has_converged(value: U256, previous_value: U256) -> bool {
+	if value > previous_value {
+		value - previous_value <= U256::one()
+	} else {
+		previous_value - value <= U256::one()
+	}
+}
+
+fn compute_d_u256(reserve_x: U256, reserve_y: U256, ann: U256) -> Option<U256> {
+	let n = U256::from(N_COINS);
+	let sum = reserve_x.checked_add(reserve_y)?;
+	if sum.is_zero() {
+		return Some(U256::zero());
+	}
+
+	if reserve_x.is_zero() || reserve_y.is_zero() {
+		return None;
+	}
+
+	let mut d = sum;
+	for _ in 0..MAX_ITERATIONS {
+		// d_p = D^3 / (n^n·x·y)
+		let d_p = d
+			.checked_mul(d)?
+			.checked_div(reserve_x.checked_mul(n)?)?
+			.checked_mul(d)?
+			.checked_div(reserve_y.checked_mul(n)?)?;
+
+		let previous_d = d;
+		let numerator = ann.checked_mul(sum)?.checked_add(d_p.checked_mul(n)?)?.checked_mul(d)?;
+		let denominator = ann
+			.checked_sub(U256::one())?
+			.checked_mul(d)?
+			.checked_add(n.checked_add(U256::one())?.checked_mul(d_p)?)?;
+		d = numerator.checked_div(denominator)?;
+
+		if has_converged(d, previous_d) {
+			return Some(d);
+		}
+	}
+
+	None
+}
+
+/// Solves the invariant for the other reserve once one side is moved to `reserve_in`.
+fn compute_y_u256(reserve_in: U256, d: U256, ann: U256) -> Option<U256> {
+	let n = U256::from(N_COINS);
+	if reserve_in.is_zero() {
+		return None;
+	}
+
+	// c = D^3 / (n^n·x·Ann), b = x + D / Ann
+	let c = d
+		.checked_mul(d)?
+		.checked_div(reserve_in.checked_mul(n)?)?
+		.checked_mul(d)?
+		.checked_div(ann.checked_mul(n)?)?;
+	let b = reserve_in.checked_add(d.checked_div(ann)?)?;
+
+	let mut y = d;
+	for _ in 0..MAX_ITERATIONS {
+		let previous_y = y;
+		let numerator = y.checked_mul(y)?.checked_add(c)?;
+		let denominator = y.checked_mul(n)?.checked_add(b)?.checked_sub(d)?;
+		y = numerator.checked_div(denominator)?;
+
+		if has_converged(y, previous_y) {
+			return Some(y);
+		}
+	}
+
+	None
+}
+
+/// The invariant `D` of a pool holding `reserve_x` and `reserve_y`.
+pub fn compute_d(reserve_x: u128, reserve_y: u128, amplification: u32) -> Option<u128> {
+	compute_d_u256(U256::from(reserve_x), U256::from(reserve_y), ann(amplification)?)?.try_into().ok()
+}
+
+/// Amount of the other asset paid out for `amount_in`, rounded down.
+pub fn compute_amount_out(
+	reserve_in: u128,
+	reserve_out: u128,
+	amount_in: u128,
+	amplification: u32,
+) -> Option<u128> {
+	let ann = ann(amplification)?;
+	let d = compute_d_u256(U256::from(reserve_in), U256::from(reserve_out), ann)?;
+	let update_reserve_in = U256::from(reserve_in).checked_add(U256::from(amount_in))?;
+	let update_reserve_out = compute_y_u256(update_reserve_in, d, ann)?;
+
+	// One unit is held back so rounding in `compute_y` always favours the pool.
+	U256::from(reserve_out)
+		.checked_sub(update_reserve_out)?
+		.checked_sub(U256::one())?
+		.try_into()
+		.ok()
+}
+
+/// Amount of the other asset needed to take `amount_out` out of the pool, rounded up.
+pub fn compute_amount_in(
+	reserve_in: u128,
+	reserve_out: u128,
+	amount_out: u128,
+	amplification: u32,
+) -> Option<u128> {
+	let ann = ann(amplification)?;
+	let d = compute_d_u256(U256::from(reserve_in), U256::from(reserve_out), ann)?;
+	let update_reserve_out = U256::from(reserve_out).checked_sub(U256::from(amount_out))?;
+	let update_reserve_in = compute_y_u256(update_reserve_out, d, ann)?;
+
+	update_reserve_in
+		.checked_sub(U256::from(reserve_in))?
+		.checked_add(U256::one())?
+		.try_into()
+		.ok()
+}
+
+/// Marginal price of `asset_x` in `asset_y`, i.e. `-dy/dx` along the invariant.
+pub fn compute_price(reserve_x: u128, reserve_y: u128, amplification: u32) -> Option<FixedU128> {
+	let d = compute_d(reserve_x, reserve_y, amplification)?;
+	if d == 0 {
+		return None;
+	}
+
+	// With a = x / D and b = y / D:  price = (4·Ann·a²b² + b) / (4·Ann·a²b² + a)
+	let a = FixedU128::checked_from_rational(reserve_x, d)?;
+	let b = FixedU128::checked_from_rational(reserve_y, d)?;
+	let ann = FixedU128::checked_from_integer(u128::from(amplification).checked_mul(u128::from(N_COINS.pow(N_COINS)))?)?;
+
+	let weight = FixedU128::checked_from_integer(u128::from(N_COINS.pow(N_COINS)))?
+		.checked_mul(&ann)?
+		.checked_mul(&a)?
+		.checked_mul(&a)?
+		.checked_mul(&b)?
+		.checked_mul(&b)?;
+
+	weight.checked_add(&b)?.checked_div(&weight.checked_add(&a)?)
+}
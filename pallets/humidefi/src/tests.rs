@@ -1,13 +1,15 @@
-use crate::{mock::{self, *}, AssetPairs, Error, Event};
-use frame_support::{assert_noop, assert_ok};
+use crate::{migrations, mock::{self, *}, AssetListingStatus, AssetPairs, Error, Event, ListingMode, NativeOrAssetId, PoolKind, PoolStatus, RedeemLiquidityAmount, MINIMUM_LIQUIDITY};
+use frame_support::{assert_err, assert_noop, assert_ok};
 use frame_support::{
 	sp_runtime::{
-		traits::{Convert, EnsureAdd, EnsureDiv, EnsureMul, EnsureSub, IntegerSquareRoot, Zero},
-		ArithmeticError, FixedU128, Perbill,
+		helpers_128bit::multiply_by_rational_with_rounding,
+		traits::{Convert, EnsureAdd, EnsureSub, IntegerSquareRoot, Zero},
+		ArithmeticError, FixedPointNumber, FixedU128, Perbill, Rounding,
 	},
-	traits::{fungible, fungibles},
+	traits::{fungible, fungibles, GetStorageVersion, Hooks, OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
 };
-use interfaces::humidefi::HumidefiHelpers;
+use interfaces::humidefi::{HumidefiInspect, PriceOracle};
 
 #[test]
 fn create_liquidity_pool_works() {
@@ -17,10 +19,2001 @@ fn create_liquidity_pool_works() {
 		let first_balance_b: u128 = 20_000_000_000_000_000_000_000_000;
 
 		let asset_pairs = AssetPairs {
-			asset_x: 1,
-			asset_y: 2
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
 		};
 
-		assert_ok!(Humidefi::new_liquidity(bob.clone(), asset_pairs, first_balance_a, first_balance_b));
+		assert_ok!(Humidefi::new_liquidity(bob.clone(), asset_pairs, first_balance_a, first_balance_b, 0));
+	});
+}
+
+#[test]
+fn swap_exact_in_for_out_charges_swap_fee() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		assert_ok!(Humidefi::swap_exact_in_for_out(alice.clone(), NativeOrAssetId::Asset(1), 1_000_000_000_000_000_000, NativeOrAssetId::Asset(2), 0, None));
+
+		let liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs).unwrap();
+		assert_eq!(liquidity_pool.asset_x_fee, FixedU128::from_inner(3_000_000_000_000_000));
+		assert_eq!(liquidity_pool.asset_y_fee, FixedU128::from_inner(0));
+		assert_eq!(
+			liquidity_pool.asset_x_balance,
+			FixedU128::from_inner(1_001_000_000_000_000_000_000)
+		);
+	});
+}
+
+#[test]
+fn swap_exact_in_for_out_enforces_min_amount_out() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs,
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		assert_err!(
+			Humidefi::swap_exact_in_for_out(
+				alice.clone(),
+				NativeOrAssetId::Asset(1),
+				1_000_000_000_000_000_000,
+				NativeOrAssetId::Asset(2),
+				2_000_000_000_000_000_000,
+				None
+			),
+			Error::<Test>::SlippageExceeded
+		);
+	});
+}
+
+#[test]
+fn swap_in_for_exact_out_rejects_expired_deadline() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs,
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		System::set_block_number(10);
+
+		assert_noop!(
+			Humidefi::swap_in_for_exact_out(
+				alice.clone(),
+				NativeOrAssetId::Asset(2),
+				1_000_000_000_000_000_000,
+				NativeOrAssetId::Asset(1),
+				u128::MAX,
+				Some(9)
+			),
+			Error::<Test>::DeadlineExpired
+		);
+	});
+}
+
+#[test]
+fn swap_in_for_exact_out_rounds_the_swap_fee_up() {
+	new_test_ext().execute_with(|| {
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		// 1 / 0.997 and 1_000_000 / 0.997 both fall short of the next unit.
+		assert_eq!(Humidefi::compute_swap_fee_exact_out(asset_pairs.clone(), 1), Ok((1, 2)));
+		assert_eq!(Humidefi::compute_swap_fee_exact_out(asset_pairs.clone(), 1_000_000), Ok((3_010, 1_003_010)));
+		assert_eq!(Humidefi::compute_swap_fee_exact_out(asset_pairs, 997_000), Ok((3_000, 1_000_000)));
+	});
+}
+
+#[test]
+fn swap_exact_in_for_out_follows_constant_product() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		let alice_asset_y_balance = Assets::balance(2, 1);
+		assert_ok!(Humidefi::swap_exact_in_for_out(alice.clone(), NativeOrAssetId::Asset(1), 1_000_000_000_000_000_000, NativeOrAssetId::Asset(2), 0, None));
+
+		// 2_000e18 * 0.997e18 / (1_000e18 + 0.997e18)
+		assert_eq!(Assets::balance(2, 1) - alice_asset_y_balance, 1_992_013_962_079_806_432);
+
+		let liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs).unwrap();
+		assert_eq!(
+			liquidity_pool.asset_y_balance,
+			FixedU128::from_inner(1_998_007_986_037_920_193_568)
+		);
+	});
+}
+
+#[test]
+fn redeem_liquidity_pays_pool_share_and_burns_lp_token() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		assert_ok!(Humidefi::swap_exact_in_for_out(alice.clone(), NativeOrAssetId::Asset(1), 1_000_000_000_000_000_000, NativeOrAssetId::Asset(2), 0, None));
+
+		let liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap();
+		let bob_asset_x_balance = Assets::balance(1, 2);
+		let bob_asset_y_balance = Assets::balance(2, 2);
+
+		assert_ok!(Humidefi::redeem_liquidity(bob.clone(), asset_pairs.clone(), liquidity_pool.lp_token, 1));
+
+		// Bob holds everything but the locked minimum liquidity, and the swap fee sits in the reserves.
+		let lp_token_supply = liquidity_pool.lp_token_balance.into_inner();
+		let bob_lp_token_balance = lp_token_supply - MINIMUM_LIQUIDITY;
+
+		assert_eq!(
+			Assets::balance(1, 2) - bob_asset_x_balance,
+			multiply_by_rational_with_rounding(
+				liquidity_pool.asset_x_balance.into_inner(),
+				bob_lp_token_balance,
+				lp_token_supply,
+				Rounding::Down,
+			).unwrap()
+		);
+		assert_eq!(
+			Assets::balance(2, 2) - bob_asset_y_balance,
+			multiply_by_rational_with_rounding(
+				liquidity_pool.asset_y_balance.into_inner(),
+				bob_lp_token_balance,
+				lp_token_supply,
+				Rounding::Down,
+			).unwrap()
+		);
+		assert_eq!(Assets::balance(liquidity_pool.lp_token, 2), 0);
+
+		let liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs).unwrap();
+		assert_eq!(liquidity_pool.lp_token_balance.into_inner(), MINIMUM_LIQUIDITY);
+	});
+}
+
+#[test]
+fn redeem_liquidity_does_not_pay_earlier_swap_fees_to_new_lps() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		for _ in 0..10 {
+			assert_ok!(Humidefi::swap_exact_in_for_out(alice.clone(), NativeOrAssetId::Asset(1), 10_000_000_000_000_000_000, NativeOrAssetId::Asset(2), 0, None));
+			assert_ok!(Humidefi::swap_exact_in_for_out(alice.clone(), NativeOrAssetId::Asset(2), 20_000_000_000_000_000_000, NativeOrAssetId::Asset(1), 0, None));
+		}
+
+		let liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap();
+		assert!(!liquidity_pool.asset_x_fee.is_zero());
+		assert!(!liquidity_pool.asset_y_fee.is_zero());
+
+		// Alice joins right after the fees were earned and leaves in the same block.
+		let alice_asset_x_balance = Assets::balance(1, 1);
+		let alice_asset_y_balance = Assets::balance(2, 1);
+
+		assert_ok!(Humidefi::new_liquidity(
+			alice.clone(),
+			asset_pairs.clone(),
+			100_000_000_000_000_000_000,
+			1_000_000_000_000_000_000_000,
+			0
+		));
+
+		let asset_x_deposit = alice_asset_x_balance - Assets::balance(1, 1);
+		let asset_y_deposit = alice_asset_y_balance - Assets::balance(2, 1);
+		let alice_asset_x_balance = Assets::balance(1, 1);
+		let alice_asset_y_balance = Assets::balance(2, 1);
+
+		assert_ok!(Humidefi::redeem_liquidity(alice, asset_pairs, liquidity_pool.lp_token, 1));
+
+		// The fees stay with the LPs that were in the pool when they were paid.
+		let asset_x_redeemed = Assets::balance(1, 1) - alice_asset_x_balance;
+		let asset_y_redeemed = Assets::balance(2, 1) - alice_asset_y_balance;
+		assert!(asset_x_redeemed <= asset_x_deposit && asset_x_deposit - asset_x_redeemed <= 1_000);
+		assert!(asset_y_redeemed <= asset_y_deposit && asset_y_deposit - asset_y_redeemed <= 1_000);
+
+		assert_ok!(Humidefi::do_try_state());
+	});
+}
+
+#[test]
+fn redeem_liquidity_keeps_other_positions() {
+	new_test_ext().execute_with(|| {
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		let lp_token = Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap().lp_token;
+
+		assert_noop!(
+			Humidefi::redeem_liquidity(bob.clone(), asset_pairs.clone(), lp_token, 3),
+			Error::<Test>::AccountLiquidityPoolIdDoesNotExists
+		);
+
+		assert_noop!(
+			Humidefi::redeem_liquidity(bob.clone(), asset_pairs.clone(), lp_token + 1, 1),
+			Error::<Test>::AccountLiquidityPoolTokenMismatch
+		);
+
+		assert_ok!(Humidefi::redeem_liquidity(bob.clone(), asset_pairs.clone(), lp_token, 1));
+
+		let account_liquidity_pools = Humidefi::account_liquidity_pool_storage((2u64, asset_pairs)).unwrap();
+		assert_eq!(account_liquidity_pools.len(), 1);
+		assert_eq!(account_liquidity_pools[0].id, 2);
+	});
+}
+
+#[test]
+fn redeem_liquidity_partial_reduces_position_in_place() {
+	new_test_ext().execute_with(|| {
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			1_000_000_000_000_000_000_000,
+			0
+		));
+
+		let liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap();
+		let account_liquidity_pool = Humidefi::account_liquidity_pool_storage((2u64, asset_pairs.clone())).unwrap()[0].clone();
+		let bob_asset_x_balance = Assets::balance(1, 2);
+
+		assert_ok!(Humidefi::redeem_liquidity_partial(
+			bob.clone(),
+			asset_pairs.clone(),
+			liquidity_pool.lp_token,
+			1,
+			RedeemLiquidityAmount::Portion(Perbill::from_percent(50))
+		));
+
+		let redeemed_lp_token_balance = account_liquidity_pool.lp_token_balance.into_inner() / 2;
+		let updated_account_liquidity_pool = Humidefi::account_liquidity_pool_storage((2u64, asset_pairs.clone())).unwrap()[0].clone();
+
+		assert_eq!(updated_account_liquidity_pool.id, 1);
+		assert_eq!(
+			updated_account_liquidity_pool.lp_token_balance.into_inner(),
+			account_liquidity_pool.lp_token_balance.into_inner() - redeemed_lp_token_balance
+		);
+		assert_eq!(
+			account_liquidity_pool.lp_token_balance.into_inner(),
+			liquidity_pool.lp_token_balance.into_inner() - MINIMUM_LIQUIDITY
+		);
+		assert_eq!(
+			updated_account_liquidity_pool.asset_x_balance,
+			FixedU128::from_inner(500_000_000_000_000_000_000)
+		);
+		// Half of bob's LP tokens, which is a little under half of the supply.
+		assert_eq!(Assets::balance(1, 2) - bob_asset_x_balance, 499_999_999_999_999_999_500);
+		assert_eq!(
+			Assets::balance(liquidity_pool.lp_token, 2),
+			account_liquidity_pool.lp_token_balance.into_inner() - redeemed_lp_token_balance
+		);
+	});
+}
+
+#[test]
+fn new_liquidity_deposits_at_pool_ratio() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		// The first deposit has to mint more than the liquidity it leaves locked.
+		assert_noop!(
+			Humidefi::new_liquidity(bob.clone(), asset_pairs.clone(), 1_000, 1_000, 0),
+			Error::<Test>::InsufficientLiquidity
+		);
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		assert_noop!(
+			Humidefi::new_liquidity(
+				alice.clone(),
+				asset_pairs.clone(),
+				100_000_000_000_000_000_000,
+				500_000_000_000_000_000_000,
+				200_000_000_000_000_000_000
+			),
+			Error::<Test>::SlippageExceeded
+		);
+
+		let alice_asset_x_balance = Assets::balance(1, 1);
+		let alice_asset_y_balance = Assets::balance(2, 1);
+
+		assert_ok!(Humidefi::new_liquidity(
+			alice.clone(),
+			asset_pairs.clone(),
+			100_000_000_000_000_000_000,
+			500_000_000_000_000_000_000,
+			100_000_000_000_000_000_000
+		));
+
+		// Only the asset Y matching the 1:2 pool ratio is taken.
+		assert_eq!(alice_asset_x_balance - Assets::balance(1, 1), 100_000_000_000_000_000_000);
+		assert_eq!(alice_asset_y_balance - Assets::balance(2, 1), 200_000_000_000_000_000_000);
+
+		let liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap();
+		assert_eq!(liquidity_pool.asset_x_balance, FixedU128::from_inner(1_100_000_000_000_000_000_000));
+		assert_eq!(liquidity_pool.asset_y_balance, FixedU128::from_inner(2_200_000_000_000_000_000_000));
+
+		let alice_position = Humidefi::account_liquidity_pool_storage((1u64, asset_pairs)).unwrap()[0].clone();
+		assert_eq!(alice_position.asset_y_balance, FixedU128::from_inner(200_000_000_000_000_000_000));
+	});
+}
+
+#[test]
+fn new_liquidity_leaves_unbalanced_excess_with_the_depositor() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		let liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap();
+		let alice_asset_x_balance = Assets::balance(1, 1);
+		let alice_asset_y_balance = Assets::balance(2, 1);
+
+		// Asset X is in excess this time, so only what matches the asset Y is taken.
+		assert_ok!(Humidefi::new_liquidity(
+			alice.clone(),
+			asset_pairs.clone(),
+			300_000_000_000_000_000_000,
+			200_000_000_000_000_000_000,
+			0
+		));
+
+		assert_eq!(alice_asset_x_balance - Assets::balance(1, 1), 100_000_000_000_000_000_000);
+		assert_eq!(alice_asset_y_balance - Assets::balance(2, 1), 200_000_000_000_000_000_000);
+		assert_eq!(
+			Assets::balance(liquidity_pool.lp_token, 1),
+			multiply_by_rational_with_rounding(liquidity_pool.lp_token_balance.into_inner(), 1, 10, Rounding::Down).unwrap()
+		);
+
+		// The existing LPs keep their price.
+		let update_liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap();
+		assert_eq!(update_liquidity_pool.price, liquidity_pool.price);
+
+		let alice_asset_x_balance = Assets::balance(1, 1);
+		let alice_asset_y_balance = Assets::balance(2, 1);
+		assert_ok!(Humidefi::redeem_liquidity(alice, asset_pairs, liquidity_pool.lp_token, 1));
+
+		let asset_x_redeemed = Assets::balance(1, 1) - alice_asset_x_balance;
+		let asset_y_redeemed = Assets::balance(2, 1) - alice_asset_y_balance;
+		assert!(asset_x_redeemed <= 100_000_000_000_000_000_000 && asset_x_redeemed + 1_000 >= 100_000_000_000_000_000_000);
+		assert!(asset_y_redeemed <= 200_000_000_000_000_000_000 && asset_y_redeemed + 1_000 >= 200_000_000_000_000_000_000);
+	});
+}
+
+#[test]
+fn swap_exact_in_for_out_path_routes_through_pools() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			AssetPairs { asset_x: NativeOrAssetId::Asset(1), asset_y: NativeOrAssetId::Asset(2) },
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			AssetPairs { asset_x: NativeOrAssetId::Asset(2), asset_y: NativeOrAssetId::Asset(3) },
+			2_000_000_000_000_000_000_000,
+			3_000_000_000_000_000_000_000,
+			0
+		));
+
+		let amounts = Humidefi::compute_swap_exact_in_path(
+			vec![NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(2), NativeOrAssetId::Asset(3)],
+			1_000_000_000_000_000_000
+		).unwrap();
+
+		let alice_asset_2_balance = Assets::balance(2, 1);
+		let alice_asset_3_balance = Assets::balance(3, 1);
+
+		assert_ok!(Humidefi::swap_exact_in_for_out_path(
+			alice.clone(),
+			vec![NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(2), NativeOrAssetId::Asset(3)].try_into().unwrap(),
+			1_000_000_000_000_000_000,
+			amounts[2],
+			None
+		));
+
+		assert_eq!(Assets::balance(2, 1), alice_asset_2_balance);
+		assert_eq!(Assets::balance(3, 1) - alice_asset_3_balance, amounts[2]);
+
+		assert_noop!(
+			Humidefi::swap_exact_in_for_out_path(
+				alice.clone(),
+				vec![NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(2), NativeOrAssetId::Asset(1)].try_into().unwrap(),
+				1_000_000_000_000_000_000,
+				0,
+				None
+			),
+			Error::<Test>::InvalidSwapPath
+		);
+	});
+}
+
+#[test]
+fn new_liquidity_normalizes_asset_pair() {
+	new_test_ext().execute_with(|| {
+		let bob = RuntimeOrigin::signed(2);
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			AssetPairs { asset_x: NativeOrAssetId::Asset(2), asset_y: NativeOrAssetId::Asset(1) },
+			2_000_000_000_000_000_000_000,
+			1_000_000_000_000_000_000_000,
+			0
+		));
+
+		assert!(Humidefi::liquidity_pool_storage(AssetPairs::<Test> { asset_x: NativeOrAssetId::Asset(2), asset_y: NativeOrAssetId::Asset(1) }).is_none());
+
+		let liquidity_pool = Humidefi::liquidity_pool_storage(AssetPairs::<Test> { asset_x: NativeOrAssetId::Asset(1), asset_y: NativeOrAssetId::Asset(2) }).unwrap();
+		assert_eq!(liquidity_pool.asset_x_balance, FixedU128::from_inner(1_000_000_000_000_000_000_000));
+		assert_eq!(liquidity_pool.asset_y_balance, FixedU128::from_inner(2_000_000_000_000_000_000_000));
+		assert_eq!(AssetPairs::<Test> { asset_x: NativeOrAssetId::Asset(2), asset_y: NativeOrAssetId::Asset(1) }.pool_id(), (NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(2)));
+
+		assert_noop!(
+			Humidefi::new_liquidity(
+				bob.clone(),
+				AssetPairs { asset_x: NativeOrAssetId::Asset(1), asset_y: NativeOrAssetId::Asset(1) },
+				1_000_000_000_000_000_000_000,
+				1_000_000_000_000_000_000_000,
+				0
+			),
+			Error::<Test>::IdenticalAssets
+		);
+	});
+}
+
+#[test]
+fn native_asset_pool_works() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Native,
+			asset_y: NativeOrAssetId::Asset(1)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			AssetPairs { asset_x: NativeOrAssetId::Asset(1), asset_y: NativeOrAssetId::Native },
+			2_000_000_000_000_000_000_000,
+			1_000_000_000_000_000_000_000,
+			0
+		));
+
+		let pool_account = Humidefi::get_pool_account(asset_pairs.clone());
+		assert_eq!(Balances::free_balance(pool_account), 1_000_000_000_000_000_000_000);
+		assert_eq!(Assets::balance(1, pool_account), 2_000_000_000_000_000_000_000);
+
+		let alice_native_balance = Balances::free_balance(1);
+		let alice_asset_1_balance = Assets::balance(1, 1);
+
+		assert_ok!(Humidefi::swap_exact_in_for_out(
+			alice.clone(),
+			NativeOrAssetId::Native,
+			1_000_000_000_000_000_000,
+			NativeOrAssetId::Asset(1),
+			0,
+			None
+		));
+
+		let liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs).unwrap();
+		let amount_out = Assets::balance(1, 1) - alice_asset_1_balance;
+
+		assert_eq!(alice_native_balance - Balances::free_balance(1), 1_000_000_000_000_000_000);
+		assert_eq!(liquidity_pool.asset_y_balance, FixedU128::from_inner(2_000_000_000_000_000_000_000 - amount_out));
+	});
+}
+
+#[test]
+fn redeem_liquidity_keeps_native_reserve_above_existential_deposit() {
+	new_test_ext().execute_with(|| {
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Native,
+			asset_y: NativeOrAssetId::Asset(1)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(bob.clone(), asset_pairs.clone(), 1_000_000, 1_000_000, 0));
+
+		let liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap();
+		let pool_account = Humidefi::get_pool_account(asset_pairs.clone());
+
+		// Redeeming everything leaves the locked minimum liquidity's share, which would be dusted.
+		ExistentialDeposit::set(MINIMUM_LIQUIDITY + 1);
+		assert_noop!(
+			Humidefi::redeem_liquidity(bob.clone(), asset_pairs.clone(), liquidity_pool.lp_token, 1),
+			Error::<Test>::NativeReserveBelowExistentialDeposit
+		);
+
+		ExistentialDeposit::set(MINIMUM_LIQUIDITY);
+		assert_ok!(Humidefi::redeem_liquidity(bob.clone(), asset_pairs, liquidity_pool.lp_token, 1));
+		assert_eq!(Balances::free_balance(pool_account), MINIMUM_LIQUIDITY);
+	});
+}
+
+#[test]
+fn quotes_match_executed_swaps() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			AssetPairs { asset_x: NativeOrAssetId::Asset(1), asset_y: NativeOrAssetId::Asset(2) },
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		assert_eq!(
+			Humidefi::quote_add_liquidity(
+				NativeOrAssetId::Asset(2),
+				NativeOrAssetId::Asset(1),
+				2_000_000_000_000_000_000,
+				1_000_000_000_000_000_000
+			),
+			Humidefi::compute_lp_token(
+				AssetPairs::<Test> { asset_x: NativeOrAssetId::Asset(1), asset_y: NativeOrAssetId::Asset(2) },
+				1_000_000_000_000_000_000,
+				2_000_000_000_000_000_000
+			).ok()
+		);
+
+		let quote = Humidefi::quote_exact_in(
+			vec![NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(2)],
+			1_000_000_000_000_000_000
+		).unwrap();
+
+		let alice_asset_2_balance = Assets::balance(2, 1);
+
+		assert_ok!(Humidefi::swap_exact_in_for_out(
+			alice.clone(),
+			NativeOrAssetId::Asset(1),
+			1_000_000_000_000_000_000,
+			NativeOrAssetId::Asset(2),
+			quote,
+			None
+		));
+
+		assert_eq!(Assets::balance(2, 1) - alice_asset_2_balance, quote);
+		assert_eq!(Humidefi::quote_exact_in(vec![NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(3)], 1), None);
+	});
+}
+
+#[test]
+fn list_pools_and_account_positions_work() {
+	new_test_ext().execute_with(|| {
+		let bob = RuntimeOrigin::signed(2);
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			AssetPairs { asset_x: NativeOrAssetId::Asset(1), asset_y: NativeOrAssetId::Asset(2) },
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			AssetPairs { asset_x: NativeOrAssetId::Asset(2), asset_y: NativeOrAssetId::Asset(3) },
+			2_000_000_000_000_000_000_000,
+			3_000_000_000_000_000_000_000,
+			0
+		));
+
+		let pools = Humidefi::list_pools(0, 10);
+		assert_eq!(pools.len(), 2);
+		assert_eq!(Humidefi::list_pools(1, 10).len(), 1);
+		assert_eq!(Humidefi::list_pools(0, 1).len(), 1);
+
+		let pool = pools.iter()
+			.find(|pool| pool.asset_x == NativeOrAssetId::Asset(1))
+			.unwrap();
+		assert_eq!(pool.asset_y, NativeOrAssetId::Asset(2));
+		assert_eq!(pool.asset_x_balance, 1_000_000_000_000_000_000_000);
+		assert_eq!(pool.asset_y_balance, 2_000_000_000_000_000_000_000);
+		assert_eq!(Assets::total_supply(pool.lp_token), pool.lp_token_supply);
+
+		let positions = Humidefi::account_positions(2);
+		assert_eq!(positions.len(), 2);
+		assert!(positions.iter().all(|position| Assets::balance(position.lp_token, 2) >= position.lp_token_balance));
+		assert!(Humidefi::account_positions(1).is_empty());
+	});
+}
+
+#[test]
+fn events_carry_amounts_and_pool_ids() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		let liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap();
+
+		System::assert_has_event(Event::PoolCreated {
+			pair: asset_pairs.clone(),
+			lp_token: liquidity_pool.lp_token,
+		}.into());
+
+		System::assert_last_event(Event::LiquidityAdded {
+			who: 2,
+			pair: asset_pairs.clone(),
+			amount_x: 1_000_000_000_000_000_000_000,
+			amount_y: 2_000_000_000_000_000_000_000,
+			lp_minted: liquidity_pool.lp_token_balance.into_inner() - MINIMUM_LIQUIDITY,
+			position_id: 1,
+		}.into());
+
+		let alice_asset_2_balance = Assets::balance(2, 1);
+
+		assert_ok!(Humidefi::swap_exact_in_for_out(
+			alice.clone(),
+			NativeOrAssetId::Asset(1),
+			1_000_000_000_000_000_000,
+			NativeOrAssetId::Asset(2),
+			0,
+			None
+		));
+
+		System::assert_last_event(Event::Swapped {
+			who: 1,
+			asset_in: NativeOrAssetId::Asset(1),
+			amount_in: 1_000_000_000_000_000_000,
+			asset_out: NativeOrAssetId::Asset(2),
+			amount_out: Assets::balance(2, 1) - alice_asset_2_balance,
+			fee: 3_000_000_000_000_000,
+		}.into());
+	});
+}
+
+#[test]
+fn try_state_holds_across_liquidity_and_swaps() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Native,
+			asset_y: NativeOrAssetId::Asset(1)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			1_000_000_000_000_000_000_000,
+			0
+		));
+
+		assert_ok!(Humidefi::new_liquidity(
+			alice.clone(),
+			asset_pairs.clone(),
+			500_000_000_000_000_000_000,
+			500_000_000_000_000_000_000,
+			0
+		));
+
+		assert_ok!(Humidefi::swap_exact_in_for_out(
+			alice.clone(),
+			NativeOrAssetId::Native,
+			1_000_000_000_000_000_000,
+			NativeOrAssetId::Asset(1),
+			0,
+			None
+		));
+
+		let lp_token = Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap().lp_token;
+		assert_ok!(Humidefi::redeem_liquidity_partial(
+			bob.clone(),
+			asset_pairs.clone(),
+			lp_token,
+			1,
+			RedeemLiquidityAmount::Portion(Perbill::from_percent(40))
+		));
+
+		assert_ok!(Humidefi::do_try_state());
+
+		// Positions cannot account for more than the LP tokens issued beside the locked ones.
+		let account_liquidity_pools = Humidefi::account_liquidity_pool_storage((1u64, asset_pairs.clone())).unwrap();
+		let mut inflated_account_liquidity_pools = account_liquidity_pools.clone();
+		inflated_account_liquidity_pools[0].lp_token_balance = Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap().lp_token_balance;
+		crate::AccountLiquidityPoolStorage::<Test>::insert((1u64, asset_pairs.clone()), inflated_account_liquidity_pools);
+		assert!(Humidefi::do_try_state().is_err());
+
+		crate::AccountLiquidityPoolStorage::<Test>::insert((1u64, asset_pairs.clone()), account_liquidity_pools);
+		assert_ok!(Humidefi::do_try_state());
+
+		assert_ok!(<Assets as fungibles::Mutate<u64>>::mint_into(lp_token, &1, 1));
+		assert!(Humidefi::do_try_state().is_err());
+	});
+}
+
+#[test]
+fn redeem_lp_token_follows_lp_token_holder() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			1_000_000_000_000_000_000_000,
+			0
+		));
+
+		let liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap();
+		let lp_token = liquidity_pool.lp_token;
+		let lp_token_balance = Assets::balance(lp_token, 2);
+
+		// Bob hands his whole share to Alice, who never deposited.
+		assert_ok!(Humidefi::transfer_asset(bob.clone(), lp_token, lp_token_balance, 1));
+
+		assert_noop!(
+			Humidefi::redeem_liquidity(bob.clone(), asset_pairs.clone(), lp_token, 1),
+			Error::<Test>::CheckAssetLiquidityPoolTokenBalanceError
+		);
+
+		let alice_asset_1_balance = Assets::balance(1, 1);
+		assert_ok!(Humidefi::redeem_lp_token(alice.clone(), asset_pairs.clone(), lp_token_balance));
+
+		assert_eq!(Assets::balance(lp_token, 1), 0);
+		assert_eq!(Assets::balance(1, 1) - alice_asset_1_balance, 1_000_000_000_000_000_000_000 - MINIMUM_LIQUIDITY);
+		assert_eq!(Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap().lp_token_balance, FixedU128::from_inner(MINIMUM_LIQUIDITY));
+
+		// Bob's position is stale bookkeeping now, but redeeming it still needs LP tokens.
+		assert!(Humidefi::account_liquidity_pool_storage((2u64, asset_pairs)).is_some());
+		assert_ok!(Humidefi::do_try_state());
+	});
+}
+
+#[test]
+fn asset_listing_gates_pool_creation() {
+	new_test_ext().execute_with(|| {
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_noop!(
+			Humidefi::set_asset_listing(bob.clone(), NativeOrAssetId::Asset(2), Some(AssetListingStatus::Denied)),
+			frame_support::sp_runtime::DispatchError::BadOrigin
+		);
+
+		assert_ok!(Humidefi::set_asset_listing(RuntimeOrigin::root(), NativeOrAssetId::Asset(2), Some(AssetListingStatus::Denied)));
+		assert_noop!(
+			Humidefi::new_liquidity(bob.clone(), asset_pairs.clone(), 1_000_000_000_000_000_000_000, 1_000_000_000_000_000_000_000, 0),
+			Error::<Test>::AssetNotListed
+		);
+
+		// Under an allow-list, clearing the deny entry is not enough on its own.
+		assert_ok!(Humidefi::set_listing_mode(RuntimeOrigin::root(), ListingMode::AllowList));
+		assert_ok!(Humidefi::set_asset_listing(RuntimeOrigin::root(), NativeOrAssetId::Asset(2), None));
+		assert_ok!(Humidefi::set_asset_listing(RuntimeOrigin::root(), NativeOrAssetId::Asset(1), Some(AssetListingStatus::Allowed)));
+		assert_noop!(
+			Humidefi::new_liquidity(bob.clone(), asset_pairs.clone(), 1_000_000_000_000_000_000_000, 1_000_000_000_000_000_000_000, 0),
+			Error::<Test>::AssetNotListed
+		);
+
+		assert_ok!(Humidefi::set_asset_listing(RuntimeOrigin::root(), NativeOrAssetId::Asset(2), Some(AssetListingStatus::Allowed)));
+		assert_ok!(Humidefi::new_liquidity(bob.clone(), asset_pairs.clone(), 1_000_000_000_000_000_000_000, 1_000_000_000_000_000_000_000, 0));
+
+		assert!(Humidefi::liquidity_pool_storage(asset_pairs.clone()).is_some());
+
+		// Listing only applies to new pools, existing ones stay open for liquidity.
+		assert_ok!(Humidefi::set_asset_listing(RuntimeOrigin::root(), NativeOrAssetId::Asset(2), Some(AssetListingStatus::Denied)));
+		assert_ok!(Humidefi::new_liquidity(bob, asset_pairs, 1_000_000_000_000_000_000_000, 1_000_000_000_000_000_000_000, 0));
+	});
+}
+
+#[test]
+fn paused_pools_reject_swaps_and_optionally_redemptions() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			1_000_000_000_000_000_000_000,
+			0
+		));
+
+		assert_noop!(
+			Humidefi::pause_pool(alice.clone(), asset_pairs.clone(), true),
+			frame_support::sp_runtime::DispatchError::BadOrigin
+		);
+
+		assert_ok!(Humidefi::pause_pool(RuntimeOrigin::root(), asset_pairs.clone(), true));
+		assert_eq!(Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap().status, PoolStatus::SwapsPaused);
+
+		assert_noop!(
+			Humidefi::swap_exact_in_for_out(
+				alice.clone(),
+				NativeOrAssetId::Asset(1),
+				1_000_000_000_000_000_000,
+				NativeOrAssetId::Asset(2),
+				0,
+				None
+			),
+			Error::<Test>::PoolPaused
+		);
+
+		assert_noop!(
+			Humidefi::new_liquidity(bob.clone(), asset_pairs.clone(), 1_000_000_000_000_000_000, 1_000_000_000_000_000_000, 0),
+			Error::<Test>::PoolPaused
+		);
+
+		assert_ok!(Humidefi::redeem_liquidity_partial(
+			bob.clone(),
+			asset_pairs.clone(),
+			Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap().lp_token,
+			1,
+			RedeemLiquidityAmount::Portion(Perbill::from_percent(10))
+		));
+
+		// A global pause without redemptions overrides the pool's own, softer status.
+		assert_ok!(Humidefi::pause_all(RuntimeOrigin::root(), false));
+		assert_noop!(
+			Humidefi::redeem_lp_token(bob.clone(), asset_pairs.clone(), 1_000_000_000_000_000_000),
+			Error::<Test>::PoolPaused
+		);
+
+		assert_ok!(Humidefi::unpause(RuntimeOrigin::root(), None));
+		assert_noop!(
+			Humidefi::swap_exact_in_for_out(
+				alice.clone(),
+				NativeOrAssetId::Asset(1),
+				1_000_000_000_000_000_000,
+				NativeOrAssetId::Asset(2),
+				0,
+				None
+			),
+			Error::<Test>::PoolPaused
+		);
+
+		assert_ok!(Humidefi::unpause(RuntimeOrigin::root(), Some(asset_pairs.clone())));
+		assert_ok!(Humidefi::swap_exact_in_for_out(
+			alice,
+			NativeOrAssetId::Asset(1),
+			1_000_000_000_000_000_000,
+			NativeOrAssetId::Asset(2),
+			0,
+			None
+		));
+	});
+}
+
+#[test]
+fn circuit_breaker_rejects_swaps_that_move_the_price_too_far() {
+	new_test_ext().execute_with(|| {
+		MaxPriceMovePerBlock::set(Perbill::from_percent(10));
+
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			1_000_000_000_000_000_000_000,
+			0
+		));
+
+		// Small swaps stay within the limit.
+		assert_ok!(Humidefi::swap_exact_in_for_out(
+			alice.clone(),
+			NativeOrAssetId::Asset(1),
+			1_000_000_000_000_000_000,
+			NativeOrAssetId::Asset(2),
+			0,
+			None
+		));
+		assert_eq!(Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap().status, PoolStatus::Active);
+
+		// The swap that would trip the breaker is rejected and leaves the pool untouched.
+		let liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap();
+		assert_noop!(
+			Humidefi::swap_exact_in_for_out(
+				alice.clone(),
+				NativeOrAssetId::Asset(1),
+				100_000_000_000_000_000_000,
+				NativeOrAssetId::Asset(2),
+				0,
+				None
+			),
+			Error::<Test>::CircuitBreakerTripped
+		);
+		assert_eq!(Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap(), liquidity_pool);
+
+		let alice_asset_x_balance = Assets::balance(1, 1);
+		assert_noop!(
+			Humidefi::swap_in_for_exact_out(
+				alice.clone(),
+				NativeOrAssetId::Asset(2),
+				90_000_000_000_000_000_000,
+				NativeOrAssetId::Asset(1),
+				200_000_000_000_000_000_000,
+				None
+			),
+			Error::<Test>::CircuitBreakerTripped
+		);
+		assert_eq!(Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap(), liquidity_pool);
+		assert_eq!(Assets::balance(1, 1), alice_asset_x_balance);
+
+		// Swaps within the limit keep going.
+		assert_ok!(Humidefi::swap_exact_in_for_out(
+			alice.clone(),
+			NativeOrAssetId::Asset(2),
+			1_000_000_000_000_000_000,
+			NativeOrAssetId::Asset(1),
+			0,
+			None
+		));
+
+		// The opening price resets with each block.
+		assert_eq!(crate::BlockOpeningPrice::<Test>::iter().count(), 1);
+		System::set_block_number(2);
+		Humidefi::on_initialize(2);
+		assert_eq!(crate::BlockOpeningPrice::<Test>::iter().count(), 0);
+		assert_ok!(Humidefi::swap_exact_in_for_out(
+			alice,
+			NativeOrAssetId::Asset(1),
+			30_000_000_000_000_000_000,
+			NativeOrAssetId::Asset(2),
+			0,
+			None
+		));
+		assert_eq!(Humidefi::liquidity_pool_storage(asset_pairs).unwrap().status, PoolStatus::Active);
+	});
+}
+
+#[test]
+fn twap_lags_spot_price_within_window() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000,
+			1_000_000_000_000_000_000_000,
+			0
+		));
+
+		let one = FixedU128::from_rational(1, 1);
+
+		// Nothing has been observed for long enough yet.
+		assert_eq!(Humidefi::twap(NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(2), 10), None);
+
+		System::set_block_number(11);
+		assert_eq!(Humidefi::twap(NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(2), 10), Some(one));
+
+		assert_ok!(Humidefi::swap_exact_in_for_out(
+			alice,
+			NativeOrAssetId::Asset(1),
+			100_000_000_000_000_000_000,
+			NativeOrAssetId::Asset(2),
+			0,
+			None
+		));
+
+		// The swap only moves the spot price until it has been in effect for some blocks.
+		let liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap();
+		assert!(liquidity_pool.price < one);
+		assert_eq!(Humidefi::twap(NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(2), 10), Some(one));
+
+		System::set_block_number(21);
+		assert_eq!(Humidefi::twap(NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(2), 10), Some(liquidity_pool.price));
+		assert_eq!(
+			Humidefi::twap(NativeOrAssetId::Asset(2), NativeOrAssetId::Asset(1), 10),
+			Some(FixedU128::from_rational(
+				liquidity_pool.asset_x_balance.into_inner(),
+				liquidity_pool.asset_y_balance.into_inner()
+			))
+		);
+
+		// Averaging across the swap weighs both prices by how long they held.
+		let twap = Humidefi::twap(NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(2), 20).unwrap();
+		assert!(liquidity_pool.price < twap && twap < one);
+
+		assert_eq!(Humidefi::twap(NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(2), 30), None);
+	});
+}
+
+#[test]
+fn inspect_reports_pool_prices_reserves_and_quotes() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob,
+			asset_pairs,
+			1_000_000_000_000_000_000_000,
+			2_000_000_000_000_000_000_000,
+			0
+		));
+
+		assert_eq!(
+			<Humidefi as HumidefiInspect>::reserves(NativeOrAssetId::Asset(2), NativeOrAssetId::Asset(1)),
+			Some((2_000_000_000_000_000_000_000, 1_000_000_000_000_000_000_000))
+		);
+		assert_eq!(
+			<Humidefi as PriceOracle>::spot_price(NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(2)),
+			Some(FixedU128::from_rational(2, 1))
+		);
+		assert_eq!(
+			<Humidefi as PriceOracle>::spot_price(NativeOrAssetId::Asset(2), NativeOrAssetId::Asset(1)),
+			Some(FixedU128::from_rational(1, 2))
+		);
+		assert_eq!(<Humidefi as PriceOracle>::spot_price(NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(3)), None);
+
+		let quote = <Humidefi as HumidefiInspect>::quote(
+			NativeOrAssetId::Asset(1),
+			NativeOrAssetId::Asset(2),
+			1_000_000_000_000_000_000
+		).unwrap();
+
+		let alice_asset_2_balance = Assets::balance(2, 1);
+		assert_ok!(Humidefi::swap_exact_in_for_out(
+			alice,
+			NativeOrAssetId::Asset(1),
+			1_000_000_000_000_000_000,
+			NativeOrAssetId::Asset(2),
+			quote,
+			None
+		));
+		assert_eq!(Assets::balance(2, 1) - alice_asset_2_balance, quote);
+	});
+}
+
+#[test]
+fn stable_swap_pools_quote_with_less_slippage() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+		let balance: u128 = 1_000_000_000_000_000_000_000;
+		let amount_in: u128 = 100_000_000_000_000_000_000;
+
+		let stable_pair = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+		let constant_product_pair = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(3)
+		};
+
+		assert_noop!(
+			Humidefi::create_pool(
+				bob.clone(),
+				stable_pair.clone(),
+				PoolKind::StableSwap { amplification: 0 },
+				balance,
+				balance
+			),
+			Error::<Test>::InvalidAmplification
+		);
+
+		assert_ok!(Humidefi::create_pool(
+			bob.clone(),
+			stable_pair.clone(),
+			PoolKind::StableSwap { amplification: 100 },
+			balance,
+			balance
+		));
+		System::assert_has_event(RuntimeEvent::Humidefi(Event::PoolKindUpdated {
+			pair: stable_pair.clone(),
+			kind: PoolKind::StableSwap { amplification: 100 },
+		}));
+
+		assert_noop!(
+			Humidefi::create_pool(
+				bob.clone(),
+				stable_pair.clone(),
+				PoolKind::ConstantProduct,
+				balance,
+				balance
+			),
+			Error::<Test>::LiquidityPoolAlreadyExists
+		);
+
+		assert_ok!(Humidefi::create_pool(
+			bob,
+			constant_product_pair.clone(),
+			PoolKind::ConstantProduct,
+			balance,
+			balance
+		));
+
+		// A balanced stable pool prices its assets one to one and gives up far less to a large trade.
+		assert_eq!(
+			<Humidefi as PriceOracle>::spot_price(NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(2)),
+			Some(FixedU128::from_rational(1, 1))
+		);
+
+		let stable_quote = <Humidefi as HumidefiInspect>::quote(
+			NativeOrAssetId::Asset(1),
+			NativeOrAssetId::Asset(2),
+			amount_in
+		).unwrap();
+		let constant_product_quote = <Humidefi as HumidefiInspect>::quote(
+			NativeOrAssetId::Asset(1),
+			NativeOrAssetId::Asset(3),
+			amount_in
+		).unwrap();
+
+		assert!(stable_quote > constant_product_quote);
+		assert!(stable_quote < amount_in);
+
+		let alice_asset_2_balance = Assets::balance(2, 1);
+		assert_ok!(Humidefi::swap_exact_in_for_out(
+			alice,
+			NativeOrAssetId::Asset(1),
+			amount_in,
+			NativeOrAssetId::Asset(2),
+			stable_quote,
+			None
+		));
+		assert_eq!(Assets::balance(2, 1), alice_asset_2_balance + stable_quote);
+
+		assert_noop!(
+			Humidefi::set_amplification(RuntimeOrigin::signed(1), stable_pair.clone(), 10),
+			frame_support::sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Humidefi::set_amplification(RuntimeOrigin::root(), constant_product_pair, 10),
+			Error::<Test>::NotStableSwapPool
+		);
+		assert_noop!(
+			Humidefi::set_amplification(RuntimeOrigin::root(), stable_pair.clone(), crate::MAX_AMPLIFICATION + 1),
+			Error::<Test>::InvalidAmplification
+		);
+
+		let price = Humidefi::get_liquidity_pool(stable_pair.clone()).unwrap().price;
+		assert_ok!(Humidefi::set_amplification(RuntimeOrigin::root(), stable_pair.clone(), 10));
+		assert_eq!(crate::PoolKinds::<Test>::get(stable_pair.clone()), PoolKind::StableSwap { amplification: 10 });
+
+		// A flatter curve moves the price of the now unbalanced pool further below one to one.
+		assert!(Humidefi::get_liquidity_pool(stable_pair).unwrap().price < price);
+	});
+}
+
+#[test]
+fn weighted_pools_price_by_weight_and_join_proportionally() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+		let amount: u128 = 1_000_000_000_000_000_000;
+
+		// Listed in reverse order, so the 20% weight belongs to asset 2.
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(2),
+			asset_y: NativeOrAssetId::Asset(1)
+		};
+		let canonical_pair = asset_pairs.normalize();
+
+		assert_noop!(
+			Humidefi::create_pool(
+				bob.clone(),
+				asset_pairs.clone(),
+				PoolKind::Weighted { weight_x: Perbill::zero() },
+				1_000_000_000_000_000_000_000,
+				4_000_000_000_000_000_000_000
+			),
+			Error::<Test>::InvalidWeights
+		);
+
+		assert_ok!(Humidefi::create_pool(
+			bob,
+			asset_pairs,
+			PoolKind::Weighted { weight_x: Perbill::from_percent(20) },
+			1_000_000_000_000_000_000_000,
+			4_000_000_000_000_000_000_000
+		));
+		assert_eq!(
+			crate::PoolKinds::<Test>::get(canonical_pair.clone()),
+			PoolKind::Weighted { weight_x: Perbill::from_percent(80) }
+		);
+
+		// Four times the reserves of asset 1 at four times its weight is an even price.
+		assert_eq!(
+			<Humidefi as PriceOracle>::spot_price(NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(2)),
+			Some(FixedU128::from_rational(1, 1))
+		);
+
+		let quote = <Humidefi as HumidefiInspect>::quote(
+			NativeOrAssetId::Asset(1),
+			NativeOrAssetId::Asset(2),
+			amount
+		).unwrap();
+		assert!(quote < amount * 997 / 1_000);
+		assert!(quote > amount * 99 / 100);
+
+		let alice_asset_2_balance = Assets::balance(2, 1);
+		assert_ok!(Humidefi::swap_exact_in_for_out(
+			alice.clone(),
+			NativeOrAssetId::Asset(1),
+			amount,
+			NativeOrAssetId::Asset(2),
+			quote,
+			None
+		));
+		assert_eq!(Assets::balance(2, 1), alice_asset_2_balance + quote);
+
+		let alice_asset_1_balance = Assets::balance(1, 1);
+		assert_ok!(Humidefi::swap_in_for_exact_out(
+			alice.clone(),
+			NativeOrAssetId::Asset(1),
+			amount,
+			NativeOrAssetId::Asset(2),
+			amount * 2,
+			None
+		));
+		assert_eq!(Assets::balance(1, 1), alice_asset_1_balance + amount);
+
+		// Joins mint against the share of the reserves added, whatever the weights, and take
+		// only the proportional amounts of an unbalanced deposit.
+		let liquidity_pool = Humidefi::get_liquidity_pool(canonical_pair.clone()).unwrap();
+		let lp_token_supply = liquidity_pool.lp_token_balance.into_inner();
+		let asset_x_deposit = liquidity_pool.asset_x_balance.into_inner() / 10;
+		let asset_y_deposit = multiply_by_rational_with_rounding(
+			asset_x_deposit,
+			liquidity_pool.asset_y_balance.into_inner(),
+			liquidity_pool.asset_x_balance.into_inner(),
+			Rounding::Up,
+		).unwrap();
+
+		let alice_asset_x_balance = Assets::balance(1, 1);
+		let alice_asset_y_balance = Assets::balance(2, 1);
+
+		assert_ok!(Humidefi::new_liquidity(
+			alice,
+			canonical_pair.clone(),
+			asset_x_deposit,
+			asset_y_deposit * 2,
+			0
+		));
+
+		assert_eq!(alice_asset_x_balance - Assets::balance(1, 1), asset_x_deposit);
+		assert_eq!(alice_asset_y_balance - Assets::balance(2, 1), asset_y_deposit);
+
+		let alice_lp_token_balance = Assets::balance(liquidity_pool.lp_token, 1);
+		assert!(alice_lp_token_balance <= lp_token_supply / 10);
+		assert!(alice_lp_token_balance + 2 >= lp_token_supply / 10);
+	});
+}
+
+#[test]
+fn concentrated_pools_pay_swap_fees_to_ranges_in_range() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+		let charlie = RuntimeOrigin::signed(3);
+		let reserve: u128 = 1_000_000_000_000_000_000_000;
+		let amount: u128 = 1_000_000_000_000_000_000;
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_noop!(
+			Humidefi::create_pool(
+				bob.clone(),
+				asset_pairs.clone(),
+				PoolKind::Concentrated { tick_spacing: 0 },
+				reserve,
+				reserve
+			),
+			Error::<Test>::InvalidTickSpacing
+		);
+
+		assert_ok!(Humidefi::create_pool(
+			bob.clone(),
+			asset_pairs.clone(),
+			PoolKind::Concentrated { tick_spacing: 10 },
+			reserve,
+			reserve
+		));
+		assert_eq!(crate::ConcentratedPools::<Test>::get(asset_pairs.clone()).unwrap().tick, 0);
+
+		assert_noop!(
+			Humidefi::add_range_liquidity(alice.clone(), asset_pairs.clone(), -105, 100, reserve, reserve),
+			Error::<Test>::InvalidTickRange
+		);
+		assert_noop!(
+			Humidefi::add_range_liquidity(alice.clone(), asset_pairs.clone(), 100, -100, reserve, reserve),
+			Error::<Test>::InvalidTickRange
+		);
+
+		// Alice concentrates around the current price, Charlie only provides above it.
+		assert_ok!(Humidefi::add_range_liquidity(alice.clone(), asset_pairs.clone(), -100, 100, reserve, reserve));
+		assert_ok!(Humidefi::add_range_liquidity(charlie.clone(), asset_pairs.clone(), 1_000, 2_000, reserve, reserve));
+
+		let charlie_position = Humidefi::account_liquidity_pool_storage((3, asset_pairs.clone())).unwrap()[0].clone();
+		assert!(!charlie_position.asset_x_balance.is_zero());
+		assert!(charlie_position.asset_x_balance <= FixedU128::from_inner(reserve));
+		assert!(charlie_position.asset_y_balance.is_zero());
+
+		let lp_token = Humidefi::get_liquidity_pool(asset_pairs.clone()).unwrap().lp_token;
+		assert_noop!(
+			Humidefi::redeem_liquidity(alice.clone(), asset_pairs.clone(), lp_token, 1),
+			Error::<Test>::RangePosition
+		);
+
+		// The range deepens the pool well beyond what its reserves alone would quote.
+		let quote = <Humidefi as HumidefiInspect>::quote(
+			NativeOrAssetId::Asset(1),
+			NativeOrAssetId::Asset(2),
+			amount
+		).unwrap();
+		let constant_product_quote = Humidefi::compute_amount_out(reserve, reserve, amount * 997 / 1_000).unwrap();
+		assert!(quote > constant_product_quote);
+
+		assert_ok!(Humidefi::swap_exact_in_for_out(
+			bob,
+			NativeOrAssetId::Asset(1),
+			amount,
+			NativeOrAssetId::Asset(2),
+			quote,
+			None
+		));
+
+		let swap_fee = amount * 3 / 1_000;
+		let range_fees = |who: u64| System::events().iter().find_map(|record| match &record.event {
+			RuntimeEvent::Humidefi(Event::RangeLiquidityRemoved { who: removed_by, fee_x, fee_y, .. })
+				if *removed_by == who => Some((*fee_x, *fee_y)),
+			_ => None,
+		});
+
+		assert_ok!(Humidefi::remove_range_liquidity(alice, asset_pairs.clone(), 1));
+		let (alice_fee_x, alice_fee_y) = range_fees(1).unwrap();
+		assert!(alice_fee_x > swap_fee * 9 / 10);
+		assert!(alice_fee_y.is_zero());
+
+		// Whatever the range did not earn stays with the LP token holders.
+		let liquidity_pool = Humidefi::get_liquidity_pool(asset_pairs.clone()).unwrap();
+		assert!(liquidity_pool.asset_x_fee.into_inner() + alice_fee_x <= swap_fee);
+
+		assert_ok!(Humidefi::remove_range_liquidity(charlie, asset_pairs.clone(), 1));
+		assert_eq!(range_fees(3), Some((0, 0)));
+		assert!(crate::InitializedTicks::<Test>::get(asset_pairs).is_empty());
+
+		assert_ok!(Humidefi::do_try_state());
+	});
+}
+
+#[test]
+fn concentrated_pools_take_deposits_and_pay_swaps_at_their_sqrt_price() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+		let reserve: u128 = 1_000_000_000_000_000_000_000;
+		let amount: u128 = 100_000_000_000_000_000_000;
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::create_pool(
+			bob.clone(),
+			asset_pairs.clone(),
+			PoolKind::Concentrated { tick_spacing: 10 },
+			reserve,
+			reserve
+		));
+		assert_ok!(Humidefi::add_range_liquidity(alice.clone(), asset_pairs.clone(), -100, 100, reserve, reserve));
+
+		// The range moves the price further than the reserves' own ratio.
+		assert_ok!(Humidefi::swap_exact_in_for_out(bob.clone(), NativeOrAssetId::Asset(1), amount, NativeOrAssetId::Asset(2), 0, None));
+
+		let liquidity_pool = Humidefi::get_liquidity_pool(asset_pairs.clone()).unwrap();
+		let sqrt_price = crate::ConcentratedPools::<Test>::get(asset_pairs.clone()).unwrap().sqrt_price;
+		assert!(liquidity_pool.asset_y_balance.into_inner() > 0);
+		assert!(
+			crate::concentrated_math::full_range_liquidity(
+				sqrt_price,
+				liquidity_pool.asset_x_balance.into_inner(),
+				liquidity_pool.asset_y_balance.into_inner()
+			).unwrap() >= crate::concentrated_math::full_range_liquidity(FixedU128::from_u32(1), reserve, reserve).unwrap()
+		);
+
+		let (asset_x_balance, asset_y_balance) = crate::concentrated_math::amounts_at_price(sqrt_price, reserve, reserve).unwrap();
+		let alice_asset_x_balance = Assets::balance(1, 1);
+		let alice_asset_y_balance = Assets::balance(2, 1);
+
+		assert_ok!(Humidefi::new_liquidity(alice, asset_pairs, reserve, reserve, 0));
+
+		assert_eq!(alice_asset_x_balance - Assets::balance(1, 1), asset_x_balance);
+		assert_eq!(alice_asset_y_balance - Assets::balance(2, 1), asset_y_balance);
+
+		assert_ok!(Humidefi::do_try_state());
+	});
+}
+
+#[test]
+fn limit_orders_fill_when_the_pool_reaches_their_price() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+		let charlie = RuntimeOrigin::signed(3);
+		let amount_in: u128 = 1_000_000_000_000_000_000_000;
+		let limit_price = FixedU128::from_rational(11, 10);
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			AssetPairs::<Test> { asset_x: NativeOrAssetId::Asset(1), asset_y: NativeOrAssetId::Asset(2) },
+			1_000_000_000_000_000_000_000_000,
+			1_000_000_000_000_000_000_000_000,
+			0
+		));
+
+		let alice_asset_1_balance = Assets::balance(1, 1);
+		let alice_asset_2_balance = Assets::balance(2, 1);
+
+		assert_ok!(Humidefi::place_limit_order(
+			alice.clone(),
+			NativeOrAssetId::Asset(1),
+			amount_in,
+			NativeOrAssetId::Asset(2),
+			limit_price,
+			10
+		));
+		assert_eq!(Assets::balance(1, 1), alice_asset_1_balance - amount_in);
+		assert_eq!(Humidefi::account_limit_orders(1).into_inner(), vec![0]);
+
+		// The pool pays less than the limit price, so the order stays open.
+		Humidefi::on_idle(System::block_number(), Weight::MAX);
+		assert!(Humidefi::limit_order(0).is_some());
+
+		assert_ok!(Humidefi::swap_exact_in_for_out(
+			charlie,
+			NativeOrAssetId::Asset(2),
+			300_000_000_000_000_000_000_000,
+			NativeOrAssetId::Asset(1),
+			0,
+			None
+		));
+
+		Humidefi::on_idle(System::block_number(), Weight::MAX);
+		assert!(Humidefi::limit_order(0).is_none());
+		assert!(Humidefi::account_limit_orders(1).is_empty());
+
+		let amount_out = Assets::balance(2, 1) - alice_asset_2_balance;
+		assert!(amount_out >= limit_price.saturating_mul_int(amount_in));
+		System::assert_last_event(Event::LimitOrderFilled { id: 0, owner: 1, amount_in, amount_out }.into());
+
+		// Orders out of reach can be cancelled by their owner only.
+		assert_ok!(Humidefi::place_limit_order(
+			alice.clone(),
+			NativeOrAssetId::Asset(1),
+			amount_in,
+			NativeOrAssetId::Asset(2),
+			FixedU128::from_u32(10),
+			10
+		));
+		assert_noop!(Humidefi::cancel_limit_order(bob, 1), Error::<Test>::NotLimitOrderOwner);
+
+		let alice_asset_1_balance = Assets::balance(1, 1);
+		assert_ok!(Humidefi::cancel_limit_order(alice.clone(), 1));
+		assert_eq!(Assets::balance(1, 1), alice_asset_1_balance + amount_in);
+		assert!(Humidefi::limit_order(1).is_none());
+		assert_noop!(Humidefi::cancel_limit_order(alice.clone(), 1), Error::<Test>::LimitOrderDoesNotExist);
+
+		// Expired orders are refunded the next time they are checked.
+		assert_ok!(Humidefi::place_limit_order(
+			alice,
+			NativeOrAssetId::Asset(1),
+			amount_in,
+			NativeOrAssetId::Asset(2),
+			FixedU128::from_u32(10),
+			2
+		));
+
+		let alice_asset_1_balance = Assets::balance(1, 1);
+		System::set_block_number(3);
+		Humidefi::on_idle(System::block_number(), Weight::MAX);
+		assert_eq!(Assets::balance(1, 1), alice_asset_1_balance + amount_in);
+		assert!(Humidefi::limit_order(2).is_none());
+		System::assert_last_event(Event::LimitOrderExpired { id: 2, owner: 1, amount_in }.into());
+	});
+}
+
+#[test]
+fn dca_schedules_swap_every_period_until_cancelled_or_completed() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+		let amount_per_period: u128 = 1_000_000_000_000_000_000_000;
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			AssetPairs::<Test> { asset_x: NativeOrAssetId::Asset(1), asset_y: NativeOrAssetId::Asset(2) },
+			1_000_000_000_000_000_000_000_000,
+			1_000_000_000_000_000_000_000_000,
+			0
+		));
+
+		assert_noop!(
+			Humidefi::schedule_dca(alice.clone(), NativeOrAssetId::Asset(1), NativeOrAssetId::Asset(2), amount_per_period, Perbill::from_percent(1), 0, 2),
+			Error::<Test>::InvalidDcaSchedule
+		);
+
+		let alice_asset_1_balance = Assets::balance(1, 1);
+		let alice_asset_2_balance = Assets::balance(2, 1);
+
+		assert_ok!(Humidefi::schedule_dca(
+			alice.clone(),
+			NativeOrAssetId::Asset(1),
+			NativeOrAssetId::Asset(2),
+			amount_per_period,
+			Perbill::from_percent(1),
+			5,
+			2
+		));
+		assert_eq!(Assets::balance(1, 1), alice_asset_1_balance - 2 * amount_per_period);
+		assert_eq!(Humidefi::dca_agenda(2).into_inner(), vec![0]);
+
+		System::set_block_number(2);
+		Humidefi::on_initialize(2);
+
+		let amount_out = Assets::balance(2, 1) - alice_asset_2_balance;
+		assert!(!amount_out.is_zero());
+		System::assert_last_event(Event::DcaExecuted {
+			id: 0,
+			owner: 1,
+			amount_in: amount_per_period,
+			amount_out,
+			remaining_runs: 1,
+		}.into());
+		assert!(Humidefi::dca_agenda(2).is_empty());
+		assert_eq!(Humidefi::dca_agenda(7).into_inner(), vec![0]);
+
+		System::set_block_number(7);
+		Humidefi::on_initialize(7);
+
+		System::assert_has_event(Event::DcaExecuted {
+			id: 0,
+			owner: 1,
+			amount_in: amount_per_period,
+			amount_out: Assets::balance(2, 1) - alice_asset_2_balance - amount_out,
+			remaining_runs: 0,
+		}.into());
+		System::assert_last_event(Event::DcaCompleted { id: 0, owner: 1, refunded: 0 }.into());
+		assert!(Humidefi::dca_schedule(0).is_none());
+		assert!(Humidefi::account_dca_schedules(1).is_empty());
+
+		// Cancelling after the first run returns the funds of the runs left.
+		assert_ok!(Humidefi::schedule_dca(
+			alice.clone(),
+			NativeOrAssetId::Asset(1),
+			NativeOrAssetId::Asset(2),
+			amount_per_period,
+			Perbill::from_percent(1),
+			5,
+			3
+		));
+
+		System::set_block_number(8);
+		Humidefi::on_initialize(8);
+		assert_eq!(Humidefi::dca_schedule(1).map(|dca_schedule| dca_schedule.remaining_runs), Some(2));
+
+		assert_noop!(Humidefi::cancel_dca(bob, 1), Error::<Test>::NotDcaScheduleOwner);
+
+		let alice_asset_1_balance = Assets::balance(1, 1);
+		assert_ok!(Humidefi::cancel_dca(alice.clone(), 1));
+		assert_eq!(Assets::balance(1, 1), alice_asset_1_balance + 2 * amount_per_period);
+		System::assert_last_event(Event::DcaCancelled { id: 1, owner: 1, refunded: 2 * amount_per_period }.into());
+		assert!(Humidefi::dca_schedule(1).is_none());
+		assert!(Humidefi::dca_agenda(13).is_empty());
+		assert_noop!(Humidefi::cancel_dca(alice, 1), Error::<Test>::DcaScheduleDoesNotExist);
+	});
+}
+
+#[test]
+fn dca_runs_are_skipped_beyond_their_max_slippage() {
+	new_test_ext().execute_with(|| {
+		let alice = RuntimeOrigin::signed(1);
+		let bob = RuntimeOrigin::signed(2);
+		let amount_per_period: u128 = 100_000_000_000_000_000_000_000;
+
+		let asset_pairs = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		assert_ok!(Humidefi::new_liquidity(
+			bob.clone(),
+			asset_pairs.clone(),
+			1_000_000_000_000_000_000_000_000,
+			1_000_000_000_000_000_000_000_000,
+			0
+		));
+
+		// A tenth of the pool per run moves the price far more than the schedule allows.
+		assert_ok!(Humidefi::schedule_dca(
+			alice.clone(),
+			NativeOrAssetId::Asset(1),
+			NativeOrAssetId::Asset(2),
+			amount_per_period,
+			Perbill::from_percent(1),
+			5,
+			2
+		));
+
+		let alice_asset_1_balance = Assets::balance(1, 1);
+		let alice_asset_2_balance = Assets::balance(2, 1);
+		let liquidity_pool = Humidefi::liquidity_pool_storage(asset_pairs.clone()).unwrap();
+
+		System::set_block_number(2);
+		Humidefi::on_initialize(2);
+
+		System::assert_last_event(Event::DcaRunFailed {
+			id: 0,
+			owner: 1,
+			error: Error::<Test>::SlippageExceeded.into(),
+			remaining_runs: 1,
+		}.into());
+		assert_eq!(Assets::balance(2, 1), alice_asset_2_balance);
+		assert_eq!(Humidefi::liquidity_pool_storage(asset_pairs).unwrap(), liquidity_pool);
+		assert_eq!(Humidefi::dca_schedule(0).map(|dca_schedule| dca_schedule.escrowed), Some(2 * amount_per_period));
+
+		// The skipped runs go back to the owner once the schedule ends.
+		System::set_block_number(7);
+		Humidefi::on_initialize(7);
+
+		System::assert_last_event(Event::DcaCompleted { id: 0, owner: 1, refunded: 2 * amount_per_period }.into());
+		assert_eq!(Assets::balance(1, 1), alice_asset_1_balance + 2 * amount_per_period);
+		assert_eq!(Assets::balance(2, 1), alice_asset_2_balance);
+
+		// With room for the price impact the same run goes through.
+		assert_ok!(Humidefi::schedule_dca(
+			alice,
+			NativeOrAssetId::Asset(1),
+			NativeOrAssetId::Asset(2),
+			amount_per_period,
+			Perbill::from_percent(20),
+			5,
+			1
+		));
+
+		System::set_block_number(8);
+		Humidefi::on_initialize(8);
+		assert!(Assets::balance(2, 1) > alice_asset_2_balance);
+	});
+}
+
+#[test]
+fn migrations_merge_reversed_duplicate_pools() {
+	new_test_ext().execute_with(|| {
+		let bob = RuntimeOrigin::signed(2);
+		let dex_account_id = Humidefi::get_dex_account();
+		let canonical_pair = AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		};
+
+		// Baseline storage where Alice opened the pool and Bob topped it up through the reversed
+		// pair. The baseline found the pool either way round, minted its LP token and wrote the
+		// pool under Bob's order with his deposit added to the opposite sides. Alice also holds a
+		// pool of an unrelated pair in the same DEX account.
+		StorageVersion::new(0).put::<Humidefi>();
+
+		for (lp_token, account_id, lp_token_balance) in [
+			(10, 1u64, 2_000_000_000_000_000_000_000),
+			(10, 2u64, 200_000_000_000_000_000_000),
+			(12, 1u64, 500_000_000_000_000_000_000),
+		] {
+			if !<Assets as fungibles::Inspect<u64>>::asset_exists(lp_token) {
+				assert_ok!(<Assets as fungibles::Create<u64>>::create(lp_token, dex_account_id, true, 1));
+			}
+			assert_ok!(<Assets as fungibles::Mutate<u64>>::mint_into(lp_token, &account_id, lp_token_balance));
+		}
+		assert_ok!(<Assets as fungibles::Mutate<u64>>::mint_into(1, &dex_account_id, 1_600_000_000_000_000_000_000));
+		assert_ok!(<Assets as fungibles::Mutate<u64>>::mint_into(2, &dex_account_id, 4_400_000_000_000_000_000_000));
+		assert_ok!(<Assets as fungibles::Mutate<u64>>::mint_into(3, &dex_account_id, 500_000_000_000_000_000_000));
+
+		for (asset_pair, asset_x_balance, asset_y_balance, lp_token, lp_token_balance) in [
+			(migrations::v0::AssetPairs::<Test> { asset_x: 1, asset_y: 2 }, 1_000_000_000_000_000_000_000, 4_000_000_000_000_000_000_000, 10, 2_000_000_000_000_000_000_000),
+			(migrations::v0::AssetPairs::<Test> { asset_x: 2, asset_y: 1 }, 1_400_000_000_000_000_000_000, 4_100_000_000_000_000_000_000, 10, 2_200_000_000_000_000_000_000),
+			(migrations::v0::AssetPairs::<Test> { asset_x: 1, asset_y: 3 }, 500_000_000_000_000_000_000, 500_000_000_000_000_000_000, 12, 500_000_000_000_000_000_000),
+		] {
+			migrations::v0::LiquidityPoolStorage::<Test>::insert(asset_pair.clone(), migrations::v0::LiquidityPool::<Test> {
+				asset_pair: asset_pair.clone(),
+				asset_x_balance: FixedU128::from_inner(asset_x_balance),
+				asset_y_balance: FixedU128::from_inner(asset_y_balance),
+				price: FixedU128::from_rational(asset_y_balance, asset_x_balance),
+				asset_x_fee: FixedU128::from_inner(0),
+				asset_y_fee: FixedU128::from_inner(0),
+				lp_token,
+				lp_token_balance: FixedU128::from_inner(lp_token_balance),
+			});
+		}
+
+		for (account_id, asset_pair, asset_x_balance, asset_y_balance, lp_token, lp_token_balance) in [
+			(1u64, migrations::v0::AssetPairs::<Test> { asset_x: 1, asset_y: 2 }, 1_000_000_000_000_000_000_000, 4_000_000_000_000_000_000_000, 10, 2_000_000_000_000_000_000_000),
+			(2u64, migrations::v0::AssetPairs::<Test> { asset_x: 2, asset_y: 1 }, 400_000_000_000_000_000_000, 100_000_000_000_000_000_000, 10, 200_000_000_000_000_000_000),
+			(1u64, migrations::v0::AssetPairs::<Test> { asset_x: 1, asset_y: 3 }, 500_000_000_000_000_000_000, 500_000_000_000_000_000_000, 12, 500_000_000_000_000_000_000),
+		] {
+			let account_liquidity_pools = vec![migrations::v0::AccountLiquidityPool::<Test> {
+				id: 1,
+				account_id,
+				asset_pair: asset_pair.clone(),
+				asset_x_balance: FixedU128::from_inner(asset_x_balance),
+				asset_y_balance: FixedU128::from_inner(asset_y_balance),
+				lp_token,
+				lp_token_balance: FixedU128::from_inner(lp_token_balance),
+			}];
+			migrations::v0::AccountLiquidityPoolStorage::<Test>::insert(
+				(account_id, asset_pair),
+				frame_support::BoundedVec::truncate_from(account_liquidity_pools),
+			);
+		}
+
+		migrations::v1::MigrateToNativeOrAssetIds::<Test>::on_runtime_upgrade();
+		migrations::v2::MigrateToPoolAccounts::<Test>::on_runtime_upgrade();
+		migrations::v3::AddPoolStatus::<Test>::on_runtime_upgrade();
+		migrations::v4::AddPositionRanges::<Test>::on_runtime_upgrade();
+		assert_eq!(Humidefi::on_chain_storage_version(), crate::STORAGE_VERSION);
+
+		// The pool holds what the DEX account held beyond the unrelated pool, and every LP token
+		// issued, without minting any more or registering the shared LP token as merged.
+		let liquidity_pool = Humidefi::liquidity_pool_storage(canonical_pair.clone()).unwrap();
+		assert_eq!(crate::LiquidityPoolStorage::<Test>::iter().count(), 2);
+		assert_eq!(liquidity_pool.asset_x_balance, FixedU128::from_inner(1_100_000_000_000_000_000_000));
+		assert_eq!(liquidity_pool.asset_y_balance, FixedU128::from_inner(4_400_000_000_000_000_000_000));
+		assert_eq!(liquidity_pool.price, FixedU128::from_u32(4));
+		assert_eq!(liquidity_pool.lp_token, 10);
+		assert_eq!(liquidity_pool.lp_token_balance.into_inner(), 2_200_000_000_000_000_000_000);
+		assert_eq!(<Assets as fungibles::Inspect<u64>>::total_issuance(10), 2_200_000_000_000_000_000_000);
+		assert!(Humidefi::merged_lp_token(10).is_none());
+
+		// Bob's position now sits in the canonical pool with its sides flipped back.
+		let account_liquidity_pools = Humidefi::account_liquidity_pool_storage((2u64, canonical_pair.clone())).unwrap();
+		assert_eq!(account_liquidity_pools.len(), 1);
+		assert_eq!(account_liquidity_pools[0].id, 1);
+		assert_eq!(account_liquidity_pools[0].lp_token, 10);
+		assert_eq!(account_liquidity_pools[0].asset_x_balance, FixedU128::from_inner(100_000_000_000_000_000_000));
+		assert_eq!(account_liquidity_pools[0].asset_y_balance, FixedU128::from_inner(400_000_000_000_000_000_000));
+		assert_ok!(Humidefi::do_try_state());
+
+		assert_noop!(
+			Humidefi::convert_merged_lp_token(bob.clone(), 10, 1),
+			Error::<Test>::LpTokenNotMerged
+		);
+
+		// Redeeming pays Bob back exactly what he deposited.
+		let bob_asset_x_balance = Assets::balance(1, 2);
+		let bob_asset_y_balance = Assets::balance(2, 2);
+
+		assert_ok!(Humidefi::redeem_liquidity(bob, canonical_pair, 10, 1));
+		assert_eq!(Assets::balance(1, 2) - bob_asset_x_balance, 100_000_000_000_000_000_000);
+		assert_eq!(Assets::balance(2, 2) - bob_asset_y_balance, 400_000_000_000_000_000_000);
+		assert_ok!(Humidefi::do_try_state());
+	});
+}
+
+#[test]
+fn migrations_leave_undecodable_pools_in_place() {
+	new_test_ext().execute_with(|| {
+		let asset_pair = migrations::v0::AssetPairs::<Test> { asset_x: 1, asset_y: 2 };
+		let undecodable_asset_pair = migrations::v0::AssetPairs::<Test> { asset_x: 2, asset_y: 3 };
+
+		StorageVersion::new(0).put::<Humidefi>();
+
+		migrations::v0::LiquidityPoolStorage::<Test>::insert(asset_pair.clone(), migrations::v0::LiquidityPool::<Test> {
+			asset_pair: asset_pair.clone(),
+			asset_x_balance: FixedU128::from_inner(1_000_000_000_000_000_000_000),
+			asset_y_balance: FixedU128::from_inner(2_000_000_000_000_000_000_000),
+			price: FixedU128::from_u32(2),
+			asset_x_fee: FixedU128::from_inner(0),
+			asset_y_fee: FixedU128::from_inner(0),
+			lp_token: 10,
+			lp_token_balance: FixedU128::from_inner(1_000_000_000_000_000_000_000),
+		});
+
+		// Values that were never written in the baseline layout.
+		let undecodable_pool_key = migrations::v0::LiquidityPoolStorage::<Test>::hashed_key_for(undecodable_asset_pair.clone());
+		let undecodable_positions_key = migrations::v0::AccountLiquidityPoolStorage::<Test>::hashed_key_for((2u64, undecodable_asset_pair));
+		frame_support::storage::unhashed::put_raw(&undecodable_pool_key, &[0xff; 3]);
+		frame_support::storage::unhashed::put_raw(&undecodable_positions_key, &[0xff; 3]);
+
+		#[cfg(feature = "try-runtime")]
+		assert!(migrations::v1::MigrateToNativeOrAssetIds::<Test>::pre_upgrade().is_err());
+
+		migrations::v1::MigrateToNativeOrAssetIds::<Test>::on_runtime_upgrade();
+		assert_eq!(Humidefi::on_chain_storage_version(), 1);
+
+		// The pool that decodes is migrated, the rest is left where it was for a fix.
+		let liquidity_pool = Humidefi::liquidity_pool_storage(AssetPairs::<Test> {
+			asset_x: NativeOrAssetId::Asset(1),
+			asset_y: NativeOrAssetId::Asset(2)
+		}).unwrap();
+		assert_eq!(liquidity_pool.lp_token, 10);
+		assert_eq!(frame_support::storage::unhashed::get_raw(&undecodable_pool_key), Some(vec![0xff; 3]));
+		assert_eq!(frame_support::storage::unhashed::get_raw(&undecodable_positions_key), Some(vec![0xff; 3]));
 	});
 }
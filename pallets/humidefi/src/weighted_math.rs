@@ -0,0 +1,143 @@
+//! Balancer-style weighted constant-mean math for two-asset pools.
+//!
+//! The invariant is `V = x^w_x · y^w_y` with `w_x + w_y = 1`. A 50/50 pool is the constant product
+//! pool; an 80/20 pool keeps four fifths of its value in `x`.
+//!
+//! Powers with fractional exponents are built from repeated square roots, so every result carries a
+//! small error. `pow_up` and `pow_down` bound it, and callers pick the side that favours the pool.
+
+use frame_support::sp_runtime::{
+	helpers_128bit::multiply_by_rational_with_rounding,
+	traits::{CheckedMul, Saturating},
+	FixedPointNumber,
+	FixedU128,
+	PerThing,
+	Perbill,
+	Rounding,
+};
+
+/// Fractional exponent bits that are resolved; the rest is covered by `MAX_POW_RELATIVE_ERROR`.
+const FRACTION_BITS: usize = 40;
+
+/// `1e-10`, well above the error of `pow` for any base the pool can produce.
+const MAX_POW_RELATIVE_ERROR: FixedU128 = FixedU128::from_inner(100_000_000);
+
+fn checked_rational(numerator: u128, denominator: u128, rounding: Rounding) -> Option<FixedU128> {
+	multiply_by_rational_with_rounding(numerator, FixedU128::DIV, denominator, rounding).map(FixedU128::from_inner)
+}
+
+fn pow(base: FixedU128, exponent: FixedU128) -> Option<FixedU128> {
+	let mut result = FixedU128::one();
+	for _ in 0..exponent.into_inner() / FixedU128::DIV {
+		result = result.checked_mul(&base)?;
+	}
+
+	// base^(Σ b_k / 2^k) = Π (base^(1 / 2^k)) over the set bits b_k of the fraction.
+	let mut fraction = exponent.into_inner() % FixedU128::DIV;
+	let mut root = base;
+	for _ in 0..FRACTION_BITS {
+		if fraction == 0 {
+			break;
+		}
+
+		root = root.sqrt();
+		fraction = fraction.saturating_mul(2);
+		if fraction >= FixedU128::DIV {
+			fraction -= FixedU128::DIV;
+			result = result.checked_mul(&root)?;
+		}
+	}
+
+	Some(result)
+}
+
+fn pow_up(base: FixedU128, exponent: FixedU128) -> Option<FixedU128> {
+	let raw = pow(base, exponent)?;
+	Some(raw.saturating_add(raw.saturating_mul(MAX_POW_RELATIVE_ERROR)).saturating_add(FixedU128::from_inner(1)))
+}
+
+fn pow_down(base: FixedU128, exponent: FixedU128) -> Option<FixedU128> {
+	let raw = pow(base, exponent)?;
+	Some(raw.saturating_sub(raw.saturating_mul(MAX_POW_RELATIVE_ERROR)).saturating_sub(FixedU128::from_inner(1)))
+}
+
+fn weight_ratio(numerator: Perbill, denominator: Perbill, rounding: Rounding) -> Option<FixedU128> {
+	if denominator.deconstruct() == 0 {
+		return None;
+	}
+
+	checked_rational(u128::from(numerator.deconstruct()), u128::from(denominator.deconstruct()), rounding)
+}
+
+/// Amount of the other asset paid out for `amount_in`, rounded down:
+/// `reserve_out · (1 - (reserve_in / (reserve_in + amount_in))^(weight_in / weight_out))`.
+pub fn compute_amount_out(
+	reserve_in: u128,
+	reserve_out: u128,
+	amount_in: u128,
+	weight_in: Perbill,
+	weight_out: Perbill,
+) -> Option<u128> {
+	let base = checked_rational(reserve_in, reserve_in.checked_add(amount_in)?, Rounding::Up)?;
+	let exponent = weight_ratio(weight_in, weight_out, Rounding::Down)?;
+	let complement = FixedU128::one().saturating_sub(pow_up(base, exponent)?);
+
+	multiply_by_rational_with_rounding(reserve_out, complement.into_inner(), FixedU128::DIV, Rounding::Down)
+}
+
+/// Amount of the other asset needed to take `amount_out` out of the pool, rounded up:
+/// `reserve_in · ((reserve_out / (reserve_out - amount_out))^(weight_out / weight_in) - 1)`.
+pub fn compute_amount_in(
+	reserve_in: u128,
+	reserve_out: u128,
+	amount_out: u128,
+	weight_in: Perbill,
+	weight_out: Perbill,
+) -> Option<u128> {
+	let base = checked_rational(reserve_out, reserve_out.checked_sub(amount_out)?, Rounding::Up)?;
+	let exponent = weight_ratio(weight_out, weight_in, Rounding::Up)?;
+	let ratio = pow_up(base, exponent)?.saturating_sub(FixedU128::one());
+
+	multiply_by_rational_with_rounding(reserve_in, ratio.into_inner(), FixedU128::DIV, Rounding::Up)
+}
+
+/// Marginal price of `asset_x` in `asset_y`: `(y / w_y) / (x / w_x)`.
+pub fn compute_price(reserve_x: u128, reserve_y: u128, weight_x: Perbill) -> Option<FixedU128> {
+	let weight_y = weight_x.left_from_one();
+	let reserve_ratio = FixedU128::checked_from_rational(reserve_y, reserve_x)?;
+
+	reserve_ratio.checked_mul(&weight_ratio(weight_x, weight_y, Rounding::Down)?)
+}
+
+/// The invariant `x^w_x · y^w_y`, rounded down. It equals `sqrt(x · y)` for a 50/50 pool.
+pub fn compute_invariant(reserve_x: u128, reserve_y: u128, weight_x: Perbill) -> Option<u128> {
+	let weight_y = weight_x.left_from_one();
+
+	let value_x = pow_down(FixedU128::from_inner(reserve_x), FixedU128::from_perbill(weight_x))?;
+	let value_y = pow_down(FixedU128::from_inner(reserve_y), FixedU128::from_perbill(weight_y))?;
+
+	Some(value_x.checked_mul(&value_y)?.into_inner())
+}
+
+/// Whether moving the reserves to `update_reserve_x` and `update_reserve_y` keeps the invariant.
+///
+/// Both sides are measured with `pow_down`, so the check allows for the error of two powers; swap
+/// amounts are quoted with `pow_up` and already leave that margin in the pool.
+pub fn invariant_holds(
+	reserve_x: u128,
+	reserve_y: u128,
+	update_reserve_x: u128,
+	update_reserve_y: u128,
+	weight_x: Perbill,
+) -> Option<bool> {
+	let weight_y = weight_x.left_from_one();
+
+	let ratio_x = checked_rational(update_reserve_x, reserve_x, Rounding::Down)?;
+	let ratio_y = checked_rational(update_reserve_y, reserve_y, Rounding::Down)?;
+
+	let invariant_ratio = pow_down(ratio_x, FixedU128::from_perbill(weight_x))?
+		.checked_mul(&pow_down(ratio_y, FixedU128::from_perbill(weight_y))?)?;
+	let tolerance = MAX_POW_RELATIVE_ERROR.saturating_mul(FixedU128::saturating_from_integer(4u32));
+
+	Some(invariant_ratio.saturating_add(tolerance) >= FixedU128::one())
+}
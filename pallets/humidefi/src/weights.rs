@@ -0,0 +1,606 @@
+
+//! Weights for pallet_humidefi
+//!
+//! These values are conservative estimates derived from the storage accesses of each call,
+//! laid out in the format produced by the benchmark CLI. Regenerate them on reference hardware
+//! before relying on them, with a node built with `--features runtime-benchmarks`:
+//!
+//! ./target/release/xode-node benchmark pallet \
+//!     --chain=dev \
+//!     --wasm-execution=compiled \
+//!     --pallet=pallet_humidefi \
+//!     --extrinsic=* \
+//!     --steps=50 \
+//!     --repeat=20 \
+//!     --output=pallets/humidefi/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_humidefi.
+pub trait WeightInfo {
+	fn new_liquidity() -> Weight;
+	fn redeem_liquidity() -> Weight;
+	fn redeem_liquidity_partial() -> Weight;
+	fn redeem_lp_token() -> Weight;
+	fn convert_merged_lp_token() -> Weight;
+	fn swap_exact_in_for_out() -> Weight;
+	fn swap_in_for_exact_out() -> Weight;
+	fn swap_exact_in_for_out_path(n: u32, ) -> Weight;
+	fn swap_in_for_exact_out_path(n: u32, ) -> Weight;
+	fn transfer_asset() -> Weight;
+	fn set_swap_fee() -> Weight;
+	fn set_listing_mode() -> Weight;
+	fn set_asset_listing() -> Weight;
+	fn pause_pool() -> Weight;
+	fn pause_all() -> Weight;
+	fn unpause() -> Weight;
+	fn create_pool() -> Weight;
+	fn set_amplification() -> Weight;
+	fn add_range_liquidity() -> Weight;
+	fn remove_range_liquidity() -> Weight;
+	fn place_limit_order() -> Weight;
+	fn cancel_limit_order() -> Weight;
+	fn process_limit_order() -> Weight;
+	fn schedule_dca() -> Weight;
+	fn cancel_dca() -> Weight;
+	fn execute_dca_run() -> Weight;
+}
+
+/// Weights for pallet_humidefi using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: Assets Asset (r:3 w:3)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:7 w:7)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AccountLiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule AccountLiquidityPoolStorage (max_values: None, max_size: Some(21777), added: 24252, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AssetListingMode (r:1 w:0)
+	/// Proof: HumidefiModule AssetListingMode (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AssetListing (r:2 w:0)
+	/// Proof: HumidefiModule AssetListing (max_values: None, max_size: Some(22), added: 2497, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceAccumulators (r:1 w:1)
+	/// Proof: HumidefiModule PriceAccumulators (max_values: None, max_size: Some(78), added: 2553, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceObservations (r:1 w:1)
+	/// Proof: HumidefiModule PriceObservations (max_values: None, max_size: Some(5231), added: 7706, mode: MaxEncodedLen)
+	fn new_liquidity() -> Weight {
+		Weight::from_parts(236_917_000, 25242)
+			.saturating_add(T::DbWeight::get().reads(18_u64))
+			.saturating_add(T::DbWeight::get().writes(15_u64))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AccountLiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule AccountLiquidityPoolStorage (max_values: None, max_size: Some(21777), added: 24252, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:3 w:3)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:5 w:5)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceAccumulators (r:1 w:1)
+	/// Proof: HumidefiModule PriceAccumulators (max_values: None, max_size: Some(78), added: 2553, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceObservations (r:1 w:1)
+	/// Proof: HumidefiModule PriceObservations (max_values: None, max_size: Some(5231), added: 7706, mode: MaxEncodedLen)
+	fn redeem_liquidity() -> Weight {
+		Weight::from_parts(191_870_000, 25242)
+			.saturating_add(T::DbWeight::get().reads(12_u64))
+			.saturating_add(T::DbWeight::get().writes(12_u64))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AccountLiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule AccountLiquidityPoolStorage (max_values: None, max_size: Some(21777), added: 24252, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:3 w:3)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:5 w:5)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceAccumulators (r:1 w:1)
+	/// Proof: HumidefiModule PriceAccumulators (max_values: None, max_size: Some(78), added: 2553, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceObservations (r:1 w:1)
+	/// Proof: HumidefiModule PriceObservations (max_values: None, max_size: Some(5231), added: 7706, mode: MaxEncodedLen)
+	fn redeem_liquidity_partial() -> Weight {
+		Weight::from_parts(193_512_000, 25242)
+			.saturating_add(T::DbWeight::get().reads(12_u64))
+			.saturating_add(T::DbWeight::get().writes(12_u64))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AccountLiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule AccountLiquidityPoolStorage (max_values: None, max_size: Some(21777), added: 24252, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:3 w:3)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:5 w:5)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceAccumulators (r:1 w:1)
+	/// Proof: HumidefiModule PriceAccumulators (max_values: None, max_size: Some(78), added: 2553, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceObservations (r:1 w:1)
+	/// Proof: HumidefiModule PriceObservations (max_values: None, max_size: Some(5231), added: 7706, mode: MaxEncodedLen)
+	fn redeem_lp_token() -> Weight {
+		Weight::from_parts(196_238_000, 25242)
+			.saturating_add(T::DbWeight::get().reads(12_u64))
+			.saturating_add(T::DbWeight::get().writes(12_u64))
+	}
+	/// Storage: HumidefiModule MergedLpTokens (r:1 w:0)
+	/// Proof: HumidefiModule MergedLpTokens (max_values: None, max_size: Some(46), added: 2521, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:0)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:2 w:2)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:3 w:3)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	fn convert_merged_lp_token() -> Weight {
+		Weight::from_parts(104_582_000, 8817)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:2 w:2)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:4 w:4)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule GlobalPoolStatus (r:1 w:0)
+	/// Proof: HumidefiModule GlobalPoolStatus (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule BlockOpeningPrice (r:1 w:1)
+	/// Proof: HumidefiModule BlockOpeningPrice (max_values: None, max_size: Some(62), added: 2537, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceAccumulators (r:1 w:1)
+	/// Proof: HumidefiModule PriceAccumulators (max_values: None, max_size: Some(78), added: 2553, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceObservations (r:1 w:1)
+	/// Proof: HumidefiModule PriceObservations (max_values: None, max_size: Some(5231), added: 7706, mode: MaxEncodedLen)
+	fn swap_exact_in_for_out() -> Weight {
+		Weight::from_parts(124_933_000, 6208)
+			.saturating_add(T::DbWeight::get().reads(11_u64))
+			.saturating_add(T::DbWeight::get().writes(10_u64))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:2 w:2)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:4 w:4)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule GlobalPoolStatus (r:1 w:0)
+	/// Proof: HumidefiModule GlobalPoolStatus (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule BlockOpeningPrice (r:1 w:1)
+	/// Proof: HumidefiModule BlockOpeningPrice (max_values: None, max_size: Some(62), added: 2537, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceAccumulators (r:1 w:1)
+	/// Proof: HumidefiModule PriceAccumulators (max_values: None, max_size: Some(78), added: 2553, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceObservations (r:1 w:1)
+	/// Proof: HumidefiModule PriceObservations (max_values: None, max_size: Some(5231), added: 7706, mode: MaxEncodedLen)
+	fn swap_in_for_exact_out() -> Weight {
+		Weight::from_parts(126_417_000, 6208)
+			.saturating_add(T::DbWeight::get().reads(11_u64))
+			.saturating_add(T::DbWeight::get().writes(10_u64))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:3 w:3)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:4 w:4)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:8 w:8)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// The range of component `n` is `[2, 4]`.
+	/// Storage: HumidefiModule PriceAccumulators (r:1 w:1)
+	/// Proof: HumidefiModule PriceAccumulators (max_values: None, max_size: Some(78), added: 2553, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceObservations (r:1 w:1)
+	/// Proof: HumidefiModule PriceObservations (max_values: None, max_size: Some(5231), added: 7706, mode: MaxEncodedLen)
+	fn swap_exact_in_for_out_path(n: u32, ) -> Weight {
+		Weight::from_parts(12_837_000, 990)
+			.saturating_add(Weight::from_parts(118_560_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads((11_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((10_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 5274).saturating_mul(n.into()))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:3 w:3)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:4 w:4)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:8 w:8)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// The range of component `n` is `[2, 4]`.
+	/// Storage: HumidefiModule PriceAccumulators (r:1 w:1)
+	/// Proof: HumidefiModule PriceAccumulators (max_values: None, max_size: Some(78), added: 2553, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceObservations (r:1 w:1)
+	/// Proof: HumidefiModule PriceObservations (max_values: None, max_size: Some(5231), added: 7706, mode: MaxEncodedLen)
+	fn swap_in_for_exact_out_path(n: u32, ) -> Weight {
+		Weight::from_parts(13_204_000, 990)
+			.saturating_add(Weight::from_parts(120_391_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads((11_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((10_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 5274).saturating_mul(n.into()))
+	}
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:2 w:2)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	fn transfer_asset() -> Weight {
+		Weight::from_parts(60_233_000, 6208)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	fn set_swap_fee() -> Weight {
+		Weight::from_parts(18_456_000, 3655)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: HumidefiModule AssetListingMode (r:0 w:1)
+	/// Proof: HumidefiModule AssetListingMode (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	fn set_listing_mode() -> Weight {
+		Weight::from_parts(9_120_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: HumidefiModule AssetListing (r:0 w:1)
+	/// Proof: HumidefiModule AssetListing (max_values: None, max_size: Some(22), added: 2497, mode: MaxEncodedLen)
+	fn set_asset_listing() -> Weight {
+		Weight::from_parts(10_347_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	fn pause_pool() -> Weight {
+		Weight::from_parts(17_902_000, 3656)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: HumidefiModule GlobalPoolStatus (r:0 w:1)
+	/// Proof: HumidefiModule GlobalPoolStatus (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	fn pause_all() -> Weight {
+		Weight::from_parts(8_934_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	fn unpause() -> Weight {
+		Weight::from_parts(17_902_000, 3656)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PoolKinds (r:1 w:1)
+	/// Proof: HumidefiModule PoolKinds (max_values: None, max_size: Some(31), added: 2506, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:3 w:3)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:7 w:7)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AccountLiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule AccountLiquidityPoolStorage (max_values: None, max_size: Some(21777), added: 24252, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AssetListingMode (r:1 w:0)
+	/// Proof: HumidefiModule AssetListingMode (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AssetListing (r:2 w:0)
+	/// Proof: HumidefiModule AssetListing (max_values: None, max_size: Some(22), added: 2497, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceAccumulators (r:1 w:1)
+	/// Proof: HumidefiModule PriceAccumulators (max_values: None, max_size: Some(78), added: 2553, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceObservations (r:1 w:1)
+	/// Proof: HumidefiModule PriceObservations (max_values: None, max_size: Some(5231), added: 7706, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule ConcentratedPools (r:1 w:1)
+	/// Proof: HumidefiModule ConcentratedPools (max_values: None, max_size: Some(94), added: 2569, mode: MaxEncodedLen)
+	fn create_pool() -> Weight {
+		Weight::from_parts(259_019_000, 25242)
+			.saturating_add(T::DbWeight::get().reads(20_u64))
+			.saturating_add(T::DbWeight::get().writes(17_u64))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PoolKinds (r:1 w:1)
+	/// Proof: HumidefiModule PoolKinds (max_values: None, max_size: Some(31), added: 2506, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceAccumulators (r:1 w:1)
+	/// Proof: HumidefiModule PriceAccumulators (max_values: None, max_size: Some(78), added: 2553, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceObservations (r:1 w:1)
+	/// Proof: HumidefiModule PriceObservations (max_values: None, max_size: Some(5231), added: 7706, mode: MaxEncodedLen)
+	fn set_amplification() -> Weight {
+		Weight::from_parts(64_218_000, 8696)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:0)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule GlobalPoolStatus (r:1 w:0)
+	/// Proof: HumidefiModule GlobalPoolStatus (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PoolKinds (r:1 w:0)
+	/// Proof: HumidefiModule PoolKinds (max_values: None, max_size: Some(31), added: 2506, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule ConcentratedPools (r:1 w:1)
+	/// Proof: HumidefiModule ConcentratedPools (max_values: None, max_size: Some(94), added: 2569, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule Ticks (r:2 w:2)
+	/// Proof: HumidefiModule Ticks (max_values: None, max_size: Some(102), added: 2577, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule InitializedTicks (r:1 w:1)
+	/// Proof: HumidefiModule InitializedTicks (max_values: None, max_size: Some(431), added: 2906, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:2 w:2)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:4 w:4)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AccountLiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule AccountLiquidityPoolStorage (max_values: None, max_size: Some(21777), added: 24252, mode: MaxEncodedLen)
+	fn add_range_liquidity() -> Weight {
+		Weight::from_parts(152_640_000, 25242)
+			.saturating_add(T::DbWeight::get().reads(14_u64))
+			.saturating_add(T::DbWeight::get().writes(11_u64))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:0)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule GlobalPoolStatus (r:1 w:0)
+	/// Proof: HumidefiModule GlobalPoolStatus (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AccountLiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule AccountLiquidityPoolStorage (max_values: None, max_size: Some(21777), added: 24252, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule ConcentratedPools (r:1 w:1)
+	/// Proof: HumidefiModule ConcentratedPools (max_values: None, max_size: Some(94), added: 2569, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule Ticks (r:2 w:2)
+	/// Proof: HumidefiModule Ticks (max_values: None, max_size: Some(102), added: 2577, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule InitializedTicks (r:1 w:1)
+	/// Proof: HumidefiModule InitializedTicks (max_values: None, max_size: Some(431), added: 2906, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:2 w:2)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:4 w:4)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	fn remove_range_liquidity() -> Weight {
+		Weight::from_parts(148_117_000, 25242)
+			.saturating_add(T::DbWeight::get().reads(13_u64))
+			.saturating_add(T::DbWeight::get().writes(11_u64))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:0)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule NextLimitOrderId (r:1 w:1)
+	/// Proof: HumidefiModule NextLimitOrderId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AccountLimitOrders (r:1 w:1)
+	/// Proof: HumidefiModule AccountLimitOrders (max_values: None, max_size: Some(305), added: 2780, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:2 w:2)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule LimitOrders (r:0 w:1)
+	/// Proof: HumidefiModule LimitOrders (max_values: None, max_size: Some(94), added: 2569, mode: MaxEncodedLen)
+	fn place_limit_order() -> Weight {
+		Weight::from_parts(74_386_000, 3790)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+	/// Storage: HumidefiModule LimitOrders (r:1 w:1)
+	/// Proof: HumidefiModule LimitOrders (max_values: None, max_size: Some(94), added: 2569, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AccountLimitOrders (r:1 w:1)
+	/// Proof: HumidefiModule AccountLimitOrders (max_values: None, max_size: Some(305), added: 2780, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:2 w:2)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	fn cancel_limit_order() -> Weight {
+		Weight::from_parts(61_902_000, 3790)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: HumidefiModule LimitOrderCursor (r:1 w:1)
+	/// Proof: HumidefiModule LimitOrderCursor (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule LimitOrders (r:1 w:1)
+	/// Proof: HumidefiModule LimitOrders (max_values: None, max_size: Some(94), added: 2569, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PoolKinds (r:1 w:0)
+	/// Proof: HumidefiModule PoolKinds (max_values: None, max_size: Some(31), added: 2506, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:2 w:2)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:4 w:4)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule GlobalPoolStatus (r:1 w:0)
+	/// Proof: HumidefiModule GlobalPoolStatus (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule BlockOpeningPrice (r:1 w:1)
+	/// Proof: HumidefiModule BlockOpeningPrice (max_values: None, max_size: Some(62), added: 2537, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceAccumulators (r:1 w:1)
+	/// Proof: HumidefiModule PriceAccumulators (max_values: None, max_size: Some(78), added: 2553, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceObservations (r:1 w:1)
+	/// Proof: HumidefiModule PriceObservations (max_values: None, max_size: Some(5231), added: 7706, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AccountLimitOrders (r:1 w:1)
+	/// Proof: HumidefiModule AccountLimitOrders (max_values: None, max_size: Some(305), added: 2780, mode: MaxEncodedLen)
+	fn process_limit_order() -> Weight {
+		Weight::from_parts(203_517_000, 8696)
+			.saturating_add(T::DbWeight::get().reads(15_u64))
+			.saturating_add(T::DbWeight::get().writes(13_u64))
+	}
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:0)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule NextDcaScheduleId (r:1 w:1)
+	/// Proof: HumidefiModule NextDcaScheduleId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AccountDcaSchedules (r:1 w:1)
+	/// Proof: HumidefiModule AccountDcaSchedules (max_values: None, max_size: Some(305), added: 2780, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule DcaAgenda (r:1 w:1)
+	/// Proof: HumidefiModule DcaAgenda (max_values: None, max_size: Some(173), added: 2648, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:2 w:2)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule DcaSchedules (r:0 w:1)
+	/// Proof: HumidefiModule DcaSchedules (max_values: None, max_size: Some(106), added: 2581, mode: MaxEncodedLen)
+	fn schedule_dca() -> Weight {
+		Weight::from_parts(78_604_000, 3790)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: HumidefiModule DcaSchedules (r:1 w:1)
+	/// Proof: HumidefiModule DcaSchedules (max_values: None, max_size: Some(106), added: 2581, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule DcaAgenda (r:1 w:1)
+	/// Proof: HumidefiModule DcaAgenda (max_values: None, max_size: Some(173), added: 2648, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule AccountDcaSchedules (r:1 w:1)
+	/// Proof: HumidefiModule AccountDcaSchedules (max_values: None, max_size: Some(305), added: 2780, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:2 w:2)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	fn cancel_dca() -> Weight {
+		Weight::from_parts(66_375_000, 3790)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: HumidefiModule DcaSchedules (r:1 w:1)
+	/// Proof: HumidefiModule DcaSchedules (max_values: None, max_size: Some(106), added: 2581, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule DcaAgenda (r:1 w:1)
+	/// Proof: HumidefiModule DcaAgenda (max_values: None, max_size: Some(173), added: 2648, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule LiquidityPoolStorage (r:1 w:1)
+	/// Proof: HumidefiModule LiquidityPoolStorage (max_values: None, max_size: Some(191), added: 2666, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PoolKinds (r:1 w:0)
+	/// Proof: HumidefiModule PoolKinds (max_values: None, max_size: Some(31), added: 2506, mode: MaxEncodedLen)
+	/// Storage: Assets Asset (r:2 w:2)
+	/// Proof: Assets Asset (max_values: None, max_size: Some(210), added: 2685, mode: MaxEncodedLen)
+	/// Storage: Assets Account (r:4 w:4)
+	/// Proof: Assets Account (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule GlobalPoolStatus (r:1 w:0)
+	/// Proof: HumidefiModule GlobalPoolStatus (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule BlockOpeningPrice (r:1 w:1)
+	/// Proof: HumidefiModule BlockOpeningPrice (max_values: None, max_size: Some(62), added: 2537, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceAccumulators (r:1 w:1)
+	/// Proof: HumidefiModule PriceAccumulators (max_values: None, max_size: Some(78), added: 2553, mode: MaxEncodedLen)
+	/// Storage: HumidefiModule PriceObservations (r:1 w:1)
+	/// Proof: HumidefiModule PriceObservations (max_values: None, max_size: Some(5231), added: 7706, mode: MaxEncodedLen)
+	fn execute_dca_run() -> Weight {
+		Weight::from_parts(203_415_000, 8696)
+			.saturating_add(T::DbWeight::get().reads(14_u64))
+			.saturating_add(T::DbWeight::get().writes(12_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn new_liquidity() -> Weight {
+		Weight::from_parts(236_917_000, 25242)
+			.saturating_add(RocksDbWeight::get().reads(18_u64))
+			.saturating_add(RocksDbWeight::get().writes(15_u64))
+	}
+	fn redeem_liquidity() -> Weight {
+		Weight::from_parts(191_870_000, 25242)
+			.saturating_add(RocksDbWeight::get().reads(12_u64))
+			.saturating_add(RocksDbWeight::get().writes(12_u64))
+	}
+	fn redeem_liquidity_partial() -> Weight {
+		Weight::from_parts(193_512_000, 25242)
+			.saturating_add(RocksDbWeight::get().reads(12_u64))
+			.saturating_add(RocksDbWeight::get().writes(12_u64))
+	}
+	fn redeem_lp_token() -> Weight {
+		Weight::from_parts(196_238_000, 25242)
+			.saturating_add(RocksDbWeight::get().reads(12_u64))
+			.saturating_add(RocksDbWeight::get().writes(12_u64))
+	}
+	fn convert_merged_lp_token() -> Weight {
+		Weight::from_parts(104_582_000, 8817)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	fn swap_exact_in_for_out() -> Weight {
+		Weight::from_parts(124_933_000, 6208)
+			.saturating_add(RocksDbWeight::get().reads(11_u64))
+			.saturating_add(RocksDbWeight::get().writes(10_u64))
+	}
+	fn swap_in_for_exact_out() -> Weight {
+		Weight::from_parts(126_417_000, 6208)
+			.saturating_add(RocksDbWeight::get().reads(11_u64))
+			.saturating_add(RocksDbWeight::get().writes(10_u64))
+	}
+	fn swap_exact_in_for_out_path(n: u32, ) -> Weight {
+		Weight::from_parts(12_837_000, 990)
+			.saturating_add(Weight::from_parts(118_560_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads((11_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((10_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 5274).saturating_mul(n.into()))
+	}
+	fn swap_in_for_exact_out_path(n: u32, ) -> Weight {
+		Weight::from_parts(13_204_000, 990)
+			.saturating_add(Weight::from_parts(120_391_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads((11_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((10_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 5274).saturating_mul(n.into()))
+	}
+	fn transfer_asset() -> Weight {
+		Weight::from_parts(60_233_000, 6208)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	fn set_swap_fee() -> Weight {
+		Weight::from_parts(18_456_000, 3655)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn set_listing_mode() -> Weight {
+		Weight::from_parts(9_120_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn set_asset_listing() -> Weight {
+		Weight::from_parts(10_347_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn pause_pool() -> Weight {
+		Weight::from_parts(17_902_000, 3656)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn pause_all() -> Weight {
+		Weight::from_parts(8_934_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn unpause() -> Weight {
+		Weight::from_parts(17_902_000, 3656)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn create_pool() -> Weight {
+		Weight::from_parts(259_019_000, 25242)
+			.saturating_add(RocksDbWeight::get().reads(20_u64))
+			.saturating_add(RocksDbWeight::get().writes(17_u64))
+	}
+	fn set_amplification() -> Weight {
+		Weight::from_parts(64_218_000, 8696)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	fn add_range_liquidity() -> Weight {
+		Weight::from_parts(152_640_000, 25242)
+			.saturating_add(RocksDbWeight::get().reads(14_u64))
+			.saturating_add(RocksDbWeight::get().writes(11_u64))
+	}
+	fn remove_range_liquidity() -> Weight {
+		Weight::from_parts(148_117_000, 25242)
+			.saturating_add(RocksDbWeight::get().reads(13_u64))
+			.saturating_add(RocksDbWeight::get().writes(11_u64))
+	}
+	fn place_limit_order() -> Weight {
+		Weight::from_parts(74_386_000, 3790)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+	fn cancel_limit_order() -> Weight {
+		Weight::from_parts(61_902_000, 3790)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	fn process_limit_order() -> Weight {
+		Weight::from_parts(203_517_000, 8696)
+			.saturating_add(RocksDbWeight::get().reads(15_u64))
+			.saturating_add(RocksDbWeight::get().writes(13_u64))
+	}
+	fn schedule_dca() -> Weight {
+		Weight::from_parts(78_604_000, 3790)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	fn cancel_dca() -> Weight {
+		Weight::from_parts(66_375_000, 3790)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	fn execute_dca_run() -> Weight {
+		Weight::from_parts(203_415_000, 8696)
+			.saturating_add(RocksDbWeight::get().reads(14_u64))
+			.saturating_add(RocksDbWeight::get().writes(12_u64))
+	}
+}
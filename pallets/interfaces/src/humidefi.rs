@@ -4,27 +4,57 @@ use core::{fmt::Debug};
 use codec::FullCodec;
 use frame_support::{
 	pallet_prelude::*,
-	sp_runtime::{ BoundedVec,  traits::ConstU32, FixedU128 },
+	sp_runtime::{FixedU128, Perbill},
+	sp_std::vec::Vec,
 };
 
 pub trait AssetPairsTrait {  type AssetPairs; }
-pub trait LiquidityPoolTrait {  type LiquidityPool; }
-pub trait AccountLiquidityPoolTrait {  type AccountLiquidityPool; }
+pub trait RedeemLiquidityAmountTrait {  type RedeemLiquidityAmount; }
 
 pub trait HumidefiCaller {
 	type AccountId: Parameter + Member + MaybeSerializeDeserialize + Debug + Ord + MaxEncodedLen;
 	type AssetId: FullCodec + Clone + Eq + PartialEq + Debug + scale_info::TypeInfo + MaxEncodedLen;
 	type AssetBalance: FullCodec + Copy + Default + Debug + scale_info::TypeInfo + MaxEncodedLen;
+	type AssetKind: FullCodec + Clone + Copy + Eq + PartialEq + Debug + scale_info::TypeInfo + MaxEncodedLen;
 	type AccountLiquidityPoolId: Parameter + Member + MaybeSerializeDeserialize + Debug + Ord + MaxEncodedLen;
 	type AssetPairs: AssetPairsTrait;
+	type RedeemLiquidityAmount: RedeemLiquidityAmountTrait;
+	type BlockNumber: Parameter + Member + MaybeSerializeDeserialize + Debug + Ord + Copy + MaxEncodedLen;
+	type PoolKind: FullCodec + Clone + Copy + Eq + PartialEq + Debug + scale_info::TypeInfo + MaxEncodedLen;
+	type LimitOrderId: Parameter + Member + MaybeSerializeDeserialize + Debug + Ord + MaxEncodedLen;
+	type DcaScheduleId: Parameter + Member + MaybeSerializeDeserialize + Debug + Ord + MaxEncodedLen;
 
 	fn new_liquidity(
 		who: Self::AccountId,
 		asset_pair: Self::AssetPairs,
 		asset_x_balance: Self::AssetBalance,
+		asset_y_balance: Self::AssetBalance,
+		min_lp_token: Self::AssetBalance
+	) -> Result<(), DispatchError>;
+
+	fn create_pool(
+		who: Self::AccountId,
+		asset_pair: Self::AssetPairs,
+		pool_kind: Self::PoolKind,
+		asset_x_balance: Self::AssetBalance,
+		asset_y_balance: Self::AssetBalance
+	) -> Result<(), DispatchError>;
+
+	fn add_range_liquidity(
+		who: Self::AccountId,
+		asset_pair: Self::AssetPairs,
+		lower_tick: i32,
+		upper_tick: i32,
+		asset_x_balance: Self::AssetBalance,
 		asset_y_balance: Self::AssetBalance
 	) -> Result<(), DispatchError>;
 
+	fn remove_range_liquidity(
+		who: Self::AccountId,
+		asset_pair: Self::AssetPairs,
+		id: Self::AccountLiquidityPoolId
+	) -> Result<(), DispatchError>;
+
 	fn redeem_liquidity(
 		who: Self::AccountId,
 		asset_pair:  Self::AssetPairs,
@@ -32,18 +62,58 @@ pub trait HumidefiCaller {
 		id: Self::AccountLiquidityPoolId
 	) -> Result<(), DispatchError>;
 
+	fn redeem_liquidity_partial(
+		who: Self::AccountId,
+		asset_pair:  Self::AssetPairs,
+		lp_token: Self::AssetId,
+		id: Self::AccountLiquidityPoolId,
+		redeem_amount: Self::RedeemLiquidityAmount
+	) -> Result<(), DispatchError>;
+
+	fn redeem_lp_token(
+		who: Self::AccountId,
+		asset_pair: Self::AssetPairs,
+		lp_token_balance: Self::AssetBalance
+	) -> Result<(), DispatchError>;
+
+	fn convert_merged_lp_token(
+		who: Self::AccountId,
+		legacy_lp_token: Self::AssetId,
+		lp_token_balance: Self::AssetBalance
+	) -> Result<(), DispatchError>;
+
 	fn swap_exact_in_for_out(
 		who: Self::AccountId,
-		asset_exact_in: Self::AssetId,
+		asset_exact_in: Self::AssetKind,
 		asset_exact_in_balance: Self::AssetBalance,
-		asset_max_out: Self::AssetId
+		asset_max_out: Self::AssetKind,
+		min_amount_out: Self::AssetBalance,
+		deadline: Option<Self::BlockNumber>
 	) -> Result<(), DispatchError>;
 
 	fn swap_in_for_exact_out(
 		who: Self::AccountId,
-		asset_exact_out: Self::AssetId,
+		asset_exact_out: Self::AssetKind,
+		asset_exact_out_balance: Self::AssetBalance,
+		asset_min_in: Self::AssetKind,
+		max_amount_in: Self::AssetBalance,
+		deadline: Option<Self::BlockNumber>
+	) -> Result<(), DispatchError>;
+
+	fn swap_exact_in_for_out_path(
+		who: Self::AccountId,
+		path: Vec<Self::AssetKind>,
+		asset_exact_in_balance: Self::AssetBalance,
+		min_amount_out: Self::AssetBalance,
+		deadline: Option<Self::BlockNumber>
+	) -> Result<(), DispatchError>;
+
+	fn swap_in_for_exact_out_path(
+		who: Self::AccountId,
+		path: Vec<Self::AssetKind>,
 		asset_exact_out_balance: Self::AssetBalance,
-		asset_min_in: Self::AssetId
+		max_amount_in: Self::AssetBalance,
+		deadline: Option<Self::BlockNumber>
 	) -> Result<(), DispatchError>;
 
 	fn transfer_asset(
@@ -52,55 +122,64 @@ pub trait HumidefiCaller {
 		asset_balance: Self::AssetBalance,
 		account_id: Self::AccountId
 	) -> Result<(), DispatchError>;
-}
-
-pub trait HumidefiHelpers {
-	type AccountId: Parameter + Member + MaybeSerializeDeserialize + Debug + Ord + MaxEncodedLen;
-	type AssetId: FullCodec + Clone + Eq + PartialEq + Debug + scale_info::TypeInfo + MaxEncodedLen;
-	type AssetBalance: FullCodec + Copy + Default + Debug + scale_info::TypeInfo + MaxEncodedLen;
-	type AccountLiquidityPoolId: Parameter + Member + MaybeSerializeDeserialize + Debug + Ord + MaxEncodedLen;
-	type AssetPairs: AssetPairsTrait;
-	type LiquidityPool: LiquidityPoolTrait;
-	type AccountLiquidityPool: AccountLiquidityPoolTrait;
-	type AssetBalanceResult;
 
-	fn get_dex_account() -> Self::AccountId;
+	fn place_limit_order(
+		who: Self::AccountId,
+		asset_in: Self::AssetKind,
+		amount_in: Self::AssetBalance,
+		asset_out: Self::AssetKind,
+		limit_price: FixedU128,
+		expiry: Self::BlockNumber
+	) -> Result<Self::LimitOrderId, DispatchError>;
+
+	fn cancel_limit_order(
+		who: Self::AccountId,
+		id: Self::LimitOrderId
+	) -> Result<(), DispatchError>;
 
-	fn get_asset_balance(
-		asset: Self::AssetId,
-		account_id: Self::AccountId
-	) -> Self::AssetBalance;
+	fn schedule_dca(
+		who: Self::AccountId,
+		asset_in: Self::AssetKind,
+		asset_out: Self::AssetKind,
+		amount_per_period: Self::AssetBalance,
+		max_slippage: Perbill,
+		period: Self::BlockNumber,
+		runs: u32
+	) -> Result<Self::DcaScheduleId, DispatchError>;
+
+	fn cancel_dca(
+		who: Self::AccountId,
+		id: Self::DcaScheduleId
+	) -> Result<(), DispatchError>;
+}
 
-	fn get_liquidity_pool(
-		asset_pair: Self::AssetPairs
-	) -> Option<Self::LiquidityPool>;
+pub trait PriceOracle {
+	type AssetKind: FullCodec + Clone + Copy + Eq + PartialEq + Debug + scale_info::TypeInfo + MaxEncodedLen;
+	type BlockNumber: Parameter + Member + MaybeSerializeDeserialize + Debug + Ord + Copy + MaxEncodedLen;
 
-	fn get_account_liquidity_pools(
-		account_id: Self::AccountId,
-		asset_pair: Self::AssetPairs
-	) -> Option<BoundedVec<Self::AccountLiquidityPool, ConstU32<100>>>;
+	fn spot_price(
+		asset_a: Self::AssetKind,
+		asset_b: Self::AssetKind
+	) -> Option<FixedU128>;
 
-	fn check_asset_balance(
-		account_id: Self::AccountId,
-		asset: Self::AssetId,
-		asset_balance: Self::AssetBalance
-	) -> Result<(), DispatchError>;
+	fn twap(
+		asset_a: Self::AssetKind,
+		asset_b: Self::AssetKind,
+		window: Self::BlockNumber
+	) -> Option<FixedU128>;
+}
 
-	fn compute_and_mint_lp_token(
-		asset_pair: Self::AssetPairs,
-		asset_x_balance: Self::AssetBalance,
-		asset_y_balance: Self::AssetBalance
-	) -> Result<(Self::AssetId, Self::AssetBalance), DispatchError>;
+pub trait HumidefiInspect: PriceOracle {
+	type AssetBalance: FullCodec + Copy + Default + Debug + scale_info::TypeInfo + MaxEncodedLen;
 
-	fn compute_price(
-		asset_x_balance: Self::AssetBalance,
-		asset_y_balance: Self::AssetBalance
-	) -> Result<FixedU128, DispatchError>;
+	fn reserves(
+		asset_a: Self::AssetKind,
+		asset_b: Self::AssetKind
+	) -> Option<(Self::AssetBalance, Self::AssetBalance)>;
 
-	fn compute_xy_assets(
-		account_id: Self::AccountId,
-		asset_pair: Self::AssetPairs,
-		lp_token: Self::AssetId,
-		id: Self::AccountLiquidityPoolId
-	) -> Self::AssetBalanceResult;
+	fn quote(
+		asset_in: Self::AssetKind,
+		asset_out: Self::AssetKind,
+		amount_in: Self::AssetBalance
+	) -> Option<Self::AssetBalance>;
 }
@@ -15,9 +15,9 @@ use sp_api::impl_runtime_apis;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, Verify, Zero},
+	traits::{AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, Verify, Zero},
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, MultiSignature,
+	ApplyExtrinsicResult, FixedU128, MultiSignature,
 };
 
 use sp_std::prelude::*;
@@ -75,6 +75,9 @@ pub type Balance = u128;
 /// Index of a transaction in the chain.
 pub type Nonce = u32;
 
+/// Asset kind accepted by the humidefi pallet, either the native token or an asset id.
+pub type HumidefiAssetId = pallet_humidefi::NativeOrAssetId<u32>;
+
 /// A hash of some data used by the chain.
 pub type Hash = sp_core::H256;
 
@@ -122,8 +125,17 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
+/// Pending storage migrations, run on the next runtime upgrade.
+pub type Migrations = (
+	pallet_humidefi::migrations::v1::MigrateToNativeOrAssetIds<Runtime>,
+	pallet_humidefi::migrations::v2::MigrateToPoolAccounts<Runtime>,
+	pallet_humidefi::migrations::v3::AddPoolStatus<Runtime>,
+	pallet_humidefi::migrations::v4::AddPositionRanges<Runtime>,
+);
+
 /// Handles converting a weight scalar to a fee value, based on the scale and granularity of the
 /// node's balance type.
 ///
@@ -463,10 +475,50 @@ impl pallet_collator_selection::Config for Runtime {
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	// 0.3% of the swap input stays in the pool for liquidity providers.
+	pub const HumidefiDefaultSwapFee: Perbill = Perbill::from_parts(3_000_000);
+	pub const HumidefiMaxPriceMovePerBlock: Perbill = Perbill::from_percent(25);
+	pub HumidefiPoolCreator: AccountId = TreasuryPalletId::get().into_account_truncating();
+	// Together with the 144 observations kept per pool, TWAPs reach back up to a day.
+	pub const HumidefiPriceObservationPeriod: BlockNumber = 10 * MINUTES;
+}
+
 impl pallet_humidefi::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type NativeBalance = Balances;
 	type Fungibles = Assets;
+	type DefaultSwapFee = HumidefiDefaultSwapFee;
+	type MaxSwapPathLength = ConstU32<4>;
+	// Pools are seeded from the treasury, which also receives the first LP tokens, so opening one
+	// takes the same council majority as approving a treasury spend.
+	type PoolCreateOrigin = EnsureWithSuccess<
+		EitherOfDiverse<
+			EnsureRoot<AccountId>,
+			pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 3, 5>,
+		>,
+		AccountId,
+		HumidefiPoolCreator,
+	>;
+	// The listing starts out as a deny-list, so new pools may use any asset the council has not
+	// denied. Switching to `AllowList` blocks new pools for every asset that was not `Allowed`
+	// beforehand. Existing pools keep trading either way.
+	type AdminOrigin = EitherOfDiverse<
+		EnsureRoot<AccountId>,
+		pallet_collective::EnsureProportionMoreThan<AccountId, CouncilCollective, 1, 2>,
+	>;
+	type PauseOrigin = EitherOfDiverse<
+		EnsureRoot<AccountId>,
+		pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 1, 2>,
+	>;
+	type MaxPriceMovePerBlock = HumidefiMaxPriceMovePerBlock;
+	type PriceObservationPeriod = HumidefiPriceObservationPeriod;
+	type MaxPriceObservations = ConstU32<144>;
+	type MaxTicksPerPool = ConstU32<100>;
+	type MaxLimitOrdersPerAccount = ConstU32<32>;
+	type MaxDcaSchedulesPerAccount = ConstU32<32>;
+	type MaxDcaRunsPerBlock = ConstU32<20>;
+	type WeightInfo = pallet_humidefi::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -866,6 +918,7 @@ mod benches {
 		[pallet_timestamp, Timestamp]
 		[pallet_collator_selection, CollatorSelection]
 		[cumulus_pallet_xcmp_queue, XcmpQueue]
+		[pallet_humidefi, HumidefiModule]
 	);
 }
 
@@ -1007,6 +1060,37 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_humidefi_rpc_runtime_api::HumidefiApi<Block, AccountId, HumidefiAssetId, u32, Balance> for Runtime {
+		fn quote_exact_in(path: Vec<HumidefiAssetId>, amount_in: Balance) -> Option<Balance> {
+			HumidefiModule::quote_exact_in(path, amount_in)
+		}
+		fn quote_exact_out(path: Vec<HumidefiAssetId>, amount_out: Balance) -> Option<Balance> {
+			HumidefiModule::quote_exact_out(path, amount_out)
+		}
+		fn quote_add_liquidity(
+			asset_x: HumidefiAssetId,
+			asset_y: HumidefiAssetId,
+			amount_x: Balance,
+			amount_y: Balance,
+		) -> Option<Balance> {
+			HumidefiModule::quote_add_liquidity(asset_x, asset_y, amount_x, amount_y)
+		}
+		fn list_pools(
+			offset: u32,
+			limit: u32,
+		) -> Vec<pallet_humidefi_rpc_runtime_api::PoolInfo<HumidefiAssetId, u32, Balance>> {
+			HumidefiModule::list_pools(offset, limit)
+		}
+		fn account_positions(
+			account: AccountId,
+		) -> Vec<pallet_humidefi_rpc_runtime_api::PositionInfo<HumidefiAssetId, u32, Balance>> {
+			HumidefiModule::account_positions(account)
+		}
+		fn twap(asset_a: HumidefiAssetId, asset_b: HumidefiAssetId, window: BlockNumber) -> Option<FixedU128> {
+			HumidefiModule::twap(asset_a, asset_b, window)
+		}
+	}
+
 	impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
 		fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
 			ParachainSystem::collect_collation_info(header)